@@ -15,6 +15,19 @@ pub enum Request {
     DelegateBatch(DelegateBatch),
 }
 
+/// Requests about the device itself, like `{"request": "master_fingerprint"}`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "request", rename_all = "snake_case")]
+pub enum DeviceRequest {
+    MasterFingerprint,
+}
+
+/// Request of the extended public key at a derivation path.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExtendedPublicKey {
+    pub derivation_path: String,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct SecureBatch {
     pub deposits: Vec<UTXO>,
@@ -1,16 +1,23 @@
 use std::net::SocketAddr;
 
-use iced::{executor, Application, Clipboard, Command, Element, Settings};
-use revault_tx::bitcoin::util::bip32::ExtendedPrivKey;
+use iced::{button, executor, Application, Clipboard, Command, Element, Settings};
+use revault_tx::bitcoin::{
+    secp256k1,
+    util::{
+        bip32::{DerivationPath, ExtendedPrivKey},
+        psbt::PartiallySignedTransaction,
+    },
+};
 use serde_json::json;
 
+use std::str::FromStr;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use crate::{
-    api,
+    api, audit,
     config::{self, Config},
-    server, sign, view,
+    server, sign, specter, view,
 };
 
 pub fn run(cfg: Config) -> iced::Result {
@@ -22,6 +29,12 @@ pub struct App {
     keys: Vec<config::Key>,
     signer: sign::Signer,
     status: AppStatus,
+    specter_address: Option<String>,
+    audit_log: Option<audit::AuditLog>,
+    /// Some if the user is browsing the past signing decisions.
+    history: Option<Vec<audit::Entry>>,
+    history_view: view::HistoryView,
+    history_button: button::State,
 }
 
 pub enum AppStatus {
@@ -31,11 +44,18 @@ pub enum AppStatus {
         writer: Arc<Mutex<server::Writer>>,
         method: Option<Method>,
     },
+    /// Connected to a client speaking the Specter serial protocol.
+    SpecterConnected {
+        addr: SocketAddr,
+        writer: Arc<Mutex<specter::Writer>>,
+        method: Option<Method>,
+    },
 }
 
 #[derive(Debug)]
 pub enum Message {
     Server(server::ServerMessage),
+    Specter(specter::SpecterMessage),
     View(view::ViewMessage),
 }
 
@@ -57,6 +77,11 @@ impl Application for App {
                 ),
                 keys: cfg.keys,
                 status: AppStatus::Waiting,
+                specter_address: cfg.specter_address,
+                audit_log: cfg.log_path.map(audit::AuditLog::new),
+                history: None,
+                history_view: view::HistoryView::new(),
+                history_button: button::State::new(),
             },
             Command::none(),
         )
@@ -78,6 +103,39 @@ impl Application for App {
             }
             Message::Server(server::ServerMessage::Request(msg)) => {
                 if let AppStatus::Connected { method, writer, .. } = &mut self.status {
+                    if let Ok(api::DeviceRequest::MasterFingerprint) =
+                        serde_json::from_value::<api::DeviceRequest>(msg.clone())
+                    {
+                        let response = match self.keys.first() {
+                            Some(key) => {
+                                json!({ "master_fingerprint": key.master_fingerprint().to_string() })
+                            }
+                            None => json!({"error": "no key"}),
+                        };
+                        return Command::perform(
+                            server::respond(writer.clone(), response),
+                            server::ServerMessage::Responded,
+                        )
+                        .map(Message::Server);
+                    }
+                    if let Ok(req) = serde_json::from_value::<api::ExtendedPublicKey>(msg.clone()) {
+                        let response = match (
+                            DerivationPath::from_str(&req.derivation_path),
+                            self.keys.first(),
+                        ) {
+                            (Err(_), _) => json!({"error": "invalid derivation path"}),
+                            (_, None) => json!({"error": "no key"}),
+                            (Ok(path), Some(key)) => match key.derive_xpub(&path) {
+                                Ok(xpub) => json!({ "xpub": xpub.to_string() }),
+                                Err(e) => json!({ "error": e }),
+                            },
+                        };
+                        return Command::perform(
+                            server::respond(writer.clone(), response),
+                            server::ServerMessage::Responded,
+                        )
+                        .map(Message::Server);
+                    }
                     match serde_json::from_value(msg) {
                         Ok(req) => {
                             if (matches!(req, api::Request::SecureBatch { .. })
@@ -115,149 +173,170 @@ impl Application for App {
                 self.status = AppStatus::Waiting {};
                 Command::none()
             }
+            Message::Specter(specter::SpecterMessage::NewConnection(addr, writer)) => {
+                self.status = AppStatus::SpecterConnected {
+                    addr,
+                    writer: Arc::new(Mutex::new(writer)),
+                    method: None,
+                };
+                Command::none()
+            }
+            Message::Specter(specter::SpecterMessage::Request(line)) => {
+                if let AppStatus::SpecterConnected { method, writer, .. } = &mut self.status {
+                    match specter::Request::from_str(&line) {
+                        Ok(specter::Request::Fingerprint) => {
+                            let response = match self.keys.first() {
+                                Some(key) => key.master_fingerprint().to_string(),
+                                None => specter::error_response("no key"),
+                            };
+                            return Command::perform(
+                                specter::acknowledge_and_respond(writer.clone(), response),
+                                specter::SpecterMessage::Responded,
+                            )
+                            .map(Message::Specter);
+                        }
+                        Ok(specter::Request::Xpub(path)) => {
+                            let response = match self.keys.first().map(|key| key.derive_xpub(&path))
+                            {
+                                Some(Ok(xpub)) => xpub.to_string(),
+                                Some(Err(e)) => specter::error_response(e),
+                                None => specter::error_response("no key"),
+                            };
+                            return Command::perform(
+                                specter::acknowledge_and_respond(writer.clone(), response),
+                                specter::SpecterMessage::Responded,
+                            )
+                            .map(Message::Specter);
+                        }
+                        Ok(specter::Request::Sign(psbt)) => {
+                            *method = Some(Method::new_sign_psbt(&self.keys, &self.signer, psbt));
+                            return Command::perform(
+                                specter::acknowledge(writer.clone()),
+                                specter::SpecterMessage::Responded,
+                            )
+                            .map(Message::Specter);
+                        }
+                        Err(e) => {
+                            return Command::perform(
+                                specter::acknowledge_and_respond(
+                                    writer.clone(),
+                                    specter::error_response(e),
+                                ),
+                                specter::SpecterMessage::Responded,
+                            )
+                            .map(Message::Specter);
+                        }
+                    }
+                }
+                Command::none()
+            }
+            Message::Specter(specter::SpecterMessage::ConnectionDropped) => {
+                self.status = AppStatus::Waiting {};
+                Command::none()
+            }
             Message::View(view::ViewMessage::Key(i, view::KeyMessage::Selected(selected))) => {
-                if let AppStatus::Connected { method, .. } = &mut self.status {
+                if let AppStatus::Connected { method, .. }
+                | AppStatus::SpecterConnected { method, .. } = &mut self.status
+                {
                     match method {
                         Some(Method::SignSpendTx { keys, .. }) => keys[i].selected = selected,
                         Some(Method::SignUnvaultTx { keys, .. }) => keys[i].selected = selected,
                         Some(Method::SignRevocationTxs { keys, .. }) => keys[i].selected = selected,
                         Some(Method::SecureBatch { keys, .. }) => keys[i].selected = selected,
                         Some(Method::DelegateBatch { keys, .. }) => keys[i].selected = selected,
+                        Some(Method::SignPsbt { keys, .. }) => keys[i].selected = selected,
                         _ => {}
                     }
                 }
                 Command::none()
             }
+            Message::View(view::ViewMessage::ShowHistory) => {
+                if let Some(audit_log) = &self.audit_log {
+                    match audit_log.entries() {
+                        Ok(entries) => self.history = Some(entries),
+                        Err(e) => eprintln!("{}", e),
+                    }
+                }
+                Command::none()
+            }
+            Message::View(view::ViewMessage::CloseHistory) => {
+                self.history = None;
+                Command::none()
+            }
             Message::View(view::ViewMessage::Confirm) => {
-                if let AppStatus::Connected { method, writer, .. } = &mut self.status {
-                    match method {
-                        Some(Method::SignUnvaultTx {
-                            target,
-                            signed,
-                            keys,
-                            ..
-                        }) => {
-                            let selected_keys = keys
-                                .iter()
-                                .filter_map(|k| if k.selected { Some(k.xpriv) } else { None })
-                                .collect();
-                            self.signer
-                                .sign_psbt(&selected_keys, &mut target.unvault_tx)
-                                .unwrap();
-                            *signed = true;
-                            return Command::perform(
-                                server::respond(writer.clone(), json!(target)),
-                                server::ServerMessage::Responded,
-                            )
-                            .map(Message::Server);
-                        }
-                        Some(Method::SignSpendTx {
-                            target,
-                            signed,
-                            keys,
-                            ..
-                        }) => {
-                            let selected_keys = keys
-                                .iter()
-                                .filter_map(|k| if k.selected { Some(k.xpriv) } else { None })
-                                .collect();
-                            self.signer
-                                .sign_psbt(&selected_keys, &mut target.spend_tx)
-                                .unwrap();
-                            *signed = true;
-                            return Command::perform(
-                                server::respond(writer.clone(), json!(target)),
-                                server::ServerMessage::Responded,
-                            )
-                            .map(Message::Server);
-                        }
-                        Some(Method::SignRevocationTxs {
-                            target,
-                            signed,
-                            keys,
-                            ..
-                        }) => {
-                            let selected_keys = keys
-                                .iter()
-                                .filter_map(|k| if k.selected { Some(k.xpriv) } else { None })
-                                .collect();
-                            self.signer
-                                .sign_psbt(&selected_keys, &mut target.emergency_tx)
-                                .unwrap();
-                            self.signer
-                                .sign_psbt(&selected_keys, &mut target.emergency_unvault_tx)
-                                .unwrap();
-                            for cancel_tx in &mut target.cancel_txs {
-                                self.signer.sign_psbt(&selected_keys, cancel_tx).unwrap();
+                let result = match &mut self.status {
+                    AppStatus::Connected {
+                        method: Some(method),
+                        ..
+                    }
+                    | AppStatus::SpecterConnected {
+                        method: Some(method),
+                        ..
+                    } => method.sign(&self.signer),
+                    _ => return Command::none(),
+                };
+                // The decision is recorded once the signer returned.
+                self.record(if result.is_ok() {
+                    audit::Decision::Confirmed
+                } else {
+                    audit::Decision::Failed
+                });
+                if let AppStatus::SpecterConnected { method, writer, .. } = &mut self.status {
+                    let response = match result {
+                        Ok(()) => match method {
+                            Some(Method::SignPsbt { target, .. }) => {
+                                specter::encode_psbt(&specter::strip_psbt(target))
                             }
-                            *signed = true;
-                            return Command::perform(
-                                server::respond(writer.clone(), json!(target)),
-                                server::ServerMessage::Responded,
-                            )
-                            .map(Message::Server);
+                            _ => return Command::none(),
+                        },
+                        Err(e) => {
+                            *method = None;
+                            specter::error_response(e)
                         }
-                        Some(Method::SecureBatch {
-                            target,
-                            signed,
-                            keys,
-                            ..
-                        }) => {
-                            let selected_keys = keys
-                                .iter()
-                                .filter_map(|k| if k.selected { Some(k.xpriv) } else { None })
-                                .collect();
-                            for revocation_txs in target.iter_mut() {
-                                self.signer
-                                    .sign_psbt(&selected_keys, &mut revocation_txs.emergency_tx)
-                                    .unwrap();
-
-                                for cancel_tx in &mut revocation_txs.cancel_txs {
-                                    self.signer.sign_psbt(&selected_keys, cancel_tx).unwrap();
-                                }
-
-                                self.signer
-                                    .sign_psbt(
-                                        &selected_keys,
-                                        &mut revocation_txs.emergency_unvault_tx,
-                                    )
-                                    .unwrap();
+                    };
+                    return Command::perform(
+                        specter::respond(writer.clone(), response),
+                        specter::SpecterMessage::Responded,
+                    )
+                    .map(Message::Specter);
+                }
+                if let AppStatus::Connected { method, writer, .. } = &mut self.status {
+                    let response = match result {
+                        Ok(()) => match method {
+                            Some(Method::SignUnvaultTx { target, .. }) => json!(target),
+                            Some(Method::SignSpendTx { target, .. }) => json!(target),
+                            Some(Method::SignRevocationTxs { target, .. }) => json!(target),
+                            Some(Method::SecureBatch { target, .. }) => {
+                                json!({ "transactions": target })
                             }
-                            *signed = true;
-                            return Command::perform(
-                                server::respond(writer.clone(), json!({ "transactions": target })),
-                                server::ServerMessage::Responded,
-                            )
-                            .map(Message::Server);
-                        }
-                        Some(Method::DelegateBatch {
-                            target,
-                            signed,
-                            keys,
-                            ..
-                        }) => {
-                            let selected_keys = keys
-                                .iter()
-                                .filter_map(|k| if k.selected { Some(k.xpriv) } else { None })
-                                .collect();
-                            for tx in target.iter_mut() {
-                                self.signer
-                                    .sign_psbt(&selected_keys, &mut tx.unvault_tx)
-                                    .unwrap();
+                            Some(Method::DelegateBatch { target, .. }) => {
+                                json!({ "transactions": target })
                             }
-                            *signed = true;
-                            return Command::perform(
-                                server::respond(writer.clone(), json!({ "transactions": target })),
-                                server::ServerMessage::Responded,
-                            )
-                            .map(Message::Server);
+                            _ => return Command::none(),
+                        },
+                        Err(e) => {
+                            *method = None;
+                            json!({ "error": e.to_string() })
                         }
-                        _ => {}
-                    }
+                    };
+                    return Command::perform(
+                        server::respond(writer.clone(), response),
+                        server::ServerMessage::Responded,
+                    )
+                    .map(Message::Server);
                 }
                 Command::none()
             }
             Message::View(view::ViewMessage::Cancel) => {
+                self.record(audit::Decision::Cancelled);
+                if let AppStatus::SpecterConnected { method, writer, .. } = &mut self.status {
+                    *method = None;
+                    return Command::perform(
+                        specter::respond(writer.clone(), specter::error_response("User cancelled")),
+                        specter::SpecterMessage::Responded,
+                    )
+                    .map(Message::Specter);
+                }
                 if let AppStatus::Connected { method, writer, .. } = &mut self.status {
                     *method = None;
                     return Command::perform(
@@ -273,42 +352,108 @@ impl Application for App {
     }
 
     fn subscription(&self) -> iced::Subscription<Message> {
-        iced::Subscription::batch(vec![server::listen("0.0.0.0:8080").map(Message::Server)])
+        let mut subscriptions = vec![server::listen("0.0.0.0:8080").map(Message::Server)];
+        if let Some(address) = &self.specter_address {
+            subscriptions.push(specter::listen(address).map(Message::Specter));
+        }
+        iced::Subscription::batch(subscriptions)
     }
 
     fn view(&mut self) -> Element<Message> {
-        match &mut self.status {
-            AppStatus::Waiting => view::waiting_connection().map(Message::View),
-            AppStatus::Connected { addr, method, .. } => match method {
-                Some(m) => m.render().map(Message::View),
-                None => view::connected(addr).map(Message::View),
-            },
+        let history_button = if self.audit_log.is_some() {
+            Some(&mut self.history_button)
+        } else {
+            None
+        };
+        match (&mut self.status, &self.history) {
+            (
+                AppStatus::Connected {
+                    method: Some(m), ..
+                },
+                _,
+            )
+            | (
+                AppStatus::SpecterConnected {
+                    method: Some(m), ..
+                },
+                _,
+            ) => m.render().map(Message::View),
+            (_, Some(entries)) => self.history_view.render(entries).map(Message::View),
+            (AppStatus::Waiting, None) => {
+                view::waiting_connection(history_button).map(Message::View)
+            }
+            (AppStatus::Connected { addr, .. }, None)
+            | (AppStatus::SpecterConnected { addr, .. }, None) => {
+                view::connected(addr, history_button).map(Message::View)
+            }
         }
     }
 }
 
+impl App {
+    /// Append the user decision on the current request to the audit log.
+    fn record(&self, decision: audit::Decision) {
+        let method = match &self.status {
+            AppStatus::Connected {
+                method: Some(method),
+                ..
+            }
+            | AppStatus::SpecterConnected {
+                method: Some(method),
+                ..
+            } => method,
+            _ => return,
+        };
+        if let Some(audit_log) = &self.audit_log {
+            if let Err(e) = audit_log.append(&method.audit_entry(decision)) {
+                eprintln!("{}", e);
+            }
+        }
+    }
+}
+
+fn selected_keys(keys: &[Key]) -> Vec<ExtendedPrivKey> {
+    keys.iter()
+        .filter(|k| k.selected)
+        .flat_map(|k| k.signing_keys.iter().copied())
+        .collect()
+}
+
 pub struct Key {
     name: String,
     xpriv: ExtendedPrivKey,
+    signing_keys: Vec<ExtendedPrivKey>,
     selected: bool,
 }
 
 impl Key {
-    pub fn new(name: String, xpriv: ExtendedPrivKey) -> Self {
+    pub fn new(key: &config::Key) -> Self {
         Key {
-            name,
-            xpriv,
+            name: key.name.clone(),
+            xpriv: key.xpriv,
+            signing_keys: key.signing_keys(),
             selected: false,
         }
     }
 
     pub fn render(&self) -> Element<view::KeyMessage> {
-        if self.name != "" {
+        if !self.name.is_empty() {
             view::key_view(&self.name, self.selected)
         } else {
             view::key_view(&self.xpriv.to_string(), self.selected)
         }
     }
+
+    /// Name of the key for the audit log, the fingerprint is used if the key has no name.
+    pub fn label(&self) -> String {
+        if !self.name.is_empty() {
+            self.name.clone()
+        } else {
+            self.xpriv
+                .fingerprint(&secp256k1::Secp256k1::signing_only())
+                .to_string()
+        }
+    }
 }
 
 pub enum Method {
@@ -344,9 +489,172 @@ pub enum Method {
         signed: bool,
         view: view::DelegateBatchView,
     },
+    SignPsbt {
+        keys: Vec<Key>,
+        target: PartiallySignedTransaction,
+        signed: bool,
+        view: view::SignPsbtView,
+    },
 }
 
 impl Method {
+    /// Signs the transactions of the request with the selected keys.
+    fn sign(&mut self, signer: &sign::Signer) -> Result<(), sign::Error> {
+        match self {
+            Self::SignUnvaultTx {
+                target,
+                signed,
+                keys,
+                ..
+            } => {
+                signer.sign_psbt(&selected_keys(keys), &mut target.unvault_tx)?;
+                *signed = true;
+            }
+            Self::SignSpendTx {
+                target,
+                signed,
+                keys,
+                ..
+            } => {
+                signer.sign_psbt(&selected_keys(keys), &mut target.spend_tx)?;
+                *signed = true;
+            }
+            Self::SignRevocationTxs {
+                target,
+                signed,
+                keys,
+                ..
+            } => {
+                let selected_keys = selected_keys(keys);
+                signer.sign_psbt(&selected_keys, &mut target.emergency_tx)?;
+                signer.sign_psbt(&selected_keys, &mut target.emergency_unvault_tx)?;
+                for cancel_tx in &mut target.cancel_txs {
+                    signer.sign_psbt(&selected_keys, cancel_tx)?;
+                }
+                *signed = true;
+            }
+            Self::SecureBatch {
+                target,
+                signed,
+                keys,
+                ..
+            } => {
+                let selected_keys = selected_keys(keys);
+                for revocation_txs in target.iter_mut() {
+                    signer.sign_psbt(&selected_keys, &mut revocation_txs.emergency_tx)?;
+                    for cancel_tx in &mut revocation_txs.cancel_txs {
+                        signer.sign_psbt(&selected_keys, cancel_tx)?;
+                    }
+                    signer.sign_psbt(&selected_keys, &mut revocation_txs.emergency_unvault_tx)?;
+                }
+                *signed = true;
+            }
+            Self::DelegateBatch {
+                target,
+                signed,
+                keys,
+                ..
+            } => {
+                let selected_keys = selected_keys(keys);
+                for tx in target.iter_mut() {
+                    signer.sign_psbt(&selected_keys, &mut tx.unvault_tx)?;
+                }
+                *signed = true;
+            }
+            Self::SignPsbt {
+                target,
+                signed,
+                keys,
+                ..
+            } => {
+                signer.sign_psbt(&selected_keys(keys), target)?;
+                *signed = true;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn audit_entry(&self, decision: audit::Decision) -> audit::Entry {
+        let (name, keys, psbts): (&str, &Vec<Key>, Vec<&PartiallySignedTransaction>) = match self {
+            Self::SignSpendTx { keys, target, .. } => ("SignSpendTx", keys, vec![&target.spend_tx]),
+            Self::SignUnvaultTx { keys, target, .. } => {
+                ("SignUnvaultTx", keys, vec![&target.unvault_tx])
+            }
+            Self::SignRevocationTxs { keys, target, .. } => {
+                let mut psbts = vec![&target.emergency_tx, &target.emergency_unvault_tx];
+                psbts.extend(target.cancel_txs.iter());
+                ("SignRevocationTxs", keys, psbts)
+            }
+            Self::SecureBatch { keys, target, .. } => {
+                let mut psbts = Vec::new();
+                for txs in target {
+                    psbts.push(&txs.emergency_tx);
+                    psbts.push(&txs.emergency_unvault_tx);
+                    psbts.extend(txs.cancel_txs.iter());
+                }
+                ("SecureBatch", keys, psbts)
+            }
+            Self::DelegateBatch { keys, target, .. } => (
+                "DelegateBatch",
+                keys,
+                target.iter().map(|tx| &tx.unvault_tx).collect(),
+            ),
+            Self::SignPsbt { keys, target, .. } => ("SignPsbt", keys, vec![target]),
+        };
+
+        let keys = if decision != audit::Decision::Cancelled {
+            keys.iter()
+                .filter(|key| key.selected)
+                .map(|key| key.label())
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        audit::Entry::new(
+            name,
+            decision,
+            psbts
+                .into_iter()
+                .map(audit::Transaction::from_psbt)
+                .collect(),
+            keys,
+        )
+    }
+
+    pub fn new_sign_psbt(
+        config_keys: &[config::Key],
+        signer: &sign::Signer,
+        target: PartiallySignedTransaction,
+    ) -> Method {
+        let mut keys: Vec<Key> = config_keys
+            .iter()
+            .filter_map(|key| {
+                if key
+                    .signing_keys()
+                    .iter()
+                    .any(|k| signer.requires_key_for_psbt(k, &target))
+                {
+                    Some(Key::new(key))
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        // if there is only one key, then it is automatically selected
+        if keys.len() == 1 {
+            keys[0].selected = true;
+        }
+
+        Method::SignPsbt {
+            keys,
+            target,
+            signed: false,
+            view: view::SignPsbtView::new(),
+        }
+    }
+
     pub fn new(
         config_keys: &Vec<config::Key>,
         signer: &sign::Signer,
@@ -357,8 +665,12 @@ impl Method {
                 let mut keys: Vec<Key> = config_keys
                     .iter()
                     .filter_map(|key| {
-                        if signer.requires_key_for_psbt(&key.xpriv, &target.spend_tx) {
-                            Some(Key::new(key.name.clone(), key.xpriv))
+                        if key
+                            .signing_keys()
+                            .iter()
+                            .any(|k| signer.requires_key_for_psbt(k, &target.spend_tx))
+                        {
+                            Some(Key::new(key))
                         } else {
                             None
                         }
@@ -380,8 +692,12 @@ impl Method {
                 let mut keys: Vec<Key> = config_keys
                     .iter()
                     .filter_map(|key| {
-                        if signer.requires_key_for_psbt(&key.xpriv, &target.unvault_tx) {
-                            Some(Key::new(key.name.clone(), key.xpriv))
+                        if key
+                            .signing_keys()
+                            .iter()
+                            .any(|k| signer.requires_key_for_psbt(k, &target.unvault_tx))
+                        {
+                            Some(Key::new(key))
                         } else {
                             None
                         }
@@ -404,8 +720,12 @@ impl Method {
                 let mut keys: Vec<Key> = config_keys
                     .iter()
                     .filter_map(|key| {
-                        if signer.requires_key_for_psbt(&key.xpriv, &target.emergency_tx) {
-                            Some(Key::new(key.name.clone(), key.xpriv))
+                        if key
+                            .signing_keys()
+                            .iter()
+                            .any(|k| signer.requires_key_for_psbt(k, &target.emergency_tx))
+                        {
+                            Some(Key::new(key))
                         } else {
                             None
                         }
@@ -453,8 +773,12 @@ impl Method {
                 let mut keys: Vec<Key> = config_keys
                     .iter()
                     .filter_map(|key| {
-                        if signer.requires_key_for_psbt(&key.xpriv, &target[0].emergency_tx) {
-                            Some(Key::new(key.name.clone(), key.xpriv))
+                        if key
+                            .signing_keys()
+                            .iter()
+                            .any(|k| signer.requires_key_for_psbt(k, &target[0].emergency_tx))
+                        {
+                            Some(Key::new(key))
                         } else {
                             None
                         }
@@ -499,8 +823,12 @@ impl Method {
                 let mut keys: Vec<Key> = config_keys
                     .iter()
                     .filter_map(|key| {
-                        if signer.requires_key_for_psbt(&key.xpriv, &target[0].unvault_tx) {
-                            Some(Key::new(key.name.clone(), key.xpriv))
+                        if key
+                            .signing_keys()
+                            .iter()
+                            .any(|k| signer.requires_key_for_psbt(k, &target[0].unvault_tx))
+                        {
+                            Some(Key::new(key))
                         } else {
                             None
                         }
@@ -599,6 +927,20 @@ impl Method {
                     .collect(),
                 keys.iter().any(|key| key.selected),
             ),
+            Self::SignPsbt {
+                view,
+                target,
+                signed,
+                keys,
+            } => view.render(
+                &target,
+                *signed,
+                keys.iter()
+                    .enumerate()
+                    .map(|(i, key)| key.render().map(move |msg| view::ViewMessage::Key(i, msg)))
+                    .collect(),
+                keys.iter().any(|key| key.selected),
+            ),
         }
     }
 }
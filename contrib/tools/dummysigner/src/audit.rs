@@ -0,0 +1,168 @@
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use revault_tx::bitcoin::{util::psbt::PartiallySignedTransaction, Txid};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Decision {
+    Confirmed,
+    Cancelled,
+    /// The request was confirmed but the signer could not sign it.
+    Failed,
+}
+
+impl std::fmt::Display for Decision {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Confirmed => write!(f, "Confirmed"),
+            Self::Cancelled => write!(f, "Cancelled"),
+            Self::Failed => write!(f, "Failed"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transaction {
+    pub txid: Txid,
+    /// Sum of the outputs value in satoshis.
+    pub amount: u64,
+}
+
+impl Transaction {
+    pub fn from_psbt(psbt: &PartiallySignedTransaction) -> Self {
+        Self {
+            txid: psbt.global.unsigned_tx.txid(),
+            amount: psbt
+                .global
+                .unsigned_tx
+                .output
+                .iter()
+                .map(|output| output.value)
+                .sum(),
+        }
+    }
+}
+
+/// A signing decision taken by the user.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    /// Seconds since the UNIX epoch.
+    pub timestamp: u64,
+    pub method: String,
+    pub decision: Decision,
+    pub transactions: Vec<Transaction>,
+    /// Keys selected to sign, empty if the request was cancelled.
+    pub keys: Vec<String>,
+}
+
+impl Entry {
+    pub fn new(
+        method: &str,
+        decision: Decision,
+        transactions: Vec<Transaction>,
+        keys: Vec<String>,
+    ) -> Self {
+        Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            method: method.to_string(),
+            decision,
+            transactions,
+            keys,
+        }
+    }
+}
+
+/// Append-only log of the signing decisions, one json entry per line.
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn append(&self, entry: &Entry) -> Result<(), Error> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| Error(format!("Opening audit log: {}", e)))?;
+        let mut line =
+            serde_json::to_string(entry).map_err(|e| Error(format!("Encoding entry: {}", e)))?;
+        line.push('\n');
+        file.write_all(line.as_bytes())
+            .map_err(|e| Error(format!("Writing to audit log: {}", e)))
+    }
+
+    pub fn entries(&self) -> Result<Vec<Entry>, Error> {
+        let file = match std::fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(Error(format!("Opening audit log: {}", e))),
+        };
+        let mut entries = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|e| Error(format!("Reading audit log: {}", e)))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            entries.push(
+                serde_json::from_str(&line)
+                    .map_err(|e| Error(format!("Parsing audit log entry: {}", e)))?,
+            );
+        }
+        Ok(entries)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Error(String);
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn append_and_read_entries() {
+        let mut path = std::env::temp_dir();
+        path.push(format!("dummysigner_audit_{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let log = AuditLog::new(path.clone());
+
+        assert!(log.entries().unwrap().is_empty());
+
+        let confirmed = Entry::new(
+            "SignSpendTx",
+            Decision::Confirmed,
+            vec![Transaction {
+                txid: Txid::from_str(
+                    "899aecbc9a3b06feaf096fc576b35da352a1ca1aa0c34db23ccfa944f30fae47",
+                )
+                .unwrap(),
+                amount: 120000000,
+            }],
+            vec!["man1".to_string()],
+        );
+        let cancelled = Entry::new("DelegateBatch", Decision::Cancelled, Vec::new(), Vec::new());
+        log.append(&confirmed).unwrap();
+        log.append(&cancelled).unwrap();
+
+        assert_eq!(log.entries().unwrap(), vec![confirmed, cancelled]);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,25 +1,137 @@
-use std::path::Path;
+use std::convert::TryFrom;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use serde::{de, Deserialize, Deserializer};
 
 use revault_tx::{
-    bitcoin::util::bip32::ExtendedPrivKey,
+    bitcoin::{
+        secp256k1,
+        util::bip32::{DerivationPath, ExtendedPrivKey, ExtendedPubKey, Fingerprint},
+        Network,
+    },
     scripts::{CpfpDescriptor, DepositDescriptor, EmergencyAddress, UnvaultDescriptor},
 };
 
+use crate::keys;
+
 #[derive(Debug, Deserialize)]
 pub struct Config {
     pub keys: Vec<Key>,
     pub descriptors: Option<Descriptors>,
     pub emergency_address: Option<EmergencyAddress>,
+    /// Address to serve the Specter serial protocol on, if any.
+    pub specter_address: Option<String>,
+    /// Path of the audit log of the signing decisions, if any.
+    pub log_path: Option<PathBuf>,
 }
 
+/// A key is either given as a xpriv or as a mnemonic with
+/// an optional derivation path and network.
 #[derive(Debug, Deserialize)]
+#[serde(try_from = "RawKey")]
 pub struct Key {
     pub name: String,
-    #[serde(deserialize_with = "deserialize_fromstr")]
     pub xpriv: ExtendedPrivKey,
+    /// Master key the xpriv was derived from, known if the key was given
+    /// by its mnemonic or if the xpriv is itself a master key.
+    pub master: Option<ExtendedPrivKey>,
+}
+
+impl Key {
+    pub fn from_xpriv(name: String, xpriv: ExtendedPrivKey) -> Self {
+        let master = if xpriv.depth == 0 { Some(xpriv) } else { None };
+        Self {
+            name,
+            xpriv,
+            master,
+        }
+    }
+
+    pub fn from_keyset(name: String, keys: keys::KeySet) -> Self {
+        Self {
+            name,
+            xpriv: keys.xpriv,
+            master: Some(keys.master),
+        }
+    }
+
+    /// Fingerprint of the master key, or of the xpriv if its master key is unknown.
+    pub fn master_fingerprint(&self) -> Fingerprint {
+        self.master
+            .unwrap_or(self.xpriv)
+            .fingerprint(&secp256k1::Secp256k1::signing_only())
+    }
+
+    /// Derives the xpub at the given path of the master key.
+    pub fn derive_xpub(&self, path: &DerivationPath) -> Result<ExtendedPubKey, String> {
+        let master = self.master.as_ref().ok_or_else(|| {
+            format!(
+                "the master key of '{}' is unknown, it cannot be derived at {}",
+                self.name, path
+            )
+        })?;
+        let curve = secp256k1::Secp256k1::signing_only();
+        master
+            .derive_priv(&curve, path)
+            .map(|xpriv| ExtendedPubKey::from_private(&curve, &xpriv))
+            .map_err(|e| e.to_string())
+    }
+
+    /// The key sources of a psbt refer either to the xpriv or to its master key.
+    pub fn signing_keys(&self) -> Vec<ExtendedPrivKey> {
+        let mut keys = vec![self.xpriv];
+        if let Some(master) = self.master {
+            if master != self.xpriv {
+                keys.push(master);
+            }
+        }
+        keys
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RawKey {
+    name: String,
+    xpriv: Option<String>,
+    mnemonic: Option<String>,
+    derivation_path: Option<String>,
+    network: Option<String>,
+}
+
+impl TryFrom<RawKey> for Key {
+    type Error = String;
+
+    fn try_from(raw: RawKey) -> Result<Self, Self::Error> {
+        match (raw.xpriv, raw.mnemonic) {
+            (Some(xpriv), None) => Ok(Key::from_xpriv(
+                raw.name.clone(),
+                ExtendedPrivKey::from_str(&xpriv)
+                    .map_err(|e| format!("Error parsing xpriv of key '{}': '{}'", raw.name, e))?,
+            )),
+            (None, Some(mnemonic)) => {
+                let network = match raw.network {
+                    Some(network) => Network::from_str(&network).map_err(|e| e.to_string())?,
+                    None => Network::Bitcoin,
+                };
+                let path = match raw.derivation_path {
+                    Some(path) => DerivationPath::from_str(&path).map_err(|e| e.to_string())?,
+                    None => keys::default_derivation_path(network),
+                };
+                let keys = keys::KeySet::from_mnemonic(
+                    keys::parse_mnemonic(&mnemonic).map_err(|e| e.to_string())?,
+                    network,
+                    path,
+                )
+                .map_err(|e| e.to_string())?;
+                Ok(Key::from_keyset(raw.name, keys))
+            }
+            _ => Err(format!(
+                "Key '{}' must have either a xpriv or a mnemonic",
+                raw.name
+            )),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -33,17 +145,13 @@ pub struct Descriptors {
 }
 
 impl Config {
-    pub fn new(xprivs: Vec<ExtendedPrivKey>) -> Self {
+    pub fn new(keys: Vec<Key>) -> Self {
         Self {
-            keys: xprivs
-                .into_iter()
-                .map(|xpriv| Key {
-                    name: "".to_string(),
-                    xpriv,
-                })
-                .collect(),
+            keys,
             descriptors: None,
             emergency_address: None,
+            specter_address: None,
+            log_path: None,
         }
     }
     pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
@@ -85,3 +193,36 @@ where
     T::from_str(&string)
         .map_err(|e| de::Error::custom(format!("Error parsing descriptor '{}': '{}'", string, e)))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_derive_xpub() {
+        let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let path = keys::default_derivation_path(Network::Testnet);
+        let set = keys::KeySet::from_mnemonic(
+            keys::parse_mnemonic(mnemonic).unwrap(),
+            Network::Testnet,
+            path.clone(),
+        )
+        .unwrap();
+        let xpub = set.xpub;
+        let key = Key::from_keyset("stk".to_string(), set);
+        assert_eq!(key.master_fingerprint().to_string(), "73c5da0a");
+        assert_eq!(key.derive_xpub(&path).unwrap(), xpub);
+        assert_eq!(key.signing_keys().len(), 2);
+
+        // The manager key is derived at the second account.
+        let manager = key
+            .derive_xpub(&DerivationPath::from_str("m/48'/1'/1'/2'").unwrap())
+            .unwrap();
+        assert_ne!(manager, xpub);
+
+        // The master key of an already derived xpriv is unknown.
+        let derived = Key::from_xpriv("derived".to_string(), key.xpriv);
+        assert!(derived.derive_xpub(&path).is_err());
+        assert_eq!(derived.signing_keys(), vec![key.xpriv]);
+    }
+}
@@ -0,0 +1,126 @@
+use std::str::FromStr;
+
+use bip39::Mnemonic;
+use revault_tx::bitcoin::{
+    secp256k1,
+    util::bip32::{DerivationPath, ExtendedPrivKey, ExtendedPubKey, Fingerprint},
+    Network,
+};
+
+/// Number of words of the generated mnemonics.
+pub const MNEMONIC_WORDS: usize = 24;
+
+/// Derivation path used if the user did not give one.
+pub fn default_derivation_path(network: Network) -> DerivationPath {
+    let path = if network == Network::Bitcoin {
+        "m/48'/0'/0'/2'"
+    } else {
+        "m/48'/1'/0'/2'"
+    };
+    DerivationPath::from_str(path).expect("Hardcoded derivation path")
+}
+
+/// A key derived from a mnemonic, the signer signs with the xpriv at the derivation path
+/// and the matching xpub is the one to give to the revault-gui installer.
+pub struct KeySet {
+    pub mnemonic: Mnemonic,
+    pub master: ExtendedPrivKey,
+    pub master_fingerprint: Fingerprint,
+    pub derivation_path: DerivationPath,
+    pub xpriv: ExtendedPrivKey,
+    pub xpub: ExtendedPubKey,
+}
+
+impl KeySet {
+    pub fn generate(network: Network, derivation_path: DerivationPath) -> Result<Self, Error> {
+        let mnemonic = Mnemonic::generate(MNEMONIC_WORDS).map_err(|e| Error(e.to_string()))?;
+        Self::from_mnemonic(mnemonic, network, derivation_path)
+    }
+
+    pub fn from_mnemonic(
+        mnemonic: Mnemonic,
+        network: Network,
+        derivation_path: DerivationPath,
+    ) -> Result<Self, Error> {
+        let curve = secp256k1::Secp256k1::new();
+        let master = ExtendedPrivKey::new_master(network, &mnemonic.to_seed(""))
+            .map_err(|e| Error(e.to_string()))?;
+        let xpriv = master
+            .derive_priv(&curve, &derivation_path)
+            .map_err(|e| Error(e.to_string()))?;
+        Ok(Self {
+            master_fingerprint: master.fingerprint(&curve),
+            master,
+            xpub: ExtendedPubKey::from_private(&curve, &xpriv),
+            mnemonic,
+            derivation_path,
+            xpriv,
+        })
+    }
+}
+
+impl std::fmt::Display for KeySet {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "mnemonic: {}", self.mnemonic)?;
+        writeln!(f, "master fingerprint: {}", self.master_fingerprint)?;
+        writeln!(f, "derivation path: {}", self.derivation_path)?;
+        writeln!(f, "xpriv: {}", self.xpriv)?;
+        write!(f, "xpub: {}", self.xpub)
+    }
+}
+
+pub fn parse_mnemonic(words: &str) -> Result<Mnemonic, Error> {
+    Mnemonic::parse(words).map_err(|e| Error(format!("Invalid mnemonic: {}", e)))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Error(String);
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keyset_from_mnemonic() {
+        // BIP39 test vector, seed with the empty passphrase.
+        let mnemonic = parse_mnemonic(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+        )
+        .unwrap();
+        let keys = KeySet::from_mnemonic(
+            mnemonic,
+            Network::Bitcoin,
+            DerivationPath::from_str("m").unwrap(),
+        )
+        .unwrap();
+        assert_eq!(keys.master_fingerprint.to_string(), "73c5da0a");
+        assert_eq!(
+            keys.xpriv.to_string(),
+            "xprv9s21ZrQH143K3GJpoapnV8SFfukcVBSfeCficPSGfubmSFDxo1kuHnLisriDvSnRRuL2Qrg5ggqHKNVpxR86QEC8w35uxmGoggxtQTPvfUu"
+        );
+    }
+
+    #[test]
+    fn keyset_derivation_path() {
+        let keys =
+            KeySet::generate(Network::Testnet, default_derivation_path(Network::Testnet)).unwrap();
+        let curve = secp256k1::Secp256k1::new();
+        assert_eq!(keys.xpub.network, Network::Testnet);
+        assert_eq!(keys.xpub.depth, 4);
+        assert_eq!(keys.xpub, ExtendedPubKey::from_private(&curve, &keys.xpriv));
+
+        let reloaded = KeySet::from_mnemonic(
+            parse_mnemonic(&keys.mnemonic.to_string()).unwrap(),
+            Network::Testnet,
+            keys.derivation_path.clone(),
+        )
+        .unwrap();
+        assert_eq!(reloaded.xpriv, keys.xpriv);
+    }
+}
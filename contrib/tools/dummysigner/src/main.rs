@@ -1,8 +1,11 @@
 mod api;
 mod app;
+mod audit;
 mod config;
+mod keys;
 mod server;
 mod sign;
+mod specter;
 mod view;
 
 use std::env;
@@ -10,21 +13,111 @@ use std::path::PathBuf;
 use std::process;
 use std::str::FromStr;
 
-use revault_tx::miniscript::descriptor::DescriptorSecretKey;
+use revault_tx::{
+    bitcoin::{
+        secp256k1,
+        util::bip32::{DerivationPath, ExtendedPubKey},
+        Network,
+    },
+    miniscript::descriptor::DescriptorSecretKey,
+};
+
+fn usage(name: &str) {
+    eprintln!(
+        "Usage:\n{} [--specter <address>] [--log <log path>] <xpriv>  <xpriv>...\n{} [--specter <address>] [--log <log path>] --conf <config path>\n{} [--specter <address>] [--log <log path>] [--network <network>] [--path <derivation path>] --mnemonic \"<words>\"\n{} generate [--network <network>] [--path <derivation path>]\n{} xpubs <keys arguments>\n\nThe revault-gui looks for a Specter simulator at {}",
+        name,
+        name,
+        name,
+        name,
+        name,
+        specter::SPECTER_DEFAULT_ADDRESS
+    );
+}
+
+fn exit_with_usage(name: &str) -> ! {
+    usage(name);
+    process::exit(1);
+}
 
 fn main() {
     let args: Vec<String> = env::args().collect();
     if args.len() < 2 {
-        eprintln!(
-            "Usage:\n{} <xpriv>  <xpriv>...\n{} --conf <config path>",
-            args[0], args[0]
-        );
-        process::exit(1);
+        exit_with_usage(&args[0]);
+    }
+
+    let (subcommand, options) = match args[1].as_str() {
+        "generate" | "xpubs" => (Some(args[1].as_str()), &args[2..]),
+        _ => (None, &args[1..]),
+    };
+
+    let mut conf_path: Option<PathBuf> = None;
+    let mut specter_address: Option<String> = None;
+    let mut log_path: Option<PathBuf> = None;
+    let mut network: Option<Network> = None;
+    let mut derivation_path: Option<DerivationPath> = None;
+    let mut mnemonics: Vec<&String> = Vec::new();
+    let mut xprivs: Vec<&String> = Vec::new();
+    let mut iter = options.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--conf" || arg == "-c" {
+            match iter.next() {
+                Some(path) => conf_path = Some(PathBuf::from(path)),
+                None => exit_with_usage(&args[0]),
+            }
+        } else if arg == "--specter" {
+            match iter.next() {
+                Some(address) => specter_address = Some(address.clone()),
+                None => exit_with_usage(&args[0]),
+            }
+        } else if arg == "--log" {
+            match iter.next() {
+                Some(path) => log_path = Some(PathBuf::from(path)),
+                None => exit_with_usage(&args[0]),
+            }
+        } else if arg == "--network" {
+            match iter.next().map(|n| Network::from_str(n)) {
+                Some(Ok(n)) => network = Some(n),
+                Some(Err(e)) => {
+                    eprintln!("{}", e);
+                    process::exit(1);
+                }
+                None => exit_with_usage(&args[0]),
+            }
+        } else if arg == "--path" {
+            match iter.next().map(|p| DerivationPath::from_str(p)) {
+                Some(Ok(p)) => derivation_path = Some(p),
+                Some(Err(e)) => {
+                    eprintln!("{}", e);
+                    process::exit(1);
+                }
+                None => exit_with_usage(&args[0]),
+            }
+        } else if arg == "--mnemonic" {
+            match iter.next() {
+                Some(words) => mnemonics.push(words),
+                None => exit_with_usage(&args[0]),
+            }
+        } else {
+            xprivs.push(arg);
+        }
     }
 
-    let cfg = if args[1] == "--conf" || args[1] == "-c" {
-        let path = &args[2];
-        match config::Config::from_file(&PathBuf::from(path)) {
+    let network = network.unwrap_or(Network::Bitcoin);
+    let derivation_path = derivation_path.unwrap_or_else(|| keys::default_derivation_path(network));
+
+    if subcommand == Some("generate") {
+        match keys::KeySet::generate(network, derivation_path) {
+            Ok(keys) => println!("{}", keys),
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(1);
+            }
+        }
+        return;
+    }
+
+    let mut cfg = if let Some(path) = conf_path {
+        match config::Config::from_file(&path) {
             Ok(cfg) => cfg,
             Err(e) => {
                 eprintln!("{}", e);
@@ -33,7 +126,7 @@ fn main() {
         }
     } else {
         let mut keys = Vec::new();
-        for arg in &args[1..] {
+        for arg in xprivs {
             let key = match DescriptorSecretKey::from_str(arg) {
                 Ok(DescriptorSecretKey::XPrv(xpriv)) => xpriv.xkey,
                 _ => {
@@ -41,11 +134,44 @@ fn main() {
                     process::exit(1);
                 }
             };
-            keys.push(key);
+            keys.push(config::Key::from_xpriv("".to_string(), key));
+        }
+        for words in mnemonics {
+            match keys::parse_mnemonic(words).and_then(|mnemonic| {
+                keys::KeySet::from_mnemonic(mnemonic, network, derivation_path.clone())
+            }) {
+                Ok(set) => keys.push(config::Key::from_keyset("".to_string(), set)),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    process::exit(1);
+                }
+            }
         }
         config::Config::new(keys)
     };
 
+    // Print the xpubs to fill in the revault-gui installer.
+    if subcommand == Some("xpubs") {
+        let curve = secp256k1::Secp256k1::new();
+        for key in &cfg.keys {
+            let xpub = ExtendedPubKey::from_private(&curve, &key.xpriv);
+            if key.name.is_empty() {
+                println!("{}", xpub);
+            } else {
+                println!("{}: {}", key.name, xpub);
+            }
+        }
+        return;
+    }
+
+    if specter_address.is_some() {
+        cfg.specter_address = specter_address;
+    }
+
+    if log_path.is_some() {
+        cfg.log_path = log_path;
+    }
+
     if let Err(e) = app::run(cfg) {
         println!("{}", e);
     }
@@ -18,6 +18,12 @@ use revault_tx::{
 #[derive(Debug)]
 pub struct Error(String);
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 pub struct Signer {
     descriptors: Option<Descriptors>,
     emergency_address: Option<EmergencyAddress>,
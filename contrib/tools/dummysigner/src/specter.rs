@@ -0,0 +1,240 @@
+use std::hash::{Hash, Hasher};
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use revault_tx::bitcoin::{
+    consensus::encode,
+    util::{bip32::DerivationPath, psbt::PartiallySignedTransaction},
+};
+use tokio::{net::TcpListener, sync::Mutex};
+use tokio_util::codec::{FramedRead, FramedWrite, LinesCodec};
+
+use iced::futures::{SinkExt, StreamExt};
+use iced_futures::futures;
+
+/// Default address of the Specter simulator, used by the revault-gui.
+pub const SPECTER_DEFAULT_ADDRESS: &str = "127.0.0.1:8789";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Error(String);
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Requests of the Specter serial protocol handled by the dummysigner.
+#[derive(Debug, Clone)]
+pub enum Request {
+    Fingerprint,
+    Xpub(DerivationPath),
+    Sign(PartiallySignedTransaction),
+}
+
+impl std::str::FromStr for Request {
+    type Err = Error;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let mut words = line.trim().splitn(2, ' ');
+        match (words.next(), words.next()) {
+            (Some("fingerprint"), None) => Ok(Request::Fingerprint),
+            (Some("xpub"), Some(path)) => {
+                let path = path
+                    .trim()
+                    .parse::<DerivationPath>()
+                    .map_err(|e| Error(e.to_string()))?;
+                Ok(Request::Xpub(path))
+            }
+            (Some("sign"), Some(psbt)) => {
+                let bytes = base64::decode(psbt.trim()).map_err(|e| Error(e.to_string()))?;
+                let psbt = encode::deserialize(&bytes).map_err(|e| Error(e.to_string()))?;
+                Ok(Request::Sign(psbt))
+            }
+            _ => Err(Error(format!("unknown command '{}'", line.trim()))),
+        }
+    }
+}
+
+/// A Specter wallet only returns the global transaction and the partial signatures
+/// of the inputs, the other fields are removed from the signed psbt.
+pub fn strip_psbt(psbt: &PartiallySignedTransaction) -> PartiallySignedTransaction {
+    let mut stripped =
+        PartiallySignedTransaction::from_unsigned_tx(psbt.global.unsigned_tx.clone())
+            .expect("Psbt global transaction is unsigned");
+    for (input, stripped_input) in psbt.inputs.iter().zip(stripped.inputs.iter_mut()) {
+        stripped_input.partial_sigs = input.partial_sigs.clone();
+    }
+    stripped
+}
+
+pub fn encode_psbt(psbt: &PartiallySignedTransaction) -> String {
+    base64::encode(encode::serialize(psbt))
+}
+
+pub fn listen<T: ToString>(url: T) -> iced::Subscription<SpecterMessage> {
+    iced::Subscription::from_recipe(Server {
+        url: url.to_string(),
+    })
+}
+
+pub struct Server {
+    url: String,
+}
+
+impl<H, I> iced_native::subscription::Recipe<H, I> for Server
+where
+    H: Hasher,
+{
+    type Output = SpecterMessage;
+
+    fn hash(&self, state: &mut H) {
+        struct Marker;
+        std::any::TypeId::of::<Marker>().hash(state);
+        self.url.hash(state);
+    }
+
+    fn stream(
+        self: Box<Self>,
+        _input: futures::stream::BoxStream<'static, I>,
+    ) -> futures::stream::BoxStream<'static, Self::Output> {
+        Box::pin(futures::stream::unfold(
+            ServerState::Ready(self.url),
+            move |state| async move {
+                match state {
+                    ServerState::Ready(url) => match TcpListener::bind(url).await {
+                        Ok(l) => Some((SpecterMessage::Started, ServerState::Listening(l))),
+                        Err(_) => Some((SpecterMessage::Stopped, ServerState::Stopped)),
+                    },
+                    ServerState::Listening(listener) => match listener.accept().await {
+                        Ok((socket, addr)) => {
+                            let (read_half, write_half) = socket.into_split();
+                            let reader = FramedRead::new(read_half, LinesCodec::new());
+                            let writer = FramedWrite::new(write_half, LinesCodec::new());
+                            Some((
+                                SpecterMessage::NewConnection(addr, writer),
+                                ServerState::Connected { listener, reader },
+                            ))
+                        }
+                        Err(_) => Some((SpecterMessage::Stopped, ServerState::Stopped)),
+                    },
+                    ServerState::Connected {
+                        listener,
+                        mut reader,
+                    } => loop {
+                        match reader.next().await {
+                            // Requests are prefixed by empty lines in order to
+                            // flush the device input buffer.
+                            Some(Ok(line)) if line.trim().is_empty() => continue,
+                            Some(Ok(line)) => {
+                                break Some((
+                                    SpecterMessage::Request(line),
+                                    ServerState::Connected { listener, reader },
+                                ))
+                            }
+                            _ => {
+                                break Some((
+                                    SpecterMessage::ConnectionDropped,
+                                    ServerState::Listening(listener),
+                                ))
+                            }
+                        }
+                    },
+                    ServerState::Stopped => None,
+                }
+            },
+        ))
+    }
+}
+
+pub type Reader = FramedRead<tokio::net::tcp::OwnedReadHalf, LinesCodec>;
+
+pub type Writer = FramedWrite<tokio::net::tcp::OwnedWriteHalf, LinesCodec>;
+
+#[derive(Debug)]
+pub enum SpecterMessage {
+    Started,
+    NewConnection(SocketAddr, Writer),
+    Request(String),
+    Responded(Result<(), Error>),
+    ConnectionDropped,
+    Stopped,
+}
+
+pub enum ServerState {
+    Ready(String),
+    Listening(TcpListener),
+    Connected {
+        listener: TcpListener,
+        reader: Reader,
+    },
+    Stopped,
+}
+
+/// Response line of a request which failed or was refused.
+pub fn error_response<E: std::fmt::Display>(e: E) -> String {
+    format!("error: {}", e)
+}
+
+/// Acknowledge the reception of a request, the device answers later
+/// once the user confirmed or refused the request.
+pub async fn acknowledge(writer: Arc<Mutex<Writer>>) -> Result<(), Error> {
+    let mut writer = writer.lock().await;
+    writer
+        .send("ACK".to_string())
+        .await
+        .map_err(|e| Error(e.to_string()))
+}
+
+pub async fn respond(writer: Arc<Mutex<Writer>>, line: String) -> Result<(), Error> {
+    let mut writer = writer.lock().await;
+    writer.send(line).await.map_err(|e| Error(e.to_string()))
+}
+
+pub async fn acknowledge_and_respond(
+    writer: Arc<Mutex<Writer>>,
+    line: String,
+) -> Result<(), Error> {
+    acknowledge(writer.clone()).await?;
+    respond(writer, line).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const PSBT: &str = "cHNidP8BAF4CAAAAAUeuD/NEqc88sk3DoBrKoVKjXbN2xW8Jr/4GO5q87JqJAQAAAAD9////ARDEJAcAAAAAIgAgy7Co1PHzwoce0hHQR5RHMS72lSZudTF3bYrNgqLbkDYAAAAAAAEBKwAOJwcAAAAAIgAgdfJpF3TIFneDGEawKCIA4oiyxZcQtY90MYPUklUH28UBBUdSIQJYLe2/RPRlZOXYzbBnU21g6+NM0dGAHP9Ru/nXrCibQyEDRwTey1W1qoj/0e9dBjZiSMExThllURNv8U6ri7pKSQ5SriIGAlgt7b9E9GVk5djNsGdTbWDr40zR0YAc/1G7+desKJtDCNZ9f+kAAAAAIgYDRwTey1W1qoj/0e9dBjZiSMExThllURNv8U6ri7pKSQ4IcqlfIgAAAAAAAA==";
+
+    #[test]
+    fn parse_request() {
+        assert!(matches!(
+            Request::from_str("fingerprint"),
+            Ok(Request::Fingerprint)
+        ));
+        assert!(matches!(
+            Request::from_str(&format!("sign {}\r", PSBT)),
+            Ok(Request::Sign(_))
+        ));
+        assert!(matches!(
+            Request::from_str("xpub m/48h/1h/0h/2h"),
+            Ok(Request::Xpub(_))
+        ));
+        assert!(Request::from_str("sign notbase64").is_err());
+        assert!(Request::from_str("xpub notapath").is_err());
+        assert!(Request::from_str("address 0").is_err());
+    }
+
+    #[test]
+    fn strip_signed_psbt() {
+        let psbt = match Request::from_str(&format!("sign {}", PSBT)).unwrap() {
+            Request::Sign(psbt) => psbt,
+            _ => panic!("request is a sign request"),
+        };
+        let stripped = strip_psbt(&psbt);
+        assert_eq!(stripped.global.unsigned_tx, psbt.global.unsigned_tx);
+        assert_eq!(stripped.inputs.len(), psbt.inputs.len());
+        assert!(stripped.inputs[0].witness_utxo.is_none());
+        assert!(stripped.inputs[0].bip32_derivation.is_empty());
+    }
+}
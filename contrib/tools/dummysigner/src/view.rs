@@ -1,18 +1,20 @@
 use iced::{
-    button, container, Align, Button, Checkbox, Color, Column, Container, Element, Length, Row,
-    Text,
+    button, container, scrollable, Align, Button, Checkbox, Color, Column, Container, Element,
+    Length, Row, Scrollable, Text,
 };
 use std::net::SocketAddr;
 
-use revault_tx::bitcoin::Amount;
+use revault_tx::bitcoin::{util::psbt::PartiallySignedTransaction, Amount};
 
-use crate::api;
+use crate::{api, audit};
 
 #[derive(Debug, Clone)]
 pub enum ViewMessage {
     Confirm,
     Cancel,
     Key(usize, KeyMessage),
+    ShowHistory,
+    CloseHistory,
 }
 
 #[derive(Debug, Clone)]
@@ -20,8 +22,10 @@ pub enum KeyMessage {
     Selected(bool),
 }
 
-pub fn waiting_connection<'a>() -> Element<'a, ViewMessage> {
-    Container::new(Text::new("waiting"))
+pub fn waiting_connection<'a>(
+    history_button: Option<&'a mut button::State>,
+) -> Element<'a, ViewMessage> {
+    Container::new(with_history_button(Text::new("waiting"), history_button))
         .width(Length::Fill)
         .height(Length::Fill)
         .align_x(Align::Center)
@@ -29,13 +33,106 @@ pub fn waiting_connection<'a>() -> Element<'a, ViewMessage> {
         .into()
 }
 
-pub fn connected<'a>(addr: &SocketAddr) -> Element<'a, ViewMessage> {
-    Container::new(Text::new(&format!("Connected to {}", addr)))
+pub fn connected<'a>(
+    addr: &SocketAddr,
+    history_button: Option<&'a mut button::State>,
+) -> Element<'a, ViewMessage> {
+    Container::new(with_history_button(
+        Text::new(&format!("Connected to {}", addr)),
+        history_button,
+    ))
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .align_x(Align::Center)
+    .align_y(Align::Center)
+    .into()
+}
+
+fn with_history_button<'a>(
+    text: Text,
+    history_button: Option<&'a mut button::State>,
+) -> Column<'a, ViewMessage> {
+    let col = Column::new()
+        .push(text)
+        .spacing(20)
+        .align_items(Align::Center);
+    if let Some(state) = history_button {
+        col.push(
+            Button::new(
+                state,
+                Container::new(Text::new("History"))
+                    .width(Length::Units(100))
+                    .align_x(Align::Center),
+            )
+            .on_press(ViewMessage::ShowHistory),
+        )
+    } else {
+        col
+    }
+}
+
+pub struct HistoryView {
+    scroll: scrollable::State,
+    back_button: button::State,
+}
+
+impl HistoryView {
+    pub fn new() -> Self {
+        Self {
+            scroll: scrollable::State::new(),
+            back_button: button::State::new(),
+        }
+    }
+
+    pub fn render<'a>(&'a mut self, entries: &[audit::Entry]) -> Element<'a, ViewMessage> {
+        let mut col = Column::new().spacing(20);
+        if entries.is_empty() {
+            col = col.push(Text::new("No signing decision recorded"));
+        }
+        // Most recent decisions first.
+        for entry in entries.iter().rev() {
+            let mut entry_col = Column::new()
+                .push(Text::new(format!(
+                    "{} {} (unix time: {})",
+                    entry.decision, entry.method, entry.timestamp
+                )))
+                .spacing(5);
+            if !entry.keys.is_empty() {
+                entry_col = entry_col.push(Text::new(format!("keys: {}", entry.keys.join(", "))));
+            }
+            for tx in &entry.transactions {
+                entry_col = entry_col.push(
+                    Text::new(format!(
+                        "{} {} BTC",
+                        tx.txid,
+                        Amount::from_sat(tx.amount).as_btc()
+                    ))
+                    .size(15),
+                );
+            }
+            col = col.push(entry_col);
+        }
+
+        Container::new(
+            Column::new()
+                .push(
+                    Button::new(
+                        &mut self.back_button,
+                        Container::new(Text::new("Back"))
+                            .width(Length::Units(100))
+                            .align_x(Align::Center),
+                    )
+                    .on_press(ViewMessage::CloseHistory),
+                )
+                .push(Text::new("Signing decisions"))
+                .push(Scrollable::new(&mut self.scroll).push(col))
+                .spacing(20)
+                .padding(20),
+        )
         .width(Length::Fill)
         .height(Length::Fill)
-        .align_x(Align::Center)
-        .align_y(Align::Center)
         .into()
+    }
 }
 
 pub struct SignSpendTxView {
@@ -335,6 +432,65 @@ impl DelegateBatchView {
     }
 }
 
+pub struct SignPsbtView {
+    cancel_button: button::State,
+    confirm_button: button::State,
+}
+
+impl SignPsbtView {
+    pub fn new() -> Self {
+        Self {
+            cancel_button: button::State::new(),
+            confirm_button: button::State::new(),
+        }
+    }
+
+    pub fn render<'a>(
+        &'a mut self,
+        psbt: &PartiallySignedTransaction,
+        signed: bool,
+        keys: Vec<Element<'a, ViewMessage>>,
+        can_confirm: bool,
+    ) -> Element<'a, ViewMessage> {
+        if signed {
+            return Container::new(Text::new("Signed transaction"))
+                .style(SuccessPageStyle)
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .align_x(Align::Center)
+                .align_y(Align::Center)
+                .into();
+        }
+
+        if keys.is_empty() {
+            return error_no_keys(&mut self.cancel_button);
+        }
+
+        Container::new(
+            Column::new()
+                .push(Text::new("Sign transaction (Specter protocol)"))
+                .push(Text::new(format!(
+                    "txid: {}",
+                    psbt.global.unsigned_tx.txid()
+                )))
+                .push(Text::new("Select keys to sign the transaction with"))
+                .push(Column::with_children(keys).spacing(10))
+                .push(confirmation_footer(
+                    &mut self.cancel_button,
+                    &mut self.confirm_button,
+                    can_confirm,
+                ))
+                .spacing(20)
+                .align_items(Align::Center),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .align_x(Align::Center)
+        .align_y(Align::Center)
+        .into()
+    }
+}
+
 pub fn key_view(name: &str, selected: bool) -> Element<'static, KeyMessage> {
     Container::new(Checkbox::new(selected, name, KeyMessage::Selected)).into()
 }
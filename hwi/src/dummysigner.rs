@@ -1,6 +1,13 @@
 use bitcoin::{
-    base64, blockdata::transaction::OutPoint, consensus::encode,
-    util::psbt::PartiallySignedTransaction as Psbt, Amount,
+    base64,
+    blockdata::transaction::OutPoint,
+    consensus::encode,
+    hashes::hex::FromHex,
+    util::{
+        bip32::{DerivationPath, ExtendedPubKey, Fingerprint},
+        psbt::PartiallySignedTransaction as Psbt,
+    },
+    Amount,
 };
 
 use async_trait::async_trait;
@@ -77,6 +84,30 @@ impl DummySigner {
         Ok(())
     }
 
+    pub async fn get_master_fingerprint(&mut self) -> Result<Fingerprint, DummySignerError> {
+        let res = self.send(json!({"request": "master_fingerprint"})).await?;
+
+        let fingerprint: MasterFingerprint =
+            serde_json::from_value(res).map_err(|e| DummySignerError::Device(e.to_string()))?;
+        Fingerprint::from_hex(&fingerprint.master_fingerprint)
+            .map_err(|e| DummySignerError::Device(e.to_string()))
+    }
+
+    pub async fn get_extended_pubkey(
+        &mut self,
+        path: &DerivationPath,
+    ) -> Result<ExtendedPubKey, DummySignerError> {
+        let res = self
+            .send(json!({
+                "derivation_path": path.to_string(),
+            }))
+            .await?;
+
+        let xpub: ExtendedPublicKey =
+            serde_json::from_value(res).map_err(|e| DummySignerError::Device(e.to_string()))?;
+        Ok(xpub.xpub)
+    }
+
     pub async fn sign_revocation_txs(
         &mut self,
         emergency_tx: &Psbt,
@@ -195,6 +226,16 @@ impl DummySigner {
     }
 }
 
+#[derive(Deserialize)]
+pub struct ExtendedPublicKey {
+    pub xpub: ExtendedPubKey,
+}
+
+#[derive(Deserialize)]
+pub struct MasterFingerprint {
+    pub master_fingerprint: String,
+}
+
 #[derive(Deserialize)]
 pub struct RevocationTransactions {
     #[serde(deserialize_with = "deserialize_psbt_array")]
@@ -324,6 +365,15 @@ impl HWI for DummySigner {
     async fn sign_tx(&mut self, tx: &Psbt) -> Result<Psbt, HWIError> {
         self.sign_spend_tx(tx).await.map_err(|e| e.into())
     }
+    async fn get_extended_pubkey(
+        &mut self,
+        path: &DerivationPath,
+    ) -> Result<ExtendedPubKey, HWIError> {
+        self.get_extended_pubkey(path).await.map_err(|e| e.into())
+    }
+    async fn get_master_fingerprint(&mut self) -> Result<Fingerprint, HWIError> {
+        self.get_master_fingerprint().await.map_err(|e| e.into())
+    }
 }
 
 #[cfg(feature = "revault")]
@@ -1,4 +1,7 @@
-use bitcoin::util::psbt::PartiallySignedTransaction as Psbt;
+use bitcoin::util::{
+    bip32::{DerivationPath, ExtendedPubKey, Fingerprint},
+    psbt::PartiallySignedTransaction as Psbt,
+};
 
 pub mod app;
 
@@ -39,4 +42,15 @@ pub trait HWI: Debug {
     async fn is_connected(&mut self) -> Result<(), HWIError>;
     /// Sign a partially signed bitcoin transaction (PSBT).
     async fn sign_tx(&mut self, tx: &Psbt) -> Result<Psbt, HWIError>;
+    /// Get the extended public key of the device at the given derivation path.
+    async fn get_extended_pubkey(
+        &mut self,
+        _path: &DerivationPath,
+    ) -> Result<ExtendedPubKey, HWIError> {
+        Err(HWIError::UnimplementedMethod)
+    }
+    /// Get the fingerprint of the master key of the device.
+    async fn get_master_fingerprint(&mut self) -> Result<Fingerprint, HWIError> {
+        Err(HWIError::UnimplementedMethod)
+    }
 }
@@ -1,4 +1,14 @@
-use bitcoin::{base64, consensus::encode, util::psbt::PartiallySignedTransaction as Psbt};
+use std::str::FromStr;
+
+use bitcoin::{
+    base64,
+    consensus::encode,
+    hashes::hex::FromHex,
+    util::{
+        bip32::{DerivationPath, ExtendedPubKey, Fingerprint},
+        psbt::PartiallySignedTransaction as Psbt,
+    },
+};
 
 use serialport::{available_ports, SerialPortType};
 use tokio::io::AsyncBufReadExt;
@@ -21,6 +31,24 @@ impl<T: Unpin + AsyncWrite + AsyncRead> Specter<T> {
         self.request("\r\n\r\nfingerprint\r\n").await
     }
 
+    pub async fn master_fingerprint(&mut self) -> Result<Fingerprint, SpecterError> {
+        self.fingerprint().await.and_then(|resp| {
+            Fingerprint::from_hex(resp.trim()).map_err(|e| SpecterError::Device(e.to_string()))
+        })
+    }
+
+    pub async fn get_extended_pubkey(
+        &mut self,
+        path: &DerivationPath,
+    ) -> Result<ExtendedPubKey, SpecterError> {
+        self.request(&format!("\r\n\r\nxpub {}\r\n", path))
+            .await
+            .and_then(|resp| {
+                ExtendedPubKey::from_str(resp.trim())
+                    .map_err(|e| SpecterError::Device(e.to_string()))
+            })
+    }
+
     pub async fn sign(&mut self, psbt: &Psbt) -> Result<Psbt, SpecterError> {
         let mut new_psbt: Psbt = self
             .request(&format!(
@@ -80,6 +108,10 @@ impl<T: Unpin + AsyncWrite + AsyncRead> Specter<T> {
             .await
             .map_err(|e| SpecterError::Device(e.to_string()))?
         {
+            // The device answers with an error line if the request failed or was refused.
+            if let Some(e) = line.strip_prefix("error: ") {
+                return Err(SpecterError::Device(e.to_string()));
+            }
             return Ok(line);
         }
         Err(SpecterError::Device("Unexpected".to_string()))
@@ -109,6 +141,15 @@ impl HWI for Specter<TcpStream> {
     async fn sign_tx(&mut self, tx: &Psbt) -> Result<Psbt, HWIError> {
         self.sign(tx).await.map_err(|e| e.into())
     }
+    async fn get_extended_pubkey(
+        &mut self,
+        path: &DerivationPath,
+    ) -> Result<ExtendedPubKey, HWIError> {
+        self.get_extended_pubkey(path).await.map_err(|e| e.into())
+    }
+    async fn get_master_fingerprint(&mut self) -> Result<Fingerprint, HWIError> {
+        self.master_fingerprint().await.map_err(|e| e.into())
+    }
 }
 
 const SPECTER_VID: u16 = 61525;
@@ -155,6 +196,15 @@ impl HWI for Specter<SerialStream> {
     async fn sign_tx(&mut self, tx: &Psbt) -> Result<Psbt, HWIError> {
         self.sign(tx).await.map_err(|e| e.into())
     }
+    async fn get_extended_pubkey(
+        &mut self,
+        path: &DerivationPath,
+    ) -> Result<ExtendedPubKey, HWIError> {
+        self.get_extended_pubkey(path).await.map_err(|e| e.into())
+    }
+    async fn get_master_fingerprint(&mut self) -> Result<Fingerprint, HWIError> {
+        self.master_fingerprint().await.map_err(|e| e.into())
+    }
 }
 
 #[derive(Debug)]
@@ -203,3 +253,143 @@ mod revault {
 
     impl<T> NoRevaultApp for Specter<T> {}
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::BufReader;
+    use tokio::net::TcpListener;
+
+    const PSBT: &str = "cHNidP8BAF4CAAAAAUeuD/NEqc88sk3DoBrKoVKjXbN2xW8Jr/4GO5q87JqJAQAAAAD9////ARDEJAcAAAAAIgAgy7Co1PHzwoce0hHQR5RHMS72lSZudTF3bYrNgqLbkDYAAAAAAAEBKwAOJwcAAAAAIgAgdfJpF3TIFneDGEawKCIA4oiyxZcQtY90MYPUklUH28UBBUdSIQJYLe2/RPRlZOXYzbBnU21g6+NM0dGAHP9Ru/nXrCibQyEDRwTey1W1qoj/0e9dBjZiSMExThllURNv8U6ri7pKSQ5SriIGAlgt7b9E9GVk5djNsGdTbWDr40zR0YAc/1G7+desKJtDCNZ9f+kAAAAAIgYDRwTey1W1qoj/0e9dBjZiSMExThllURNv8U6ri7pKSQ4IcqlfIgAAAAAAAA==";
+
+    fn psbt() -> Psbt {
+        encode::deserialize(&base64::decode(PSBT).unwrap()).unwrap()
+    }
+
+    /// Psbt as returned by a Specter: only the global transaction
+    /// and the partial signatures.
+    fn specter_signed_psbt(psbt: &Psbt, signer: usize) -> Psbt {
+        let mut signed = Psbt::from_unsigned_tx(psbt.global.unsigned_tx.clone()).unwrap();
+        let pubkey = *psbt.inputs[0].bip32_derivation.keys().nth(signer).unwrap();
+        signed.inputs[0].partial_sigs.insert(
+            pubkey,
+            vec![0x30, 0x06, 0x02, 0x01, 0x01, 0x02, 0x01, 0x01, 0x01],
+        );
+        signed
+    }
+
+    /// Local stand-in of the Specter simulator, it answers the first request
+    /// with the given lines.
+    async fn responder(response: String) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = socket.split();
+            let mut lines = BufReader::new(reader).lines();
+            while let Some(line) = lines.next_line().await.unwrap() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                writer.write_all(response.as_bytes()).await.unwrap();
+                break;
+            }
+        });
+        address
+    }
+
+    #[tokio::test]
+    async fn fingerprint() {
+        let address = responder("ACK\r\nd67d7fe9\r\n".to_string()).await;
+        let mut specter = Specter::try_connect_simulator(address).await.unwrap();
+        assert_eq!(specter.fingerprint().await.unwrap(), "d67d7fe9");
+    }
+
+    #[tokio::test]
+    async fn master_fingerprint() {
+        let address = responder("ACK\r\nd67d7fe9\r\n".to_string()).await;
+        let mut specter = Specter::try_connect_simulator(address).await.unwrap();
+        assert_eq!(
+            specter.master_fingerprint().await.unwrap(),
+            Fingerprint::from_hex("d67d7fe9").unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn extended_pubkey() {
+        let xpub = "xpub6CZFHPW1GiB8YgV7zGpeQDB6mMHZYPQyUaHrM1nMvKMgLxwok4xCtnzjuxQ3p1LHJUkz5i1Y7bRy5fmGrdg8UBVb39XdXNtWWd2wTsNd7T9";
+        let address = responder(format!("ACK\r\n{}\r\n", xpub)).await;
+        let mut specter = Specter::try_connect_simulator(address).await.unwrap();
+        let path = DerivationPath::from_str("m/48'/0'/0'/2'").unwrap();
+        assert_eq!(
+            specter
+                .get_extended_pubkey(&path)
+                .await
+                .unwrap()
+                .to_string(),
+            xpub
+        );
+    }
+
+    #[tokio::test]
+    async fn incorrect_answer() {
+        let address = responder("NACK\r\n".to_string()).await;
+        let mut specter = Specter::try_connect_simulator(address).await.unwrap();
+        assert!(specter.fingerprint().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn error_answer() {
+        let address = responder("ACK\r\nerror: no key\r\n".to_string()).await;
+        let mut specter = Specter::try_connect_simulator(address).await.unwrap();
+        match specter.fingerprint().await {
+            Err(SpecterError::Device(e)) => assert_eq!(e, "no key"),
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[tokio::test]
+    async fn sign_merges_partial_signatures() {
+        let mut psbt = psbt();
+        // The first key already signed.
+        let first = specter_signed_psbt(&psbt, 0);
+        psbt.inputs[0].partial_sigs = first.inputs[0].partial_sigs.clone();
+
+        let response = specter_signed_psbt(&psbt, 1);
+        let address = responder(format!(
+            "ACK\r\n{}\r\n",
+            base64::encode(&encode::serialize(&response))
+        ))
+        .await;
+        let mut specter = Specter::try_connect_simulator(address).await.unwrap();
+        let signed = specter.sign(&psbt).await.unwrap();
+
+        assert_eq!(signed.inputs[0].partial_sigs.len(), 2);
+        // The fields removed by the device are kept from the original psbt.
+        assert_eq!(signed.inputs[0].witness_utxo, psbt.inputs[0].witness_utxo);
+        assert_eq!(
+            signed.inputs[0].witness_script,
+            psbt.inputs[0].witness_script
+        );
+        assert_eq!(
+            signed.inputs[0].bip32_derivation,
+            psbt.inputs[0].bip32_derivation
+        );
+    }
+
+    #[tokio::test]
+    async fn sign_without_signatures() {
+        let psbt = psbt();
+        let response = Psbt::from_unsigned_tx(psbt.global.unsigned_tx.clone()).unwrap();
+        let address = responder(format!(
+            "ACK\r\n{}\r\n",
+            base64::encode(&encode::serialize(&response))
+        ))
+        .await;
+        let mut specter = Specter::try_connect_simulator(address).await.unwrap();
+        assert!(matches!(
+            specter.sign(&psbt).await,
+            Err(SpecterError::DeviceDidNotSign)
+        ));
+    }
+}
@@ -0,0 +1,171 @@
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::app::error::Error;
+
+pub const DEFAULT_FILE_NAME: &str = "address_book.json";
+
+/// Maximum number of entries suggested in the recipient form.
+const MAX_SUGGESTIONS: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub label: String,
+    pub address: String,
+    pub network: bitcoin::Network,
+    #[serde(default)]
+    pub notes: String,
+}
+
+/// AddressBook is the list of the known recipients, stored in the network datadir.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AddressBook {
+    pub entries: Vec<Entry>,
+}
+
+impl AddressBook {
+    /// Returns an empty address book if the file does not exist yet.
+    pub fn load(network_datadir: &Path) -> Result<Self, Error> {
+        let path = path(network_datadir);
+        match std::fs::read(&path) {
+            Ok(content) => serde_json::from_slice(&content)
+                .map_err(|e| Error::File(format!("Failed to read {}: {}", path.display(), e))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(Error::File(format!(
+                "Failed to read {}: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+
+    pub fn save(&self, network_datadir: &Path) -> Result<(), Error> {
+        let path = path(network_datadir);
+        let content = serde_json::to_vec_pretty(self)
+            .expect("AddressBook has a proper Serialize implementation");
+        std::fs::write(&path, content)
+            .map_err(|e| Error::File(format!("Failed to write {}: {}", path.display(), e)))
+    }
+
+    /// Adds the entry or replaces the one with the same address.
+    pub fn insert(&mut self, entry: Entry) -> Result<(), String> {
+        if entry.label.trim().is_empty() {
+            return Err("The label is empty".to_string());
+        }
+        let address = bitcoin::Address::from_str(&entry.address)
+            .map_err(|e| format!("Invalid address: {}", e))?;
+        // Base58 addresses of the test networks are parsed as testnet addresses.
+        if address.network != entry.network
+            && !(address.network == bitcoin::Network::Testnet
+                && entry.network != bitcoin::Network::Bitcoin)
+        {
+            return Err(format!("The address is not for {}", entry.network));
+        }
+        match self.entries.iter_mut().find(|e| e.address == entry.address) {
+            Some(existing) => *existing = entry,
+            None => self.entries.push(entry),
+        }
+        Ok(())
+    }
+
+    pub fn remove(&mut self, address: &str) {
+        self.entries.retain(|entry| entry.address != address);
+    }
+
+    pub fn get(&self, address: &str, network: bitcoin::Network) -> Option<&Entry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.network == network && entry.address == address)
+    }
+
+    /// Entries of the network whose label or address contains the query, ignoring case.
+    pub fn search(&self, query: &str, network: bitcoin::Network) -> Vec<&Entry> {
+        let query = query.to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        self.entries
+            .iter()
+            .filter(|entry| {
+                entry.network == network
+                    && (entry.label.to_lowercase().contains(&query)
+                        || entry.address.to_lowercase().contains(&query))
+            })
+            .take(MAX_SUGGESTIONS)
+            .collect()
+    }
+}
+
+pub fn path(network_datadir: &Path) -> PathBuf {
+    let mut path = network_datadir.to_path_buf();
+    path.push(DEFAULT_FILE_NAME);
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: &str = "bcrt1q9q5j52ev95hz7vp3xgengdfkxuurjw3m58r49z";
+
+    fn entry(label: &str, address: &str, network: bitcoin::Network) -> Entry {
+        Entry {
+            label: label.to_string(),
+            address: address.to_string(),
+            network,
+            notes: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_address_book() {
+        let mut book = AddressBook::default();
+        book.insert(entry("Vendor", ADDRESS, bitcoin::Network::Regtest))
+            .unwrap();
+        assert!(book
+            .insert(entry("", ADDRESS, bitcoin::Network::Regtest))
+            .is_err());
+        assert!(book
+            .insert(entry("Wrong", "bcrt1qwrong", bitcoin::Network::Regtest))
+            .is_err());
+        assert!(book
+            .insert(entry("Mainnet", ADDRESS, bitcoin::Network::Bitcoin))
+            .is_err());
+
+        // Same address, the entry is replaced.
+        book.insert(entry("Payroll", ADDRESS, bitcoin::Network::Regtest))
+            .unwrap();
+        assert_eq!(book.entries.len(), 1);
+        assert_eq!(
+            book.get(ADDRESS, bitcoin::Network::Regtest).unwrap().label,
+            "Payroll"
+        );
+        assert!(book.get(ADDRESS, bitcoin::Network::Bitcoin).is_none());
+
+        assert_eq!(book.search("payr", bitcoin::Network::Regtest).len(), 1);
+        assert_eq!(book.search("9q5j52", bitcoin::Network::Regtest).len(), 1);
+        assert!(book.search("vendor", bitcoin::Network::Regtest).is_empty());
+        assert!(book.search("", bitcoin::Network::Regtest).is_empty());
+
+        book.remove(ADDRESS);
+        assert!(book.entries.is_empty());
+    }
+
+    #[test]
+    fn save_and_load_address_book() {
+        let mut datadir = std::env::temp_dir();
+        datadir.push(format!("revault-gui-address-book-{}", std::process::id()));
+        std::fs::create_dir_all(&datadir).unwrap();
+        assert_eq!(AddressBook::load(&datadir).unwrap(), AddressBook::default());
+
+        let mut book = AddressBook::default();
+        book.insert(entry("Vendor", ADDRESS, bitcoin::Network::Regtest))
+            .unwrap();
+        book.save(&datadir).unwrap();
+        assert_eq!(AddressBook::load(&datadir).unwrap(), book);
+
+        std::fs::remove_dir_all(datadir).unwrap();
+    }
+}
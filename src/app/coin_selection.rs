@@ -0,0 +1,261 @@
+use std::fmt;
+
+/// Virtual size of the spend transaction without its inputs and outputs.
+const BASE_VBYTES: u64 = 11;
+/// Virtual size of a P2WSH output.
+const OUTPUT_VBYTES: u64 = 43;
+/// Virtual size of an unvault output spent by the managers and the cosigning
+/// servers, an upper estimate for the usual federation sizes.
+const INPUT_VBYTES: u64 = 250;
+
+/// Maximum number of combinations explored by the MinimizeChange strategy
+/// before keeping the best one found.
+const MAX_TRIES: usize = 100_000;
+
+/// Strategy used to select the vaults spent by a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoinSelection {
+    MinimizeVaults,
+    MinimizeChange,
+    OldestFirst,
+}
+
+impl CoinSelection {
+    pub const ALL: [CoinSelection; 3] = [
+        CoinSelection::MinimizeVaults,
+        CoinSelection::MinimizeChange,
+        CoinSelection::OldestFirst,
+    ];
+}
+
+impl fmt::Display for CoinSelection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MinimizeVaults => write!(f, "Fewest vaults"),
+            Self::MinimizeChange => write!(f, "Least change"),
+            Self::OldestFirst => write!(f, "Oldest first"),
+        }
+    }
+}
+
+/// A vault that can be spent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Candidate {
+    /// Amount of the unvault output, in satoshis.
+    pub amount: u64,
+    /// The deposit addresses are derived in order, a lower index is an older deposit.
+    pub derivation_index: u32,
+}
+
+/// Estimated fees in satoshis of a spend transaction. The outputs are the recipients
+/// and the change and cpfp outputs added by the daemon.
+pub fn estimate_fees(feerate: u64, number_of_inputs: usize, number_of_recipients: usize) -> u64 {
+    feerate * estimate_vbytes(number_of_inputs, number_of_recipients + 2)
+}
+
+/// Estimated virtual size of a signed spend transaction with the given number
+/// of outputs, all of them counted.
+pub fn estimate_vbytes(number_of_inputs: usize, number_of_outputs: usize) -> u64 {
+    BASE_VBYTES + INPUT_VBYTES * number_of_inputs as u64 + OUTPUT_VBYTES * number_of_outputs as u64
+}
+
+/// Returns the indexes of the candidates covering the output amount and the fees,
+/// None if all the candidates do not cover them.
+/// `fees` gives the fees of a transaction for a number of inputs.
+pub fn select_coins(
+    strategy: CoinSelection,
+    candidates: &[Candidate],
+    output_amount: u64,
+    fees: impl Fn(usize) -> u64,
+) -> Option<Vec<usize>> {
+    let mut indexes: Vec<usize> = (0..candidates.len()).collect();
+    match strategy {
+        CoinSelection::MinimizeVaults => {
+            indexes.sort_by(|a, b| candidates[*b].amount.cmp(&candidates[*a].amount));
+            accumulate(candidates, &indexes, output_amount, &fees)
+        }
+        CoinSelection::OldestFirst => {
+            indexes.sort_by_key(|i| candidates[*i].derivation_index);
+            accumulate(candidates, &indexes, output_amount, &fees)
+        }
+        CoinSelection::MinimizeChange => {
+            indexes.sort_by(|a, b| candidates[*b].amount.cmp(&candidates[*a].amount));
+            let mut best = accumulate(candidates, &indexes, output_amount, &fees)?;
+            let mut best_change = change(candidates, &best, output_amount, &fees);
+            let mut search = Search {
+                candidates,
+                indexes: &indexes,
+                output_amount,
+                fees: &fees,
+                tries: 0,
+            };
+            search.explore(0, &mut Vec::new(), 0, &mut best, &mut best_change);
+            best.sort_unstable();
+            Some(best)
+        }
+    }
+}
+
+/// Takes the candidates in the given order until they cover the output amount and the fees.
+fn accumulate(
+    candidates: &[Candidate],
+    indexes: &[usize],
+    output_amount: u64,
+    fees: &impl Fn(usize) -> u64,
+) -> Option<Vec<usize>> {
+    let mut selected = Vec::new();
+    let mut amount = 0;
+    for i in indexes {
+        selected.push(*i);
+        amount += candidates[*i].amount;
+        if amount >= output_amount + fees(selected.len()) {
+            selected.sort_unstable();
+            return Some(selected);
+        }
+    }
+    None
+}
+
+fn change(
+    candidates: &[Candidate],
+    selected: &[usize],
+    output_amount: u64,
+    fees: &impl Fn(usize) -> u64,
+) -> u64 {
+    let amount: u64 = selected.iter().map(|i| candidates[*i].amount).sum();
+    amount - output_amount - fees(selected.len())
+}
+
+/// Depth-first search of the combination with the smallest change,
+/// the candidates are explored from the biggest amount to the smallest.
+struct Search<'a, F: Fn(usize) -> u64> {
+    candidates: &'a [Candidate],
+    indexes: &'a [usize],
+    output_amount: u64,
+    fees: &'a F,
+    tries: usize,
+}
+
+impl<'a, F: Fn(usize) -> u64> Search<'a, F> {
+    fn explore(
+        &mut self,
+        position: usize,
+        selected: &mut Vec<usize>,
+        amount: u64,
+        best: &mut Vec<usize>,
+        best_change: &mut u64,
+    ) {
+        if *best_change == 0 || self.tries >= MAX_TRIES {
+            return;
+        }
+        self.tries += 1;
+
+        let target = self.output_amount + (self.fees)(selected.len());
+        if !selected.is_empty() && amount >= target {
+            if amount - target < *best_change {
+                *best_change = amount - target;
+                *best = selected.clone();
+            }
+            // Adding a vault only increases the change.
+            return;
+        }
+
+        let remaining: u64 = self.indexes[position..]
+            .iter()
+            .map(|i| self.candidates[*i].amount)
+            .sum();
+        if amount + remaining < target {
+            return;
+        }
+
+        for next in position..self.indexes.len() {
+            let i = self.indexes[next];
+            selected.push(i);
+            self.explore(
+                next + 1,
+                selected,
+                amount + self.candidates[i].amount,
+                best,
+                best_change,
+            );
+            selected.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates(amounts: &[u64]) -> Vec<Candidate> {
+        amounts
+            .iter()
+            .enumerate()
+            .map(|(i, amount)| Candidate {
+                amount: *amount,
+                derivation_index: i as u32,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_select_coins() {
+        let candidates = candidates(&[500, 3_000, 1_200, 800, 2_000]);
+        let no_fees = |_| 0;
+
+        assert_eq!(
+            select_coins(CoinSelection::MinimizeVaults, &candidates, 4_000, no_fees),
+            Some(vec![1, 4])
+        );
+        assert_eq!(
+            select_coins(CoinSelection::OldestFirst, &candidates, 4_000, no_fees),
+            Some(vec![0, 1, 2])
+        );
+        assert_eq!(
+            select_coins(CoinSelection::MinimizeChange, &candidates, 4_000, no_fees),
+            Some(vec![2, 3, 4])
+        );
+        // 3_000 + 1_200 is the closest to 4_100.
+        assert_eq!(
+            select_coins(CoinSelection::MinimizeChange, &candidates, 4_100, no_fees),
+            Some(vec![1, 2])
+        );
+        assert_eq!(
+            select_coins(CoinSelection::MinimizeChange, &candidates, 2_500, no_fees),
+            Some(vec![0, 4])
+        );
+
+        // Each input costs 100 sats.
+        let fees = |inputs: usize| 100 * inputs as u64;
+        assert_eq!(
+            select_coins(CoinSelection::MinimizeVaults, &candidates, 2_900, fees),
+            Some(vec![1])
+        );
+        assert_eq!(
+            select_coins(CoinSelection::MinimizeVaults, &candidates, 2_950, fees),
+            Some(vec![1, 4])
+        );
+
+        for strategy in &CoinSelection::ALL {
+            assert_eq!(
+                select_coins(*strategy, &candidates, 7_500, no_fees),
+                Some(vec![0, 1, 2, 3, 4])
+            );
+            assert_eq!(select_coins(*strategy, &candidates, 7_501, no_fees), None);
+            assert_eq!(select_coins(*strategy, &[], 1, no_fees), None);
+        }
+    }
+
+    #[test]
+    fn test_estimate_fees() {
+        assert_eq!(estimate_fees(0, 3, 2), 0);
+        assert_eq!(
+            estimate_fees(2, 1, 1),
+            2 * (BASE_VBYTES + INPUT_VBYTES + 3 * OUTPUT_VBYTES)
+        );
+        assert_eq!(
+            estimate_vbytes(1, 1),
+            BASE_VBYTES + INPUT_VBYTES + OUTPUT_VBYTES
+        );
+    }
+}
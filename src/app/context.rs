@@ -1,10 +1,11 @@
 use std::fs::OpenOptions;
 use std::future::Future;
 use std::io::Write;
+use std::path::PathBuf;
 use std::pin::Pin;
 use std::sync::Arc;
 
-use bitcoin::util::psbt::PartiallySignedTransaction as Psbt;
+use bitcoin::util::bip32::{ExtendedPubKey, Fingerprint};
 
 use revaultd::config::Config as DaemonConfig;
 use revaultd::revault_tx::miniscript::DescriptorPublicKey;
@@ -12,10 +13,16 @@ use revaultd::revault_tx::miniscript::DescriptorPublicKey;
 use revault_hwi::{app::revault::RevaultHWI, HWIError};
 
 use crate::{
-    app::{config, error::Error, menu::Menu},
+    app::{
+        config::{self, default_datadir},
+        error::Error,
+        inspector::OwnScripts,
+        labels::{LabelType, Labels},
+        menu::Menu,
+    },
     conversion::Converter,
     daemon::Daemon,
-    revault::Role,
+    revault::{Role, UnvaultParameters},
 };
 
 pub type HardwareWallet =
@@ -30,7 +37,11 @@ pub struct Context {
     pub converter: Converter,
     pub menu: Menu,
     pub role: Role,
+    /// Number of managers signatures required by the unvault descriptor,
+    /// 0 if it could not be derived from the descriptors.
     pub managers_threshold: usize,
+    /// Labels of the user, loaded with the application.
+    pub labels: Labels,
     pub hardware_wallet: Box<dyn Fn() -> Pin<HardwareWallet> + Send + Sync>,
 }
 
@@ -43,6 +54,12 @@ impl Context {
         menu: Menu,
         hardware_wallet: Box<dyn Fn() -> Pin<HardwareWallet> + Send + Sync>,
     ) -> Self {
+        let managers_threshold = UnvaultParameters::new(
+            &config.daemon.scripts_config.deposit_descriptor,
+            &config.daemon.scripts_config.unvault_descriptor,
+        )
+        .map(|parameters| parameters.managers_threshold)
+        .unwrap_or(0);
         Self {
             config,
             blockheight: 0,
@@ -50,7 +67,8 @@ impl Context {
             converter,
             role,
             menu,
-            managers_threshold: 0,
+            managers_threshold,
+            labels: Labels::default(),
             hardware_wallet,
         }
     }
@@ -64,6 +82,20 @@ impl Context {
         self.config.daemon.bitcoind_config.network
     }
 
+    /// Directory of the daemon files for the network, None if the default
+    /// datadir cannot be located.
+    pub fn network_datadir(&self) -> Option<PathBuf> {
+        self.config
+            .daemon
+            .data_dir
+            .clone()
+            .or_else(|| default_datadir().ok())
+            .map(|mut path| {
+                path.push(self.network().to_string());
+                path
+            })
+    }
+
     pub fn stakeholders_xpubs(&self) -> Vec<DescriptorPublicKey> {
         self.config.daemon.scripts_config.deposit_descriptor.xpubs()
     }
@@ -85,34 +117,58 @@ impl Context {
             .collect()
     }
 
-    pub fn user_signed(&self, psbt: &Psbt) -> bool {
-        let man_fp = &self
-            .config
-            .daemon
-            .manager_config
-            .as_ref()
-            .map(|key| key.xpub.fingerprint());
-        let stk_fp = &self
-            .config
-            .daemon
-            .stakeholder_config
-            .as_ref()
-            .map(|key| key.xpub.fingerprint());
-        if let Some(input) = psbt.inputs.first() {
-            input.partial_sigs.keys().any(|key| {
-                input
-                    .bip32_derivation
-                    .get(key)
-                    .map(|(fingerprint, _)| {
-                        Some(*fingerprint) == *man_fp || Some(*fingerprint) == *stk_fp
-                    })
-                    .unwrap_or(false)
-            })
-        } else {
-            false
+    /// Master fingerprints of the managers' xpubs, in the order of the unvault descriptor.
+    pub fn managers_fingerprints(&self) -> Vec<Fingerprint> {
+        self.managers_xpubs()
+            .iter()
+            .map(|xpub| xpub.master_fingerprint())
+            .collect()
+    }
+
+    /// Master fingerprint of the manager key of the user, None if the user is not
+    /// a manager of the unvault descriptor.
+    pub fn manager_fingerprint(&self) -> Option<Fingerprint> {
+        let cfg = self.config.daemon.manager_config.as_ref()?;
+        master_fingerprint(&self.managers_xpubs(), &cfg.xpub)
+    }
+
+    /// Name of a manager: "You" for the user, the label of the manager xpub
+    /// or else its position in the unvault descriptor.
+    pub fn manager_name(&self, fingerprint: &Fingerprint) -> String {
+        if self.manager_fingerprint().as_ref() == Some(fingerprint) {
+            return "You".to_string();
+        }
+        let xpubs = self.managers_xpubs();
+        match xpubs
+            .iter()
+            .position(|xpub| xpub.master_fingerprint() == *fingerprint)
+        {
+            Some(i) => {
+                let label = match &xpubs[i] {
+                    DescriptorPublicKey::XPub(xpub) => {
+                        self.labels.get(LabelType::Xpub, &xpub.xkey.to_string())
+                    }
+                    DescriptorPublicKey::SinglePub(_) => None,
+                };
+                label
+                    .map(|label| label.to_string())
+                    .unwrap_or_else(|| format!("Manager {}", i + 1))
+            }
+            None => fingerprint.to_string(),
         }
     }
 
+    /// Scripts of the setup descriptors derived at the given indexes.
+    pub fn own_scripts(&self, indexes: &[u32]) -> OwnScripts {
+        let scripts_config = &self.config.daemon.scripts_config;
+        OwnScripts::derive(
+            &scripts_config.deposit_descriptor,
+            &scripts_config.unvault_descriptor,
+            &scripts_config.cpfp_descriptor,
+            indexes,
+        )
+    }
+
     pub fn load_daemon_config(&mut self, cfg: DaemonConfig) -> Result<(), Error> {
         loop {
             if let Some(daemon) = Arc::get_mut(&mut self.revaultd) {
@@ -140,7 +196,47 @@ impl Context {
     }
 }
 
+/// Master fingerprint of the descriptor key of the xpub, it is the key origin
+/// fingerprint if the descriptor key has one.
+fn master_fingerprint(keys: &[DescriptorPublicKey], xpub: &ExtendedPubKey) -> Option<Fingerprint> {
+    keys.iter()
+        .find(|key| matches!(key, DescriptorPublicKey::XPub(xkey) if xkey.xkey == *xpub))
+        .map(|key| key.master_fingerprint())
+}
+
 pub struct ConfigContext {
     pub daemon: DaemonConfig,
     pub gui: config::Config,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const MANAGER_XPUB: &str = "xpub6CZFHPW1GiB8YgV7zGpeQDB6mMHZYPQyUaHrM1nMvKMgLxwok4xCtnzjuxQ3p1LHJUkz5i1Y7bRy5fmGrdg8UBVb39XdXNtWWd2wTsNd7T9";
+
+    #[test]
+    fn manager_master_fingerprint() {
+        let xpub = ExtendedPubKey::from_str(MANAGER_XPUB).unwrap();
+        let keys = vec![
+            DescriptorPublicKey::from_str("xpub6Doj75MBvKp7bgHxF1KeDGxm36rd4wonZWv8sfzTeNoNVX2QZaQdrEcs7NDXvs4Cbsy9TPMx5VDcMK6JjSKepBbYDPiJ9bLBR4bqfdHmxZx/*").unwrap(),
+            DescriptorPublicKey::from_str(&format!("[d34db33f/48'/0'/1'/2']{}/*", MANAGER_XPUB))
+                .unwrap(),
+        ];
+        // The key origin is the device master key, not the xpub itself.
+        assert_eq!(
+            master_fingerprint(&keys, &xpub),
+            Some(Fingerprint::from_str("d34db33f").unwrap())
+        );
+        assert_ne!(
+            xpub.fingerprint(),
+            Fingerprint::from_str("d34db33f").unwrap()
+        );
+
+        let keys = vec![DescriptorPublicKey::from_str(&format!("{}/*", MANAGER_XPUB)).unwrap()];
+        assert_eq!(master_fingerprint(&keys, &xpub), Some(xpub.fingerprint()));
+
+        assert_eq!(master_fingerprint(&keys[..0], &xpub), None);
+    }
+}
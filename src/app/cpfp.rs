@@ -0,0 +1,382 @@
+use std::{collections::HashSet, fmt};
+
+use bitcoin::{
+    blockdata::{script::Script, transaction::TxIn},
+    util::psbt::{self, PartiallySignedTransaction as Psbt},
+    Amount, OutPoint, Transaction, TxOut, Txid,
+};
+
+use crate::{
+    app::inspector::{OwnScripts, ScriptKind},
+    bitcoind::MempoolEntry,
+};
+
+/// Below this value the output of the child transaction would not be relayed.
+pub const DUST_VALUE: u64 = 330;
+/// Sequence of the child inputs, it signals replaceability.
+const SEQUENCE_RBF: u32 = 0xFFFF_FFFD;
+
+/// Output of an unconfirmed transaction paying to the CPFP descriptor,
+/// spent by the child transaction to bump the fees of its package.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CpfpParent {
+    pub txid: Txid,
+    pub vout: u32,
+    pub txout: TxOut,
+    /// Witness script and key origins of the output, given by the daemon.
+    pub output: psbt::Output,
+    pub entry: MempoolEntry,
+}
+
+impl CpfpParent {
+    pub fn outpoint(&self) -> OutPoint {
+        OutPoint::new(self.txid, self.vout)
+    }
+}
+
+/// Index of the output paying to the CPFP descriptor.
+pub fn cpfp_output(psbt: &Psbt, scripts: &OwnScripts) -> Option<usize> {
+    psbt.global.unsigned_tx.output.iter().position(|txout| {
+        matches!(
+            scripts.get(&txout.script_pubkey),
+            Some((ScriptKind::Cpfp, _))
+        )
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CpfpError {
+    /// The transactions are confirmed or were evicted from the mempool.
+    NothingToBump,
+    MissingWitnessScript(OutPoint),
+    /// The package already pays at least the target feerate.
+    FeerateTooLow {
+        current: u64,
+    },
+    InsufficientFunds {
+        needed: Amount,
+        available: Amount,
+    },
+    MissingSignature(OutPoint),
+}
+
+impl fmt::Display for CpfpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NothingToBump => write!(f, "No transaction to bump is in the mempool"),
+            Self::MissingWitnessScript(outpoint) => write!(
+                f,
+                "The witness script of the CPFP output {} is unknown",
+                outpoint
+            ),
+            Self::FeerateTooLow { current } => write!(
+                f,
+                "The transactions already pay {} sats/vbyte, the feerate must be higher",
+                current
+            ),
+            Self::InsufficientFunds { needed, available } => write!(
+                f,
+                "The CPFP outputs hold {} sats but {} sats are needed",
+                available.as_sat(),
+                needed.as_sat()
+            ),
+            Self::MissingSignature(outpoint) => {
+                write!(f, "The input {} of the child is not signed", outpoint)
+            }
+        }
+    }
+}
+
+/// Virtual size and fees of the transactions and their unconfirmed ancestors.
+/// A transaction with several CPFP outputs is counted once, but the ancestors
+/// are only known in aggregate: distinct parent transactions must not share
+/// unconfirmed ancestors, which holds for the spend transactions as their
+/// unvault transactions are confirmed before the relative timelock expires.
+fn package(parents: &[CpfpParent]) -> (u64, u64) {
+    let mut txids = HashSet::new();
+    parents
+        .iter()
+        .filter(|p| txids.insert(p.txid))
+        .fold((0, 0), |(vsize, fees), p| {
+            (vsize + p.entry.ancestor_vsize, fees + p.entry.ancestor_fees)
+        })
+}
+
+/// Feerate in sats/vbyte of the transactions and their unconfirmed ancestors.
+pub fn package_feerate(parents: &[CpfpParent]) -> u64 {
+    let (vsize, fees) = package(parents);
+    if vsize == 0 {
+        0
+    } else {
+        fees / vsize
+    }
+}
+
+/// Estimated virtual size of the signed child, the CPFP descriptor is a
+/// `multi(1, ..)` whose witness is an empty element, a signature and the script.
+pub fn child_vsize(witness_scripts: &[&Script]) -> u64 {
+    // version, locktime, counts of inputs and outputs, then the segwit marker and flag.
+    let mut weight = 4 * (4 + 4 + 1 + 1) + 2;
+    // A single P2WSH output.
+    weight += 4 * (8 + 1 + 34);
+    for script in witness_scripts {
+        // outpoint, empty script_sig and sequence.
+        weight += 4 * (36 + 1 + 4);
+        let script_len = script.len() as u64;
+        weight += 1 + 1 + (1 + 72) + varint_len(script_len) + script_len;
+    }
+    (weight + 3) / 4
+}
+
+fn varint_len(n: u64) -> u64 {
+    match n {
+        0..=0xFC => 1,
+        0xFD..=0xFFFF => 3,
+        0x10000..=0xFFFF_FFFF => 5,
+        _ => 9,
+    }
+}
+
+/// Creates the child transaction spending the CPFP outputs of the parents, with
+/// the fees giving the package the target feerate in sats/vbyte. The remaining
+/// value goes back to the CPFP output of the first parent.
+pub fn create_child(parents: &[CpfpParent], feerate: u64) -> Result<Psbt, CpfpError> {
+    if parents.is_empty() {
+        return Err(CpfpError::NothingToBump);
+    }
+    let mut witness_scripts = Vec::new();
+    for parent in parents {
+        match &parent.output.witness_script {
+            Some(script) => witness_scripts.push(script),
+            None => return Err(CpfpError::MissingWitnessScript(parent.outpoint())),
+        }
+    }
+
+    let current = package_feerate(parents);
+    if feerate <= current {
+        return Err(CpfpError::FeerateTooLow { current });
+    }
+    let (package_vsize, package_fees) = package(parents);
+    let fees =
+        (feerate * (package_vsize + child_vsize(&witness_scripts))).saturating_sub(package_fees);
+    let available: u64 = parents.iter().map(|p| p.txout.value).sum();
+    if available < fees + DUST_VALUE {
+        return Err(CpfpError::InsufficientFunds {
+            needed: Amount::from_sat(fees + DUST_VALUE),
+            available: Amount::from_sat(available),
+        });
+    }
+
+    let tx = Transaction {
+        version: 2,
+        lock_time: 0,
+        input: parents
+            .iter()
+            .map(|parent| TxIn {
+                previous_output: parent.outpoint(),
+                script_sig: Script::new(),
+                sequence: SEQUENCE_RBF,
+                witness: Vec::new(),
+            })
+            .collect(),
+        output: vec![TxOut {
+            value: available - fees,
+            script_pubkey: parents[0].txout.script_pubkey.clone(),
+        }],
+    };
+    let mut psbt = Psbt::from_unsigned_tx(tx).expect("the transaction is unsigned");
+    for (input, parent) in psbt.inputs.iter_mut().zip(parents) {
+        input.witness_utxo = Some(parent.txout.clone());
+        input.witness_script = parent.output.witness_script.clone();
+        input.bip32_derivation = parent.output.bip32_derivation.clone();
+    }
+    psbt.outputs[0] = parents[0].output.clone();
+    Ok(psbt)
+}
+
+/// Finalizes the signed child, a single signature satisfies the CPFP descriptor.
+pub fn finalize_child(mut psbt: Psbt) -> Result<Transaction, CpfpError> {
+    let outpoints: Vec<OutPoint> = psbt
+        .global
+        .unsigned_tx
+        .input
+        .iter()
+        .map(|txin| txin.previous_output)
+        .collect();
+    for (input, outpoint) in psbt.inputs.iter_mut().zip(outpoints) {
+        let script = input
+            .witness_script
+            .clone()
+            .ok_or(CpfpError::MissingWitnessScript(outpoint))?;
+        let sig = input
+            .partial_sigs
+            .values()
+            .next()
+            .cloned()
+            .ok_or(CpfpError::MissingSignature(outpoint))?;
+        input.final_script_witness = Some(vec![Vec::new(), sig, script.into_bytes()]);
+    }
+    Ok(psbt.extract_tx())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::fixtures::PSBT;
+    use bitcoin::{
+        secp256k1,
+        util::{bip32::ChildNumber, ecdsa::PublicKey},
+    };
+    use revaultd::revault_tx::{miniscript::DescriptorPublicKey, scripts::CpfpDescriptor};
+    use std::str::FromStr;
+
+    const MANAGERS_XPUBS: [&str; 2] = [
+        "xpub6CZFHPW1GiB8YgV7zGpeQDB6mMHZYPQyUaHrM1nMvKMgLxwok4xCtnzjuxQ3p1LHJUkz5i1Y7bRy5fmGrdg8UBVb39XdXNtWWd2wTsNd7T9",
+        "xpub6Doj75MBvKp7bgHxF1KeDGxm36rd4wonZWv8sfzTeNoNVX2QZaQdrEcs7NDXvs4Cbsy9TPMx5VDcMK6JjSKepBbYDPiJ9bLBR4bqfdHmxZx",
+    ];
+
+    /// Output of 16_608 sats paying to the CPFP descriptor of the managers
+    /// derived at index 3.
+    fn parent() -> CpfpParent {
+        let cpfp = CpfpDescriptor::new(
+            MANAGERS_XPUBS
+                .iter()
+                .map(|xpub| DescriptorPublicKey::from_str(&format!("{}/*", xpub)).unwrap())
+                .collect(),
+        )
+        .unwrap();
+        let secp = secp256k1::Secp256k1::verification_only();
+        let derived = cpfp.derive(ChildNumber::from_normal_idx(3).unwrap(), &secp);
+        let output = psbt::Output {
+            witness_script: Some(derived.inner().explicit_script()),
+            ..Default::default()
+        };
+        CpfpParent {
+            txid: Txid::from_str(
+                "6a4bdf8c3e2a17d05e9b0c1f4d2a7e83b5c6f9012d3e4a5b6c7d8e9f0a1b2c3d",
+            )
+            .unwrap(),
+            vout: 0,
+            txout: TxOut {
+                value: 16_608,
+                script_pubkey: derived.inner().script_pubkey(),
+            },
+            output,
+            entry: MempoolEntry {
+                vsize: 180,
+                fees: 5968,
+                ancestor_vsize: 180,
+                ancestor_fees: 5968,
+            },
+        }
+    }
+
+    #[test]
+    fn test_cpfp_output() {
+        let psbt = Psbt::from_str(PSBT).unwrap();
+        let mut scripts = OwnScripts::default();
+        assert_eq!(cpfp_output(&psbt, &scripts), None);
+        scripts.insert(
+            psbt.global.unsigned_tx.output[0].script_pubkey.clone(),
+            ScriptKind::Cpfp,
+            0,
+        );
+        scripts.insert(
+            psbt.global.unsigned_tx.output[1].script_pubkey.clone(),
+            ScriptKind::Deposit,
+            0,
+        );
+        assert_eq!(cpfp_output(&psbt, &scripts), Some(0));
+    }
+
+    #[test]
+    fn test_package_feerate() {
+        let parent = parent();
+        assert_eq!(package_feerate(&[]), 0);
+
+        // Two CPFP outputs of the same transaction count its entry once.
+        let mut sibling = parent.clone();
+        sibling.vout = 1;
+        assert_eq!(package_feerate(&[parent.clone(), sibling]), 33);
+
+        let mut other = parent.clone();
+        other.txid = Txid::default();
+        other.entry.ancestor_vsize = 120;
+        other.entry.ancestor_fees = 12_032;
+        // (5968 + 12032) / (180 + 120)
+        assert_eq!(package_feerate(&[parent, other]), 60);
+    }
+
+    #[test]
+    fn test_create_child() {
+        let parent = parent();
+        assert_eq!(package_feerate(&[parent.clone()]), 33);
+        // 525 weight units with the witness script of `multi(1, A, B)`, 71 bytes.
+        let witness_script = parent.output.witness_script.as_ref().unwrap();
+        assert_eq!(witness_script.len(), 71);
+        let vsize = child_vsize(&[witness_script]);
+        assert_eq!(vsize, 132);
+
+        assert_eq!(
+            create_child(&[parent.clone()], 33),
+            Err(CpfpError::FeerateTooLow { current: 33 })
+        );
+        assert_eq!(create_child(&[], 50), Err(CpfpError::NothingToBump));
+
+        let child = create_child(&[parent.clone()], 50).unwrap();
+        let tx = &child.global.unsigned_tx;
+        let fees = 50 * (180 + vsize) - 5968;
+        assert_eq!(tx.input[0].previous_output, parent.outpoint());
+        assert_eq!(tx.output[0].value, 16_608 - fees);
+        assert_eq!(tx.output[0].script_pubkey, parent.txout.script_pubkey);
+        assert_eq!(child.inputs[0].witness_utxo, Some(parent.txout.clone()));
+        assert_eq!(child.inputs[0].witness_script, parent.output.witness_script);
+        assert_eq!(child.outputs[0], parent.output);
+
+        // The CPFP output cannot pay for the whole package.
+        let needed = 200 * (180 + vsize) - 5968 + DUST_VALUE;
+        assert_eq!(
+            create_child(&[parent.clone()], 200),
+            Err(CpfpError::InsufficientFunds {
+                needed: Amount::from_sat(needed),
+                available: Amount::from_sat(16_608),
+            })
+        );
+
+        let mut unknown = parent;
+        unknown.output.witness_script = None;
+        assert_eq!(
+            create_child(&[unknown.clone()], 50),
+            Err(CpfpError::MissingWitnessScript(unknown.outpoint()))
+        );
+    }
+
+    #[test]
+    fn test_finalize_child() {
+        let parent = parent();
+        let mut child = create_child(&[parent.clone()], 50).unwrap();
+        let outpoint = child.global.unsigned_tx.input[0].previous_output;
+        assert_eq!(
+            finalize_child(child.clone()),
+            Err(CpfpError::MissingSignature(outpoint))
+        );
+
+        let sig = vec![0x30, 0x44, 0x01];
+        child.inputs[0].partial_sigs.insert(
+            PublicKey::from_str(
+                "0202933ab1037ead0aa4a7858c6d49d6705e1fb3bc5f66b00918af7b9f00ecd526",
+            )
+            .unwrap(),
+            sig.clone(),
+        );
+        let tx = finalize_child(child).unwrap();
+        assert_eq!(
+            tx.input[0].witness,
+            vec![
+                Vec::new(),
+                sig,
+                parent.output.witness_script.unwrap().into_bytes()
+            ]
+        );
+    }
+}
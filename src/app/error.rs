@@ -1,4 +1,4 @@
-use crate::daemon::RevaultDError;
+use crate::{bitcoind, daemon::RevaultDError};
 use revaultd::config::ConfigError;
 use std::convert::From;
 use std::io::ErrorKind;
@@ -9,6 +9,10 @@ pub enum Error {
     // TODO: add Clone to ConfigError
     Config(String),
     Daemon(RevaultDError),
+    /// Failure of a request sent directly to bitcoind.
+    Bitcoind(bitcoind::Error),
+    /// Failure to read or write a file chosen by the user.
+    File(String),
     Unexpected(String),
 }
 
@@ -37,6 +41,8 @@ impl std::fmt::Display for Error {
                     write!(f, "[{:?}] {}", code, e)
                 }
             },
+            Self::Bitcoind(e) => write!(f, "{}", e),
+            Self::File(e) => write!(f, "{}", e),
             Self::Unexpected(e) => write!(f, "Unexpected error: {}", e),
         }
     }
@@ -59,3 +65,9 @@ impl From<revault_hwi::HWIError> for Error {
         Error::Hardware(error)
     }
 }
+
+impl From<bitcoind::Error> for Error {
+    fn from(error: bitcoind::Error) -> Self {
+        Error::Bitcoind(error)
+    }
+}
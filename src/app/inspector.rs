@@ -0,0 +1,508 @@
+use std::collections::HashMap;
+
+use bitcoin::{
+    consensus::encode,
+    hashes::hex::FromHex,
+    secp256k1,
+    util::{
+        bip32::{ChildNumber, Fingerprint},
+        ecdsa::PublicKey,
+        psbt::PartiallySignedTransaction as Psbt,
+    },
+    Amount, OutPoint, Script, Transaction, Txid,
+};
+use revaultd::revault_tx::scripts::{CpfpDescriptor, DepositDescriptor, UnvaultDescriptor};
+
+use crate::app::psbt_file;
+
+/// Number of derivation indexes after the last known one where the outputs
+/// are looked for.
+const GAP_LIMIT: u32 = 20;
+
+/// Sequence disabling the relative timelock and the absolute one if all inputs have it.
+const SEQUENCE_FINAL: u32 = 0xFFFF_FFFF;
+const SEQUENCE_LOCKTIME_DISABLE_FLAG: u32 = 1 << 31;
+const SEQUENCE_LOCKTIME_TYPE_FLAG: u32 = 1 << 22;
+const SEQUENCE_LOCKTIME_MASK: u32 = 0x0000_FFFF;
+/// Lock times below are block heights, above are unix timestamps.
+const LOCKTIME_THRESHOLD: u32 = 500_000_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Decoded {
+    Psbt(Psbt),
+    Transaction(Transaction),
+}
+
+/// Parses a PSBT, binary, base64 or hex, or a raw transaction, binary or hex.
+pub fn decode(content: &[u8]) -> Result<Decoded, String> {
+    if let Ok(psbt) = psbt_file::decode(content) {
+        return Ok(Decoded::Psbt(psbt));
+    }
+    if let Some(Ok(bytes)) = std::str::from_utf8(content)
+        .ok()
+        .map(|text| Vec::<u8>::from_hex(text.trim()))
+    {
+        if let Ok(psbt) = psbt_file::decode(&bytes) {
+            return Ok(Decoded::Psbt(psbt));
+        }
+        return encode::deserialize(&bytes)
+            .map(Decoded::Transaction)
+            .map_err(|e| format!("invalid transaction: {}", e));
+    }
+    encode::deserialize(content)
+        .map(Decoded::Transaction)
+        .map_err(|_| "not a PSBT or a transaction".to_string())
+}
+
+/// Descriptor of the setup a script was derived from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptKind {
+    Deposit,
+    Unvault,
+    Cpfp,
+}
+
+impl std::fmt::Display for ScriptKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Deposit => write!(f, "Deposit"),
+            Self::Unvault => write!(f, "Unvault"),
+            Self::Cpfp => write!(f, "CPFP"),
+        }
+    }
+}
+
+/// OwnScripts are the scripts of the setup descriptors with their derivation index.
+#[derive(Debug, Clone, Default)]
+pub struct OwnScripts(HashMap<Script, (ScriptKind, u32)>);
+
+impl OwnScripts {
+    pub fn derive(
+        deposit: &DepositDescriptor,
+        unvault: &UnvaultDescriptor,
+        cpfp: &CpfpDescriptor,
+        indexes: &[u32],
+    ) -> Self {
+        let secp = secp256k1::Secp256k1::verification_only();
+        let mut scripts = Self::default();
+        for index in indexes {
+            let child = match ChildNumber::from_normal_idx(*index) {
+                Ok(child) => child,
+                Err(_) => continue,
+            };
+            scripts.insert(
+                deposit.derive(child, &secp).inner().script_pubkey(),
+                ScriptKind::Deposit,
+                *index,
+            );
+            scripts.insert(
+                unvault.derive(child, &secp).inner().script_pubkey(),
+                ScriptKind::Unvault,
+                *index,
+            );
+            scripts.insert(
+                cpfp.derive(child, &secp).inner().script_pubkey(),
+                ScriptKind::Cpfp,
+                *index,
+            );
+        }
+        scripts
+    }
+
+    pub fn insert(&mut self, script: Script, kind: ScriptKind, index: u32) {
+        self.0.insert(script, (kind, index));
+    }
+
+    pub fn get(&self, script: &Script) -> Option<(ScriptKind, u32)> {
+        self.0.get(script).copied()
+    }
+}
+
+/// Derivation indexes of the vaults and of the PSBT keys, followed by the gap limit.
+pub fn derivation_indexes(decoded: &Decoded, vaults: &[(OutPoint, Amount, u32)]) -> Vec<u32> {
+    let mut indexes: Vec<u32> = vaults.iter().map(|(_, _, index)| *index).collect();
+    if let Decoded::Psbt(psbt) = decoded {
+        let derivations = psbt
+            .inputs
+            .iter()
+            .flat_map(|input| input.bip32_derivation.values())
+            .chain(
+                psbt.outputs
+                    .iter()
+                    .flat_map(|output| output.bip32_derivation.values()),
+            );
+        for (_, path) in derivations {
+            if let Some(ChildNumber::Normal { index }) = path.as_ref().last() {
+                indexes.push(*index);
+            }
+        }
+    }
+    let next = indexes.iter().max().map(|max| max + 1).unwrap_or(0);
+    indexes.extend(next..next + GAP_LIMIT);
+    indexes.sort_unstable();
+    indexes.dedup();
+    indexes
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Timelock {
+    Height(u32),
+    /// Unix timestamp.
+    Time(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RelativeTimelock {
+    Blocks(u32),
+    Seconds(u32),
+}
+
+/// A signature of an input with the fingerprint of the signer if the PSBT has its key source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InspectedSignature {
+    pub pubkey: PublicKey,
+    pub fingerprint: Option<Fingerprint>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InspectedInput {
+    pub previous_output: OutPoint,
+    pub amount: Option<Amount>,
+    /// Descriptor and derivation index of the spent script if it is one of the setup.
+    pub script: Option<(ScriptKind, u32)>,
+    /// Deposit outpoint of the vault the input belongs to.
+    pub vault: Option<OutPoint>,
+    pub sequence: u32,
+    pub relative_timelock: Option<RelativeTimelock>,
+    pub signatures: Vec<InspectedSignature>,
+    /// Signatures of the final witness, which signers are not known.
+    pub witness_signatures: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InspectedOutput {
+    pub script_pubkey: Script,
+    pub amount: Amount,
+    /// Descriptor and derivation index of the script if it is one of the setup.
+    pub script: Option<(ScriptKind, u32)>,
+}
+
+/// Inspection is the decoded content of a PSBT or of a raw transaction,
+/// matched against the vaults and the descriptors of the setup.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inspection {
+    pub txid: Txid,
+    pub is_psbt: bool,
+    /// True if every input has a witness.
+    pub finalized: bool,
+    pub version: i32,
+    pub lock_time: Option<Timelock>,
+    pub inputs: Vec<InspectedInput>,
+    pub outputs: Vec<InspectedOutput>,
+    /// Virtual size of the transaction, without the missing witnesses of a PSBT.
+    pub vsize: u64,
+    /// Fees, if the amounts of all the inputs are known.
+    pub fees: Option<Amount>,
+}
+
+impl Inspection {
+    pub fn new(
+        decoded: &Decoded,
+        vaults: &[(OutPoint, Amount, u32)],
+        scripts: &OwnScripts,
+    ) -> Self {
+        let tx = match decoded {
+            Decoded::Psbt(psbt) => psbt.clone().extract_tx(),
+            Decoded::Transaction(tx) => tx.clone(),
+        };
+
+        let inputs: Vec<InspectedInput> = tx
+            .input
+            .iter()
+            .enumerate()
+            .map(|(i, txin)| {
+                let psbt_input = match decoded {
+                    Decoded::Psbt(psbt) => psbt.inputs.get(i),
+                    Decoded::Transaction(_) => None,
+                };
+                let deposit = vaults
+                    .iter()
+                    .find(|(outpoint, _, _)| *outpoint == txin.previous_output);
+
+                // The spent script is given by the PSBT or by the witness script,
+                // the last element of the P2WSH witness.
+                let spent_output = psbt_input.and_then(|input| {
+                    input.witness_utxo.clone().or_else(|| {
+                        input.non_witness_utxo.as_ref().and_then(|prev| {
+                            prev.output.get(txin.previous_output.vout as usize).cloned()
+                        })
+                    })
+                });
+                let script_pubkey = spent_output
+                    .as_ref()
+                    .map(|output| output.script_pubkey.clone())
+                    .or_else(|| {
+                        psbt_input
+                            .and_then(|input| input.witness_script.as_ref())
+                            .map(|script| script.to_v0_p2wsh())
+                    })
+                    .or_else(|| {
+                        txin.witness
+                            .last()
+                            .map(|script| Script::from(script.clone()).to_v0_p2wsh())
+                    });
+                let script = script_pubkey
+                    .and_then(|spk| scripts.get(&spk))
+                    .or_else(|| deposit.map(|(_, _, index)| (ScriptKind::Deposit, *index)));
+
+                let vault = deposit.map(|(outpoint, _, _)| *outpoint).or_else(|| {
+                    script.and_then(|(kind, index)| {
+                        if kind == ScriptKind::Unvault {
+                            vaults
+                                .iter()
+                                .find(|(_, _, i)| *i == index)
+                                .map(|(outpoint, _, _)| *outpoint)
+                        } else {
+                            None
+                        }
+                    })
+                });
+
+                let signatures = psbt_input
+                    .map(|input| {
+                        input
+                            .partial_sigs
+                            .keys()
+                            .map(|pubkey| InspectedSignature {
+                                pubkey: *pubkey,
+                                fingerprint: input
+                                    .bip32_derivation
+                                    .get(pubkey)
+                                    .map(|(fingerprint, _)| *fingerprint),
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+
+                InspectedInput {
+                    previous_output: txin.previous_output,
+                    amount: spent_output
+                        .map(|output| Amount::from_sat(output.value))
+                        .or_else(|| deposit.map(|(_, amount, _)| *amount)),
+                    script,
+                    vault,
+                    sequence: txin.sequence,
+                    relative_timelock: relative_timelock(tx.version, txin.sequence),
+                    signatures,
+                    witness_signatures: txin.witness.iter().filter(|e| is_signature(e)).count(),
+                }
+            })
+            .collect();
+
+        let outputs: Vec<InspectedOutput> = tx
+            .output
+            .iter()
+            .map(|txout| InspectedOutput {
+                script_pubkey: txout.script_pubkey.clone(),
+                amount: Amount::from_sat(txout.value),
+                script: scripts.get(&txout.script_pubkey),
+            })
+            .collect();
+
+        let fees = inputs
+            .iter()
+            .map(|input| input.amount)
+            .sum::<Option<Amount>>()
+            .and_then(|inputs_amount| {
+                inputs_amount.checked_sub(outputs.iter().map(|output| output.amount).sum())
+            });
+
+        Self {
+            txid: tx.txid(),
+            is_psbt: matches!(decoded, Decoded::Psbt(_)),
+            finalized: !tx.input.is_empty() && tx.input.iter().all(|txin| !txin.witness.is_empty()),
+            version: tx.version,
+            lock_time: lock_time(&tx),
+            inputs,
+            outputs,
+            vsize: (tx.get_weight() as u64 + 3) / 4,
+            fees,
+        }
+    }
+
+    /// Feerate in sat/vbyte.
+    pub fn feerate(&self) -> Option<u64> {
+        self.fees
+            .filter(|_| self.vsize != 0)
+            .map(|fees| fees.as_sat() / self.vsize)
+    }
+}
+
+/// The lock time is enforced only if an input does not have the final sequence.
+fn lock_time(tx: &Transaction) -> Option<Timelock> {
+    if tx.lock_time == 0 || tx.input.iter().all(|txin| txin.sequence == SEQUENCE_FINAL) {
+        None
+    } else if tx.lock_time < LOCKTIME_THRESHOLD {
+        Some(Timelock::Height(tx.lock_time))
+    } else {
+        Some(Timelock::Time(tx.lock_time))
+    }
+}
+
+/// Relative timelocks are defined by BIP-68 for transactions of version 2.
+fn relative_timelock(version: i32, sequence: u32) -> Option<RelativeTimelock> {
+    if version < 2 || sequence & SEQUENCE_LOCKTIME_DISABLE_FLAG != 0 {
+        return None;
+    }
+    let value = sequence & SEQUENCE_LOCKTIME_MASK;
+    if sequence & SEQUENCE_LOCKTIME_TYPE_FLAG != 0 {
+        Some(RelativeTimelock::Seconds(value * 512))
+    } else {
+        Some(RelativeTimelock::Blocks(value))
+    }
+}
+
+/// A DER encoded ECDSA signature followed by its sighash type.
+fn is_signature(element: &[u8]) -> bool {
+    element.len() >= 9
+        && element.len() <= 73
+        && element[0] == 0x30
+        && element[1] as usize == element.len() - 3
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::fixtures::PSBT;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_decode() {
+        let psbt = Psbt::from_str(PSBT).unwrap();
+        assert_eq!(
+            decode(PSBT.as_bytes()).unwrap(),
+            Decoded::Psbt(psbt.clone())
+        );
+        assert_eq!(
+            decode(&psbt_file::encode(&psbt)).unwrap(),
+            Decoded::Psbt(psbt.clone())
+        );
+        let psbt_hex = bitcoin::hashes::hex::ToHex::to_hex(&psbt_file::encode(&psbt)[..]);
+        assert_eq!(
+            decode(psbt_hex.as_bytes()).unwrap(),
+            Decoded::Psbt(psbt.clone())
+        );
+
+        let tx = psbt.global.unsigned_tx.clone();
+        assert_eq!(
+            decode(encode::serialize_hex(&tx).as_bytes()).unwrap(),
+            Decoded::Transaction(tx.clone())
+        );
+        assert_eq!(
+            decode(&encode::serialize(&tx)).unwrap(),
+            Decoded::Transaction(tx)
+        );
+        assert!(decode(b"0200").is_err());
+        assert!(decode(b"not a transaction").is_err());
+    }
+
+    #[test]
+    fn test_inspection() {
+        let mut psbt = Psbt::from_str(PSBT).unwrap();
+        let mut scripts = OwnScripts::default();
+        scripts.insert(
+            psbt.inputs[0].witness_utxo.clone().unwrap().script_pubkey,
+            ScriptKind::Unvault,
+            3,
+        );
+        scripts.insert(
+            psbt.global.unsigned_tx.output[1].script_pubkey.clone(),
+            ScriptKind::Deposit,
+            7,
+        );
+        let vault = OutPoint::from_str(
+            "f91d0a8a78462bc59398f2c5d7a84fcff491c26ba54c4833478b202796c8aafd:1",
+        )
+        .unwrap();
+        let vaults = vec![(vault, Amount::from_sat(100_000_000), 3)];
+
+        psbt.inputs[0].partial_sigs.insert(
+            PublicKey::from_str(
+                "0202933ab1037ead0aa4a7858c6d49d6705e1fb3bc5f66b00918af7b9f00ecd526",
+            )
+            .unwrap(),
+            "3044".as_bytes().to_vec(),
+        );
+
+        let inspection = Inspection::new(&Decoded::Psbt(psbt.clone()), &vaults, &scripts);
+        assert!(inspection.is_psbt);
+        assert!(!inspection.finalized);
+        assert_eq!(inspection.txid, psbt.global.unsigned_tx.txid());
+        assert_eq!(inspection.lock_time, None);
+        assert_eq!(inspection.vsize, 180);
+        assert_eq!(inspection.fees, Some(Amount::from_sat(5_968)));
+        assert_eq!(inspection.feerate(), Some(33));
+
+        let input = &inspection.inputs[0];
+        assert_eq!(input.amount, Some(Amount::from_sat(99_965_368)));
+        assert_eq!(input.script, Some((ScriptKind::Unvault, 3)));
+        assert_eq!(input.vault, Some(vault));
+        assert_eq!(input.relative_timelock, Some(RelativeTimelock::Blocks(8)));
+        assert_eq!(
+            input.signatures[0].fingerprint,
+            Some(Fingerprint::from_str("251d6009").unwrap())
+        );
+
+        assert_eq!(inspection.outputs.len(), 3);
+        assert_eq!(inspection.outputs[0].script, None);
+        assert_eq!(inspection.outputs[1].script, Some((ScriptKind::Deposit, 7)));
+
+        // The amount of an input of a raw transaction is known only if it is a deposit.
+        let tx = psbt.global.unsigned_tx.clone();
+        let inspection = Inspection::new(&Decoded::Transaction(tx.clone()), &vaults, &scripts);
+        assert!(!inspection.is_psbt);
+        assert_eq!(inspection.inputs[0].amount, None);
+        assert_eq!(inspection.fees, None);
+        let deposit = vec![(
+            tx.input[0].previous_output,
+            Amount::from_sat(100_000_000),
+            0,
+        )];
+        let inspection = Inspection::new(&Decoded::Transaction(tx), &deposit, &scripts);
+        assert_eq!(inspection.inputs[0].script, Some((ScriptKind::Deposit, 0)));
+        assert_eq!(inspection.fees, Some(Amount::from_sat(40_600)));
+    }
+
+    #[test]
+    fn test_timelocks() {
+        assert_eq!(relative_timelock(1, 8), None);
+        assert_eq!(relative_timelock(2, SEQUENCE_FINAL), None);
+        assert_eq!(
+            relative_timelock(2, 144),
+            Some(RelativeTimelock::Blocks(144))
+        );
+        assert_eq!(
+            relative_timelock(2, SEQUENCE_LOCKTIME_TYPE_FLAG | 2),
+            Some(RelativeTimelock::Seconds(1024))
+        );
+
+        let mut tx = Psbt::from_str(PSBT).unwrap().global.unsigned_tx;
+        tx.lock_time = 700_000;
+        assert_eq!(lock_time(&tx), Some(Timelock::Height(700_000)));
+        tx.lock_time = 1_600_000_000;
+        assert_eq!(lock_time(&tx), Some(Timelock::Time(1_600_000_000)));
+        tx.input[0].sequence = SEQUENCE_FINAL;
+        assert_eq!(lock_time(&tx), None);
+    }
+
+    #[test]
+    fn test_derivation_indexes() {
+        let psbt = Psbt::from_str(PSBT).unwrap();
+        let vaults = vec![(OutPoint::default(), Amount::from_sat(1), 5)];
+        let indexes = derivation_indexes(&Decoded::Psbt(psbt), &vaults);
+        assert_eq!(indexes[0], 0);
+        assert_eq!(indexes[1], 5);
+        assert_eq!(indexes.len(), 2 + GAP_LIMIT as usize);
+        assert_eq!(indexes.last(), Some(&(5 + GAP_LIMIT)));
+    }
+}
@@ -0,0 +1,264 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::app::{error::Error, recipients::RowError};
+
+pub const DEFAULT_FILE_NAME: &str = "labels.jsonl";
+
+/// Kind of the labelled reference, as defined by BIP-329.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LabelType {
+    /// A transaction, referenced by its txid.
+    Tx,
+    /// An address.
+    Addr,
+    Pubkey,
+    /// A transaction input, referenced by the outpoint it spends.
+    Input,
+    /// A transaction output, referenced by its outpoint. Vaults are labelled
+    /// with the outpoint of their deposit.
+    Output,
+    Xpub,
+}
+
+/// A line of a BIP-329 export.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Label {
+    #[serde(rename = "type")]
+    pub kind: LabelType,
+    #[serde(rename = "ref")]
+    pub reference: String,
+    #[serde(default)]
+    pub label: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spendable: Option<bool>,
+}
+
+impl Label {
+    pub fn new(kind: LabelType, reference: String, label: String) -> Self {
+        Self {
+            kind,
+            reference,
+            label,
+            origin: None,
+            spendable: None,
+        }
+    }
+}
+
+/// Number of labels merged from an imported file and the errors of the other lines.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportedLabels {
+    pub imported: usize,
+    pub errors: Vec<RowError>,
+}
+
+/// Labels is the local store of the user labels, stored in the network datadir
+/// with the BIP-329 format.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Labels {
+    labels: Vec<Label>,
+}
+
+impl Labels {
+    /// Returns an empty store if the file does not exist yet.
+    pub fn load(network_datadir: &Path) -> Result<Self, Error> {
+        let path = path(network_datadir);
+        match std::fs::read_to_string(&path) {
+            Ok(content) => {
+                let mut labels = Self::default();
+                let imported = labels.import_jsonl(&content);
+                if let Some(e) = imported.errors.first() {
+                    return Err(Error::File(format!(
+                        "Failed to read {}: {}",
+                        path.display(),
+                        e
+                    )));
+                }
+                Ok(labels)
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(Error::File(format!(
+                "Failed to read {}: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+
+    pub fn save(&self, network_datadir: &Path) -> Result<(), Error> {
+        let path = path(network_datadir);
+        std::fs::write(&path, self.to_jsonl())
+            .map_err(|e| Error::File(format!("Failed to write {}: {}", path.display(), e)))
+    }
+
+    pub fn get(&self, kind: LabelType, reference: &str) -> Option<&str> {
+        self.labels
+            .iter()
+            .find(|l| l.kind == kind && l.reference == reference)
+            .map(|l| l.label.as_str())
+            .filter(|label| !label.is_empty())
+    }
+
+    /// Replaces the label with the same type and reference,
+    /// a label without text removes it.
+    pub fn set(&mut self, label: Label) {
+        let position = self
+            .labels
+            .iter()
+            .position(|l| l.kind == label.kind && l.reference == label.reference);
+        match position {
+            Some(i) if label.label.trim().is_empty() => {
+                self.labels.remove(i);
+            }
+            Some(i) => self.labels[i] = label,
+            None if label.label.trim().is_empty() => {}
+            None => self.labels.push(label),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    /// Merges the labels of the BIP-329 lines, the imported ones replace
+    /// the labels with the same type and reference.
+    pub fn import_jsonl(&mut self, content: &str) -> ImportedLabels {
+        let mut imported = ImportedLabels::default();
+        for (i, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<Label>(line) {
+                Ok(label) => {
+                    self.set(label);
+                    imported.imported += 1;
+                }
+                Err(e) => imported.errors.push(RowError {
+                    line: i + 1,
+                    error: e.to_string(),
+                }),
+            }
+        }
+        imported
+    }
+
+    /// Writes one JSON label per line.
+    pub fn to_jsonl(&self) -> String {
+        let mut content = String::new();
+        for label in &self.labels {
+            content.push_str(
+                &serde_json::to_string(label).expect("Label has a proper Serialize implementation"),
+            );
+            content.push('\n');
+        }
+        content
+    }
+}
+
+pub fn path(network_datadir: &Path) -> PathBuf {
+    let mut path = network_datadir.to_path_buf();
+    path.push(DEFAULT_FILE_NAME);
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TXID: &str = "f91d0a8a78462bc59398f2c5d7a84fcff491c26ba54c4833478b202796c8aafd";
+
+    #[test]
+    fn test_labels() {
+        let mut labels = Labels::default();
+        let outpoint = format!("{}:1", TXID);
+        labels.set(Label::new(
+            LabelType::Output,
+            outpoint.clone(),
+            "Treasury".to_string(),
+        ));
+        labels.set(Label::new(
+            LabelType::Tx,
+            TXID.to_string(),
+            "Payroll".to_string(),
+        ));
+        assert_eq!(labels.get(LabelType::Output, &outpoint), Some("Treasury"));
+        assert_eq!(labels.get(LabelType::Tx, TXID), Some("Payroll"));
+        assert_eq!(labels.get(LabelType::Tx, &outpoint), None);
+
+        labels.set(Label::new(
+            LabelType::Tx,
+            TXID.to_string(),
+            "Salaries".to_string(),
+        ));
+        assert_eq!(labels.len(), 2);
+        assert_eq!(labels.get(LabelType::Tx, TXID), Some("Salaries"));
+
+        labels.set(Label::new(LabelType::Tx, TXID.to_string(), " ".to_string()));
+        assert_eq!(labels.len(), 1);
+        assert_eq!(labels.get(LabelType::Tx, TXID), None);
+    }
+
+    #[test]
+    fn test_import_export_jsonl() {
+        let content = format!(
+            "{{\"type\":\"tx\",\"ref\":\"{txid}\",\"label\":\"Payroll\",\"origin\":\"wpkh([d34db33f/84'/0'/0'])\"}}\n\
+            \n\
+            {{\"type\":\"output\",\"ref\":\"{txid}:0\",\"label\":\"Treasury\",\"spendable\":false}}\n\
+            {{\"type\":\"unknown\",\"ref\":\"{txid}\",\"label\":\"a\"}}\n\
+            not json\n",
+            txid = TXID
+        );
+        let mut labels = Labels::default();
+        let imported = labels.import_jsonl(&content);
+        assert_eq!(imported.imported, 2);
+        assert_eq!(
+            imported
+                .errors
+                .iter()
+                .map(|e| e.line)
+                .collect::<Vec<usize>>(),
+            vec![4, 5]
+        );
+
+        let exported = labels.to_jsonl();
+        assert_eq!(
+            exported,
+            format!(
+                "{{\"type\":\"tx\",\"ref\":\"{txid}\",\"label\":\"Payroll\",\"origin\":\"wpkh([d34db33f/84'/0'/0'])\"}}\n\
+                {{\"type\":\"output\",\"ref\":\"{txid}:0\",\"label\":\"Treasury\",\"spendable\":false}}\n",
+                txid = TXID
+            )
+        );
+        let mut reimported = Labels::default();
+        assert!(reimported.import_jsonl(&exported).errors.is_empty());
+        assert_eq!(reimported, labels);
+    }
+
+    #[test]
+    fn save_and_load_labels() {
+        let mut datadir = std::env::temp_dir();
+        datadir.push(format!("revault-gui-labels-{}", std::process::id()));
+        std::fs::create_dir_all(&datadir).unwrap();
+        assert_eq!(Labels::load(&datadir).unwrap(), Labels::default());
+
+        let mut labels = Labels::default();
+        labels.set(Label::new(
+            LabelType::Tx,
+            TXID.to_string(),
+            "Payroll".to_string(),
+        ));
+        labels.save(&datadir).unwrap();
+        assert_eq!(Labels::load(&datadir).unwrap(), labels);
+
+        std::fs::remove_dir_all(datadir).unwrap();
+    }
+}
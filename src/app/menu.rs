@@ -11,6 +11,8 @@ pub enum Menu {
     RevaultVaults,
     DelegateFunds,
     Settings,
+    AddressBook,
+    Inspector,
     Vaults(VaultsMenu),
 }
 
@@ -1,13 +1,21 @@
 use std::sync::Arc;
 
-use bitcoin::{util::psbt::PartiallySignedTransaction as Psbt, OutPoint};
+use bitcoin::{util::psbt::PartiallySignedTransaction as Psbt, OutPoint, Txid};
 use tokio::sync::Mutex;
 
 use revault_hwi::{app::revault::RevaultHWI, HWIError};
 use revaultd::config::Config as DaemonConfig;
 
 use crate::{
-    app::{error::Error, menu::Menu},
+    app::{
+        address_book::AddressBook,
+        coin_selection::CoinSelection,
+        cpfp::CpfpParent,
+        error::Error,
+        labels::{ImportedLabels, Label, Labels},
+        menu::Menu,
+    },
+    bitcoind::{self, BlockchainInfo, FeeEstimates},
     daemon::{
         model::{
             HistoryEvent, HistoryEventKind, ServersStatuses, SpendTx, SpendTxStatus, Vault,
@@ -16,6 +24,7 @@ use crate::{
         RevaultDError,
     },
     revault::Role,
+    server,
 };
 
 impl Into<Message> for Menu {
@@ -53,6 +62,10 @@ pub enum Message {
     Previous,
     DepositAddress(Result<bitcoin::Address, RevaultDError>),
     Recipient(usize, RecipientMessage),
+    RecipientsCsv(RecipientsCsvMessage),
+    AddressBook(AddressBookMessage),
+    Inspector(InspectorMessage),
+    Labels(LabelsMessage),
     Input(usize, InputMessage),
     AddRecipient,
     SpendTransaction(Result<(SpendTx, u64), RevaultDError>),
@@ -65,6 +78,7 @@ pub enum Message {
     Revaulted(Result<(), RevaultDError>),
     Settings(usize, SettingsMessage),
     AddWatchtower,
+    ToggleFingerprintQRCode,
     LoadDaemonConfig(DaemonConfig),
     DaemonConfigLoaded(Result<(), Error>),
 }
@@ -76,14 +90,31 @@ pub enum SettingsMessage {
     FieldEdited(&'static str, String),
     CancelEdit,
     ConfirmEdit,
+    TestConnection,
+    BitcoindTested(Result<BlockchainInfo, bitcoind::Error>),
+    ServerTested(Result<(), server::Error>),
 }
 
 #[derive(Debug, Clone)]
 pub enum SpendTxMessage {
     FeerateEdited(String),
+    FeesEstimated(Result<FeeEstimates, bitcoind::Error>),
     PsbtEdited(String),
+    /// Adds the edited PSBT to the PSBTs to combine.
+    AddPsbt,
+    RemovePsbt(usize),
+    /// Path of the .psbt file to import or export.
+    PsbtPathEdited(String),
+    ImportPsbtFile,
+    PsbtFileImported(Result<Psbt, Error>),
+    ExportPsbtFile,
+    PsbtFileExported(Result<(), Error>),
     Import,
+    /// Imports the PSBT despite the warnings of its report.
+    ConfirmImport,
     Generate,
+    /// Select the inputs of the spend transaction with the given strategy.
+    SelectCoins(CoinSelection),
     /// Select the SpendTxMessage with the given psbt.
     Select(Psbt),
     Sign(SignMessage),
@@ -99,17 +130,23 @@ pub enum SpendTxMessage {
     Update,
     Updated(Result<(), RevaultDError>),
     WithPriority(bool),
+    SelectBumpFee,
+    UnselectBumpFee,
+    Cpfp(CpfpMessage),
+    Label(LabelMessage),
 }
 
 #[derive(Debug, Clone)]
 pub enum HistoryEventMessage {
     OnChainTransactions(Result<Vec<VaultTransactions>, RevaultDError>),
+    Label(LabelMessage),
 }
 
 #[derive(Debug, Clone)]
 pub enum VaultMessage {
     ListOnchainTransaction,
     OnChainTransactions(Result<VaultTransactions, RevaultDError>),
+    Label(LabelMessage),
 }
 
 #[derive(Debug, Clone)]
@@ -117,6 +154,17 @@ pub enum VaultFilterMessage {
     Status(&'static [VaultStatus]),
 }
 
+#[derive(Debug, Clone)]
+pub enum CpfpMessage {
+    UnvaultTxs(Result<Vec<Psbt>, RevaultDError>),
+    /// CPFP outputs of the transactions to bump which are in the mempool.
+    Parents(Result<Vec<CpfpParent>, bitcoind::Error>),
+    FeerateEdited(String),
+    Create,
+    Sign(SignMessage),
+    Broadcasted(Result<Txid, bitcoind::Error>),
+}
+
 #[derive(Debug, Clone)]
 pub enum SignMessage {
     CheckConnection,
@@ -136,4 +184,66 @@ pub enum RecipientMessage {
     Delete,
     AddressEdited(String),
     AmountEdited(String),
+    LabelEdited(String),
+    /// Address and label of the address book entry suggested for the recipient.
+    SuggestionSelected(String, String),
+}
+
+#[derive(Debug, Clone)]
+pub enum AddressBookMessage {
+    Loaded(Result<AddressBook, Error>),
+    LabelEdited(String),
+    AddressEdited(String),
+    NotesEdited(String),
+    Add,
+    /// Fills the form with the entry to edit it.
+    Edit(usize),
+    Delete(usize),
+    Saved(Result<AddressBook, Error>),
+}
+
+#[derive(Debug, Clone)]
+pub enum InspectorMessage {
+    /// Pasted PSBT or raw transaction.
+    InputEdited(String),
+    Inspect,
+    PathEdited(String),
+    ImportFile,
+    /// Content of the imported file.
+    FileImported(Result<Vec<u8>, Error>),
+    Vaults(Result<Vec<Vault>, RevaultDError>),
+}
+
+#[derive(Debug, Clone)]
+pub enum RecipientsCsvMessage {
+    PathEdited(String),
+    Import,
+    /// Content of the imported file.
+    Imported(Result<String, Error>),
+    Export,
+    Exported(Result<(), Error>),
+}
+
+#[derive(Debug, Clone)]
+pub enum LabelMessage {
+    Edit,
+    Edited(String),
+    Cancel,
+    Confirm,
+}
+
+#[derive(Debug, Clone)]
+pub enum LabelsMessage {
+    Loaded(Result<Labels, Error>),
+    /// Replaces the labels in the store of the context and saves it.
+    Set(Vec<Label>),
+    Saved(Result<(), Error>),
+    PathEdited(String),
+    Import,
+    /// Content of the imported file.
+    Imported(Result<String, Error>),
+    /// Result of the merge of the imported file in the store.
+    Merged(ImportedLabels),
+    Export,
+    Exported(Result<(), Error>),
 }
@@ -1,10 +1,21 @@
+pub mod address_book;
+pub mod coin_selection;
 pub mod config;
 pub mod context;
+pub mod cpfp;
+pub mod inspector;
+pub mod labels;
 pub mod menu;
 pub mod message;
+pub mod psbt_file;
+pub mod recipients;
+pub mod signatures;
+pub mod spend_check;
 pub mod state;
 
 mod error;
+#[cfg(test)]
+mod fixtures;
 mod view;
 
 use std::sync::Arc;
@@ -16,12 +27,15 @@ use iced_native::{window, Event};
 pub use config::Config;
 pub use message::{Message, SettingsMessage};
 
+use message::LabelsMessage;
+
 use menu::Menu;
 use state::{
-    DepositState, EmergencyState, HistoryState, ManagerCreateSendTransactionState,
-    ManagerHomeState, ManagerImportSendTransactionState, ManagerSendState, RevaultVaultsState,
-    SettingsState, StakeholderCreateVaultsState, StakeholderDelegateVaultsState,
-    StakeholderHomeState, State, VaultsState,
+    cmd::{load_labels, save_labels},
+    AddressBookState, DepositState, EmergencyState, HistoryState, InspectorState,
+    ManagerCreateSendTransactionState, ManagerHomeState, ManagerImportSendTransactionState,
+    ManagerSendState, RevaultVaultsState, SettingsState, StakeholderCreateVaultsState,
+    StakeholderDelegateVaultsState, StakeholderHomeState, State, VaultsState,
 };
 
 use crate::{app::context::Context, revault::Role};
@@ -39,6 +53,7 @@ pub fn new_state(context: &Context) -> Box<dyn State> {
         (_, Menu::Vaults(menu)) => VaultsState::new(menu).into(),
         (_, Menu::RevaultVaults) => RevaultVaultsState::default().into(),
         (_, Menu::Settings) => SettingsState::new(context).into(),
+        (_, Menu::Inspector) => InspectorState::new().into(),
         (Role::Stakeholder, Menu::Home) => StakeholderHomeState::new().into(),
         (Role::Stakeholder, Menu::CreateVaults) => StakeholderCreateVaultsState::new().into(),
         (Role::Stakeholder, Menu::DelegateFunds) => StakeholderDelegateVaultsState::new().into(),
@@ -47,6 +62,7 @@ pub fn new_state(context: &Context) -> Box<dyn State> {
         (Role::Manager, Menu::Send) => ManagerSendState::new().into(),
         (Role::Manager, Menu::CreateSpend) => ManagerCreateSendTransactionState::new().into(),
         (Role::Manager, Menu::ImportSpend) => ManagerImportSendTransactionState::new().into(),
+        (Role::Manager, Menu::AddressBook) => AddressBookState::new().into(),
 
         // If menu is not available for the role, the user is redirected to Home.
         (Role::Stakeholder, _) => StakeholderHomeState::new().into(),
@@ -57,7 +73,12 @@ pub fn new_state(context: &Context) -> Box<dyn State> {
 impl App {
     pub fn new(context: Context) -> (App, Command<Message>) {
         let state = new_state(&context);
-        let cmd = state.load(&context);
+        let cmd = Command::batch(vec![
+            state.load(&context),
+            Command::perform(load_labels(context.network_datadir()), |res| {
+                Message::Labels(LabelsMessage::Loaded(res))
+            }),
+        ]);
         (
             Self {
                 should_exit: false,
@@ -94,6 +115,13 @@ impl App {
         }
     }
 
+    fn save_labels(&self) -> Command<Message> {
+        Command::perform(
+            save_labels(self.context.network_datadir(), self.context.labels.clone()),
+            |res| Message::Labels(LabelsMessage::Saved(res)),
+        )
+    }
+
     pub fn update(&mut self, message: Message) -> Command<Message> {
         match message {
             Message::Tick => {
@@ -124,6 +152,34 @@ impl App {
                 self.state.load(&self.context)
             }
             Message::Clipboard(text) => clipboard::write(text),
+            Message::Labels(LabelsMessage::Loaded(res)) => {
+                match res {
+                    Ok(labels) => self.context.labels = labels,
+                    Err(e) => log::warn!("Failed to load labels: {}", e),
+                }
+                Command::none()
+            }
+            Message::Labels(LabelsMessage::Set(labels)) => {
+                for label in labels {
+                    self.context.labels.set(label);
+                }
+                self.save_labels()
+            }
+            Message::Labels(LabelsMessage::Imported(Ok(content))) => {
+                let imported = self.context.labels.import_jsonl(&content);
+                Command::batch(vec![
+                    self.save_labels(),
+                    self.state.update(
+                        &self.context,
+                        Message::Labels(LabelsMessage::Merged(imported)),
+                    ),
+                ])
+            }
+            Message::Labels(LabelsMessage::Saved(Err(e))) => {
+                log::warn!("Failed to save labels: {}", e);
+                self.state
+                    .update(&self.context, Message::Labels(LabelsMessage::Saved(Err(e))))
+            }
             Message::Event(Event::Window(window::Event::CloseRequested)) => {
                 self.stop();
                 Command::none()
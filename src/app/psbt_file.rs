@@ -0,0 +1,41 @@
+use bitcoin::{consensus::encode, util::psbt::PartiallySignedTransaction as Psbt};
+
+/// Magic bytes starting a binary PSBT, as defined by BIP-174.
+const MAGIC: &[u8] = b"psbt\xff";
+
+/// Parses the content of a PSBT file, either the binary serialization of BIP-174
+/// or its base64 encoding.
+pub fn decode(content: &[u8]) -> Result<Psbt, String> {
+    if content.starts_with(MAGIC) {
+        return encode::deserialize(content).map_err(|e| format!("invalid PSBT: {}", e));
+    }
+    let text = std::str::from_utf8(content).map_err(|_| "not a PSBT file".to_string())?;
+    let bytes =
+        bitcoin::base64::decode(text.trim()).map_err(|e| format!("invalid base64 PSBT: {}", e))?;
+    encode::deserialize(&bytes).map_err(|e| format!("invalid PSBT: {}", e))
+}
+
+/// Binary serialization of the PSBT, the usual content of a .psbt file.
+pub fn encode(psbt: &Psbt) -> Vec<u8> {
+    encode::serialize(psbt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::fixtures::PSBT;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_decode_psbt_file() {
+        let psbt = Psbt::from_str(PSBT).unwrap();
+        let binary = encode(&psbt);
+        assert!(binary.starts_with(MAGIC));
+        assert_eq!(decode(&binary).unwrap(), psbt);
+        assert_eq!(decode(format!("{}\n", PSBT).as_bytes()).unwrap(), psbt);
+
+        assert!(decode(&binary[..binary.len() - 1]).is_err());
+        assert!(decode(b"cHNidP8B").is_err());
+        assert!(decode(&[0xff, 0xfe, 0x00]).is_err());
+    }
+}
@@ -0,0 +1,221 @@
+use std::str::FromStr;
+
+use bitcoin::{Address, Amount, Denomination, Network};
+
+/// Header of the exported file, an imported file may omit it.
+const HEADER: &str = "address,amount,label";
+
+/// Recipient of a spend transaction, read from a CSV line `address,amount[,label]`
+/// with the amount in BTC.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recipient {
+    pub address: Address,
+    pub amount: Amount,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowError {
+    /// Number of the line in the file, starting at 1.
+    pub line: usize,
+    pub error: String,
+}
+
+impl std::fmt::Display for RowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
+/// Valid recipients of a CSV file and the errors of the other lines.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportedRecipients {
+    pub recipients: Vec<Recipient>,
+    pub errors: Vec<RowError>,
+}
+
+impl ImportedRecipients {
+    pub fn total(&self) -> Amount {
+        self.recipients
+            .iter()
+            .fold(Amount::ZERO, |total, recipient| total + recipient.amount)
+    }
+}
+
+/// Parses the CSV lines, the addresses already in the transaction are given
+/// to reject duplicates.
+pub fn parse_csv(content: &str, network: Network, existing: &[String]) -> ImportedRecipients {
+    let mut imported = ImportedRecipients::default();
+    for (i, line) in content.lines().enumerate() {
+        if line.trim().is_empty() || (i == 0 && line.trim().eq_ignore_ascii_case(HEADER)) {
+            continue;
+        }
+        let fields = split_line(line);
+        match parse_recipient(&fields, network) {
+            Ok(recipient) => {
+                let address = recipient.address.to_string();
+                if existing.contains(&address)
+                    || imported
+                        .recipients
+                        .iter()
+                        .any(|r| r.address == recipient.address)
+                {
+                    imported.errors.push(RowError {
+                        line: i + 1,
+                        error: format!("duplicate address {}", address),
+                    });
+                } else {
+                    imported.recipients.push(recipient);
+                }
+            }
+            Err(error) => imported.errors.push(RowError { line: i + 1, error }),
+        }
+    }
+    imported
+}
+
+fn parse_recipient(fields: &[String], network: Network) -> Result<Recipient, String> {
+    if fields.len() < 2 || fields.len() > 3 {
+        return Err("expected address,amount[,label]".to_string());
+    }
+    let address = Address::from_str(&fields[0])
+        .map_err(|e| format!("invalid address {}: {}", fields[0], e))?;
+    if !is_valid_for_network(&address, network) {
+        return Err(format!("address {} is not for {}", address, network));
+    }
+    let amount = Amount::from_str_in(&fields[1], Denomination::Bitcoin)
+        .map_err(|e| format!("invalid amount {}: {}", fields[1], e))?;
+    if amount <= address.script_pubkey().dust_value() {
+        return Err(format!("amount {} is below the dust limit", fields[1]));
+    }
+    Ok(Recipient {
+        address,
+        amount,
+        label: fields.get(2).cloned().unwrap_or_default(),
+    })
+}
+
+/// Base58 addresses of the test networks are parsed as testnet addresses,
+/// and the bech32 ones of signet as well.
+fn is_valid_for_network(address: &Address, network: Network) -> bool {
+    address.network == network
+        || (address.network == Network::Testnet
+            && (network == Network::Regtest || network == Network::Signet))
+}
+
+/// Splits the comma separated fields, a field may be quoted to hold commas
+/// and a quote is escaped by doubling it.
+fn split_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+fn quote(field: &str) -> String {
+    if field.contains(',') || field.contains('"') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Writes the (address, amount in BTC, label) lines with a header.
+pub fn to_csv(recipients: &[(String, String, String)]) -> String {
+    let mut content = format!("{}\n", HEADER);
+    for (address, amount, label) in recipients {
+        content.push_str(&format!(
+            "{},{},{}\n",
+            quote(address),
+            quote(amount),
+            quote(label)
+        ));
+    }
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS_A: &str = "bcrt1qqypqxpq9qcrsszg2pvxq6rs0zqg3yyc5z5tpwxqergd3c8g7rusq7snjn6";
+    const ADDRESS_B: &str = "bcrt1q9q5j52ev95hz7vp3xgengdfkxuurjw3m58r49z";
+
+    #[test]
+    fn test_parse_csv() {
+        let content = format!(
+            "address,amount,label\n\
+            {a},0.5,\"Vendor, Inc.\"\n\
+            \n\
+            {b},0.00000100\n\
+            {a},0.1,duplicate\n\
+            tb1qwrong,0.1\n\
+            {b},abc\n\
+            bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq,0.1\n\
+            {b}\n",
+            a = ADDRESS_A,
+            b = ADDRESS_B
+        );
+        let imported = parse_csv(&content, Network::Regtest, &[]);
+        assert_eq!(imported.recipients.len(), 1);
+        assert_eq!(imported.recipients[0].address.to_string(), ADDRESS_A);
+        assert_eq!(imported.recipients[0].label, "Vendor, Inc.");
+        assert_eq!(imported.total(), Amount::from_sat(50_000_000));
+        assert_eq!(
+            imported
+                .errors
+                .iter()
+                .map(|e| e.line)
+                .collect::<Vec<usize>>(),
+            vec![4, 5, 6, 7, 8, 9]
+        );
+        // 100 sats is dust.
+        assert!(imported.errors[0].error.contains("dust"));
+        assert!(imported.errors[1].error.contains("duplicate"));
+        assert!(imported.errors[4].error.contains("is not for"));
+
+        let imported = parse_csv(
+            &format!("{},0.5", ADDRESS_A),
+            Network::Regtest,
+            &[ADDRESS_A.to_string()],
+        );
+        assert!(imported.recipients.is_empty());
+        assert_eq!(imported.errors.len(), 1);
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let content = to_csv(&[
+            (
+                ADDRESS_A.to_string(),
+                "0.5".to_string(),
+                "Vendor, \"Inc\"".to_string(),
+            ),
+            (ADDRESS_B.to_string(), "0.1".to_string(), "".to_string()),
+        ]);
+        assert_eq!(
+            content,
+            format!(
+                "address,amount,label\n{},0.5,\"Vendor, \"\"Inc\"\"\"\n{},0.1,\n",
+                ADDRESS_A, ADDRESS_B
+            )
+        );
+        let imported = parse_csv(&content, Network::Regtest, &[]);
+        assert!(imported.errors.is_empty());
+        assert_eq!(imported.recipients[0].label, "Vendor, \"Inc\"");
+        assert_eq!(imported.recipients[1].amount, Amount::from_sat(10_000_000));
+    }
+}
@@ -0,0 +1,135 @@
+use bitcoin::util::{bip32::Fingerprint, psbt::PartiallySignedTransaction as Psbt};
+
+/// SignaturesMatrix is the progress of the managers signing a spend transaction:
+/// which manager signed which input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignaturesMatrix {
+    /// Master fingerprints of the managers, one per row.
+    pub managers: Vec<Fingerprint>,
+    /// `signed[i][j]` is true if the manager `i` signed the input `j`.
+    pub signed: Vec<Vec<bool>>,
+    /// Number of inputs of the transaction, one per column.
+    pub inputs: usize,
+}
+
+impl SignaturesMatrix {
+    /// A manager signed an input if the input has a partial signature of a key
+    /// derived from the manager master fingerprint.
+    pub fn new(managers: &[Fingerprint], psbt: &Psbt) -> Self {
+        let signed = managers
+            .iter()
+            .map(|manager| {
+                psbt.inputs
+                    .iter()
+                    .map(|input| {
+                        input.partial_sigs.keys().any(|key| {
+                            input
+                                .bip32_derivation
+                                .get(key)
+                                .map(|(fingerprint, _)| fingerprint == manager)
+                                .unwrap_or(false)
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+        Self {
+            managers: managers.to_vec(),
+            signed,
+            inputs: psbt.inputs.len(),
+        }
+    }
+
+    /// Number of managers who signed the input.
+    pub fn input_signatures(&self, input: usize) -> usize {
+        self.signed.iter().filter(|row| row[input]).count()
+    }
+
+    /// Number of signatures of the least signed input, the progress of the transaction.
+    pub fn signatures(&self) -> usize {
+        (0..self.inputs)
+            .map(|input| self.input_signatures(input))
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// True if every input is signed by at least the threshold of managers.
+    pub fn is_complete(&self, managers_threshold: usize) -> bool {
+        self.inputs != 0 && self.signatures() >= managers_threshold
+    }
+
+    /// True if the manager signed every input of the transaction.
+    pub fn signed_all(&self, manager: &Fingerprint) -> bool {
+        self.inputs != 0
+            && self
+                .managers
+                .iter()
+                .position(|m| m == manager)
+                .map(|i| self.signed[i].iter().all(|signed| *signed))
+                .unwrap_or(false)
+    }
+
+    /// Managers who signed every input of the transaction.
+    pub fn signers(&self) -> Vec<Fingerprint> {
+        self.managers
+            .iter()
+            .filter(|manager| self.signed_all(manager))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::fixtures::PSBT;
+    use bitcoin::util::ecdsa::PublicKey;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_signatures_matrix() {
+        let mut psbt = Psbt::from_str(PSBT).unwrap();
+        // The psbt has a single input, a second one is added without signature.
+        psbt.global
+            .unsigned_tx
+            .input
+            .push(psbt.global.unsigned_tx.input[0].clone());
+        psbt.inputs.push(psbt.inputs[0].clone());
+
+        let manager = Fingerprint::from_str("251d6009").unwrap();
+        let other = Fingerprint::from_str("d67d7fe9").unwrap();
+        let matrix = SignaturesMatrix::new(&[manager, other], &psbt);
+        assert_eq!(matrix.inputs, 2);
+        assert_eq!(matrix.signatures(), 0);
+        assert!(!matrix.is_complete(1));
+
+        // The key of the first input derived from the fingerprint 251d6009.
+        psbt.inputs[0].partial_sigs.insert(
+            PublicKey::from_str(
+                "0202933ab1037ead0aa4a7858c6d49d6705e1fb3bc5f66b00918af7b9f00ecd526",
+            )
+            .unwrap(),
+            "3044".as_bytes().to_vec(),
+        );
+        let matrix = SignaturesMatrix::new(&[manager, other], &psbt);
+        assert_eq!(matrix.signed, vec![vec![true, false], vec![false, false]]);
+        assert_eq!(matrix.input_signatures(0), 1);
+        assert_eq!(matrix.signatures(), 0);
+        assert!(!matrix.is_complete(1));
+        assert!(!matrix.signed_all(&manager));
+        assert!(matrix.signers().is_empty());
+
+        psbt.inputs[1] = psbt.inputs[0].clone();
+        let matrix = SignaturesMatrix::new(&[manager, other], &psbt);
+        assert_eq!(matrix.signatures(), 1);
+        assert!(matrix.is_complete(1));
+        assert!(!matrix.is_complete(2));
+        assert!(matrix.signed_all(&manager));
+        assert!(!matrix.signed_all(&other));
+        assert_eq!(matrix.signers(), vec![manager]);
+
+        // A signature of an unknown key is not counted.
+        let matrix = SignaturesMatrix::new(&[other], &psbt);
+        assert_eq!(matrix.signatures(), 0);
+    }
+}
@@ -0,0 +1,288 @@
+use std::fmt;
+
+use bitcoin::{util::psbt::PartiallySignedTransaction as Psbt, Amount, OutPoint};
+
+use crate::{
+    app::{
+        coin_selection::estimate_vbytes,
+        inspector::{OwnScripts, ScriptKind},
+    },
+    daemon::model::{outpoint, Vault},
+};
+
+/// Feerate above which the fees of a spend transaction are reported, in sats/vbyte.
+const HIGH_FEERATE: u64 = 200;
+/// Share of the spent amount above which the fees are reported, in percent.
+const HIGH_FEES_PERCENT: u64 = 5;
+
+/// Unvault output of a vault which can be spent by the managers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpendableOutput {
+    /// Deposit outpoint of the vault.
+    pub vault: OutPoint,
+    pub outpoint: OutPoint,
+    pub amount: Amount,
+    pub derivation_index: u32,
+}
+
+impl SpendableOutput {
+    pub fn new(vault: &Vault, unvault_tx: &Psbt) -> Self {
+        let tx = &unvault_tx.global.unsigned_tx;
+        // The output with less value is considered as the cpfp output.
+        let vout = if tx.output.len() == 2 && tx.output[1].value > tx.output[0].value {
+            1
+        } else {
+            0
+        };
+        Self {
+            vault: outpoint(vault),
+            outpoint: OutPoint::new(tx.txid(), vout),
+            amount: Amount::from_sat(tx.output[vout as usize].value),
+            derivation_index: vault.derivation_index.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpendIssue {
+    /// The input does not spend the unvault output of an active or unvaulting vault.
+    UnknownInput(OutPoint),
+    MissingCpfpOutput,
+    /// Index of the output and the reason why it is not expected.
+    UnexpectedOutput(usize, &'static str),
+    HighFees {
+        fees: Amount,
+        feerate: u64,
+    },
+}
+
+impl SpendIssue {
+    /// High fees may be accepted by the user, the other issues prevent the import.
+    pub fn is_blocking(&self) -> bool {
+        !matches!(self, Self::HighFees { .. })
+    }
+}
+
+impl fmt::Display for SpendIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnknownInput(outpoint) => write!(
+                f,
+                "Input {} does not spend the unvault output of an active or unvaulting vault",
+                outpoint
+            ),
+            Self::MissingCpfpOutput => write!(
+                f,
+                "The transaction has no output paying to the CPFP descriptor"
+            ),
+            Self::UnexpectedOutput(i, reason) => write!(f, "Output {} {}", i + 1, reason),
+            Self::HighFees { fees, feerate } => write!(
+                f,
+                "The fees of {} sats ({} sats/vbyte) are unusually high",
+                fees.as_sat(),
+                feerate
+            ),
+        }
+    }
+}
+
+/// SpendReport is the result of the checks of a spend transaction
+/// before it is imported.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpendReport {
+    pub issues: Vec<SpendIssue>,
+    pub cpfp_index: Option<usize>,
+    pub change_index: Option<usize>,
+    /// Fees, if all the inputs are known.
+    pub fees: Option<Amount>,
+    /// Feerate of the estimated size of the signed transaction, in sats/vbyte.
+    pub feerate: Option<u64>,
+}
+
+impl SpendReport {
+    pub fn is_blocking(&self) -> bool {
+        self.issues.iter().any(|issue| issue.is_blocking())
+    }
+}
+
+/// Checks that the inputs spend our unvault outputs and that the outputs paying
+/// to our descriptors are one CPFP output and at most one change output.
+pub fn check_spend(
+    psbt: &Psbt,
+    spendable: &[SpendableOutput],
+    scripts: &OwnScripts,
+) -> SpendReport {
+    let tx = &psbt.global.unsigned_tx;
+    let mut issues = Vec::new();
+
+    let mut inputs_amount = Some(Amount::from_sat(0));
+    for txin in &tx.input {
+        match spendable
+            .iter()
+            .find(|output| output.outpoint == txin.previous_output)
+        {
+            Some(output) => inputs_amount = inputs_amount.map(|amount| amount + output.amount),
+            None => {
+                inputs_amount = None;
+                issues.push(SpendIssue::UnknownInput(txin.previous_output));
+            }
+        }
+    }
+
+    let mut cpfp_index = None;
+    let mut change_index = None;
+    for (i, txout) in tx.output.iter().enumerate() {
+        match scripts.get(&txout.script_pubkey) {
+            Some((ScriptKind::Cpfp, _)) => {
+                if cpfp_index.is_none() {
+                    cpfp_index = Some(i);
+                } else {
+                    issues.push(SpendIssue::UnexpectedOutput(i, "is a second CPFP output"));
+                }
+            }
+            Some((ScriptKind::Deposit, _)) => {
+                if change_index.is_none() {
+                    change_index = Some(i);
+                } else {
+                    issues.push(SpendIssue::UnexpectedOutput(i, "is a second change output"));
+                }
+            }
+            Some((ScriptKind::Unvault, _)) => {
+                issues.push(SpendIssue::UnexpectedOutput(
+                    i,
+                    "pays to the unvault descriptor",
+                ));
+            }
+            None => {
+                if psbt
+                    .outputs
+                    .get(i)
+                    .map(|output| !output.bip32_derivation.is_empty())
+                    .unwrap_or(false)
+                {
+                    issues.push(SpendIssue::UnexpectedOutput(
+                        i,
+                        "is derived from keys but does not match our descriptors",
+                    ));
+                }
+            }
+        }
+    }
+    if cpfp_index.is_none() {
+        issues.push(SpendIssue::MissingCpfpOutput);
+    }
+
+    let outputs_amount: Amount = tx
+        .output
+        .iter()
+        .map(|txout| Amount::from_sat(txout.value))
+        .sum();
+    let fees = inputs_amount.and_then(|amount| amount.checked_sub(outputs_amount));
+    // Every output is counted as is, the change output is optional and the cpfp
+    // output may be missing.
+    let vbytes = estimate_vbytes(tx.input.len(), tx.output.len());
+    let feerate = fees.map(|fees| fees.as_sat() / vbytes);
+    if let (Some(fees), Some(feerate), Some(inputs_amount)) = (fees, feerate, inputs_amount) {
+        if feerate > HIGH_FEERATE
+            || fees.as_sat() * 100 > inputs_amount.as_sat() * HIGH_FEES_PERCENT
+        {
+            issues.push(SpendIssue::HighFees { fees, feerate });
+        }
+    }
+
+    SpendReport {
+        issues,
+        cpfp_index,
+        change_index,
+        fees,
+        feerate,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::fixtures::PSBT;
+    use std::str::FromStr;
+
+    fn spendable(psbt: &Psbt, amount: u64) -> Vec<SpendableOutput> {
+        vec![SpendableOutput {
+            vault: OutPoint::default(),
+            outpoint: psbt.global.unsigned_tx.input[0].previous_output,
+            amount: Amount::from_sat(amount),
+            derivation_index: 0,
+        }]
+    }
+
+    #[test]
+    fn test_check_spend() {
+        let psbt = Psbt::from_str(PSBT).unwrap();
+        let outputs = &psbt.global.unsigned_tx.output;
+        let mut scripts = OwnScripts::default();
+        scripts.insert(outputs[0].script_pubkey.clone(), ScriptKind::Cpfp, 0);
+        scripts.insert(outputs[1].script_pubkey.clone(), ScriptKind::Deposit, 1);
+
+        let report = check_spend(&psbt, &spendable(&psbt, 99_965_368), &scripts);
+        assert_eq!(report.issues, Vec::new());
+        assert_eq!(report.cpfp_index, Some(0));
+        assert_eq!(report.change_index, Some(1));
+        assert_eq!(report.fees, Some(Amount::from_sat(5_968)));
+        assert_eq!(report.feerate, Some(15));
+
+        // The input is not one of our unvault outputs.
+        let report = check_spend(&psbt, &[], &scripts);
+        assert_eq!(
+            report.issues,
+            vec![SpendIssue::UnknownInput(
+                psbt.global.unsigned_tx.input[0].previous_output
+            )]
+        );
+        assert!(report.is_blocking());
+        assert_eq!(report.fees, None);
+
+        // The first output has a key source but is not our CPFP output.
+        let report = check_spend(&psbt, &spendable(&psbt, 99_965_368), &OwnScripts::default());
+        assert_eq!(
+            report.issues,
+            vec![
+                SpendIssue::UnexpectedOutput(
+                    0,
+                    "is derived from keys but does not match our descriptors"
+                ),
+                SpendIssue::MissingCpfpOutput,
+            ]
+        );
+
+        let mut unvault = scripts.clone();
+        unvault.insert(outputs[2].script_pubkey.clone(), ScriptKind::Unvault, 2);
+        let report = check_spend(&psbt, &spendable(&psbt, 99_965_368), &unvault);
+        assert_eq!(
+            report.issues,
+            vec![SpendIssue::UnexpectedOutput(
+                2,
+                "pays to the unvault descriptor"
+            )]
+        );
+
+        let report = check_spend(&psbt, &spendable(&psbt, 200_000_000), &scripts);
+        assert_eq!(
+            report.issues,
+            vec![SpendIssue::HighFees {
+                fees: Amount::from_sat(100_040_600),
+                feerate: 256_514,
+            }]
+        );
+        assert!(!report.is_blocking());
+
+        // A transaction without recipients nor change, only its cpfp output is counted.
+        let mut cpfp_only = psbt.clone();
+        cpfp_only.global.unsigned_tx.output.truncate(1);
+        cpfp_only.outputs.truncate(1);
+        let report = check_spend(&cpfp_only, &spendable(&psbt, 19_648), &scripts);
+        assert_eq!(report.cpfp_index, Some(0));
+        assert_eq!(report.change_index, None);
+        assert_eq!(report.fees, Some(Amount::from_sat(3_040)));
+        // 3_040 sats for 304 vbytes.
+        assert_eq!(report.feerate, Some(10));
+    }
+}
@@ -0,0 +1,186 @@
+use std::convert::From;
+
+use iced::{Command, Element};
+
+use revault_ui::component::form;
+
+use super::{
+    cmd::{load_address_book, save_address_book},
+    State,
+};
+
+use crate::app::{
+    address_book::{AddressBook, Entry},
+    context::Context,
+    error::Error,
+    message::{AddressBookMessage, Message},
+    view::{AddressBookView, LoadingDashboard},
+};
+
+/// AddressBookState lists the known recipients of the manager spends
+/// and edits the address book file of the datadir.
+#[derive(Debug)]
+pub enum AddressBookState {
+    Loading {
+        fail: Option<Error>,
+        view: LoadingDashboard,
+    },
+    Loaded {
+        book: AddressBook,
+        label: form::Value<String>,
+        address: form::Value<String>,
+        notes: form::Value<String>,
+        /// Reason why the entry of the form was not added.
+        form_error: Option<String>,
+        processing: bool,
+        warning: Option<Error>,
+        view: AddressBookView,
+    },
+}
+
+impl AddressBookState {
+    pub fn new() -> Self {
+        AddressBookState::Loading {
+            fail: None,
+            view: LoadingDashboard::default(),
+        }
+    }
+
+    fn loaded(book: AddressBook) -> Self {
+        AddressBookState::Loaded {
+            book,
+            label: form::Value::default(),
+            address: form::Value::default(),
+            notes: form::Value::default(),
+            form_error: None,
+            processing: false,
+            warning: None,
+            view: AddressBookView::default(),
+        }
+    }
+}
+
+impl State for AddressBookState {
+    fn update(&mut self, ctx: &Context, message: Message) -> Command<Message> {
+        match self {
+            Self::Loading { fail, .. } => {
+                if let Message::AddressBook(AddressBookMessage::Loaded(res)) = message {
+                    match res {
+                        Ok(book) => *self = Self::loaded(book),
+                        Err(e) => *fail = Some(e),
+                    }
+                }
+            }
+            Self::Loaded {
+                book,
+                label,
+                address,
+                notes,
+                form_error,
+                processing,
+                warning,
+                ..
+            } => match message {
+                Message::Reload => return self.load(ctx),
+                Message::AddressBook(msg) => match msg {
+                    AddressBookMessage::Loaded(res) => match res {
+                        Ok(b) => *book = b,
+                        Err(e) => *warning = Some(e),
+                    },
+                    AddressBookMessage::LabelEdited(value) => {
+                        label.value = value;
+                        label.valid = true;
+                    }
+                    AddressBookMessage::AddressEdited(value) => {
+                        address.value = value;
+                        address.valid = true;
+                    }
+                    AddressBookMessage::NotesEdited(value) => notes.value = value,
+                    AddressBookMessage::Edit(i) => {
+                        if let Some(entry) = book.entries.get(i) {
+                            label.value = entry.label.clone();
+                            address.value = entry.address.clone();
+                            notes.value = entry.notes.clone();
+                        }
+                    }
+                    AddressBookMessage::Add => {
+                        let mut new_book = book.clone();
+                        let entry = Entry {
+                            label: label.value.trim().to_string(),
+                            address: address.value.trim().to_string(),
+                            network: ctx.network(),
+                            notes: notes.value.trim().to_string(),
+                        };
+                        if let Err(e) = new_book.insert(entry) {
+                            *form_error = Some(e);
+                            return Command::none();
+                        }
+                        *form_error = None;
+                        *processing = true;
+                        return Command::perform(
+                            save_address_book(ctx.network_datadir(), new_book),
+                            |res| Message::AddressBook(AddressBookMessage::Saved(res)),
+                        );
+                    }
+                    AddressBookMessage::Delete(i) => {
+                        if let Some(entry) = book.entries.get(i) {
+                            let mut new_book = book.clone();
+                            new_book.remove(&entry.address);
+                            *processing = true;
+                            return Command::perform(
+                                save_address_book(ctx.network_datadir(), new_book),
+                                |res| Message::AddressBook(AddressBookMessage::Saved(res)),
+                            );
+                        }
+                    }
+                    AddressBookMessage::Saved(res) => {
+                        *processing = false;
+                        match res {
+                            Ok(b) => *self = Self::loaded(b),
+                            Err(e) => *warning = Some(e),
+                        }
+                    }
+                },
+                _ => {}
+            },
+        };
+        Command::none()
+    }
+
+    fn view(&mut self, ctx: &Context) -> Element<Message> {
+        match self {
+            Self::Loading { fail, view } => view.view(ctx, fail.as_ref()),
+            Self::Loaded {
+                book,
+                label,
+                address,
+                notes,
+                form_error,
+                processing,
+                warning,
+                view,
+            } => view.view(
+                ctx,
+                warning.as_ref(),
+                &book.entries,
+                label,
+                address,
+                notes,
+                form_error.as_ref(),
+                *processing,
+            ),
+        }
+    }
+
+    fn load(&self, ctx: &Context) -> Command<Message> {
+        Command::perform(load_address_book(ctx.network_datadir()), |res| {
+            Message::AddressBook(AddressBookMessage::Loaded(res))
+        })
+    }
+}
+
+impl From<AddressBookState> for Box<dyn State> {
+    fn from(s: AddressBookState) -> Box<dyn State> {
+        Box::new(s)
+    }
+}
@@ -1,10 +1,18 @@
 use bitcoin::{util::psbt::PartiallySignedTransaction as Psbt, OutPoint, Txid};
+use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
 
+use revaultd::revault_tx::transactions::RevaultTransaction;
+
+use crate::app::{
+    address_book::AddressBook, cpfp::CpfpParent, error::Error, labels::Labels, psbt_file,
+};
+use crate::bitcoind;
 use crate::daemon::{
     model::{
-        RevocationTransactions, ServersStatuses, SpendTx, SpendTxStatus, Vault, VaultStatus,
-        VaultTransactions,
+        outpoint, RevocationTransactions, ServersStatuses, SpendTx, SpendTxStatus, Vault,
+        VaultStatus, VaultTransactions,
     },
     Daemon, RevaultDError,
 };
@@ -60,6 +68,68 @@ pub async fn set_unvault_tx(
     revaultd.set_unvault_tx(&outpoint, &unvault_tx)
 }
 
+/// Lists the vaults with the given statuses along with their unvault transaction.
+pub async fn list_vaults_with_unvault_tx(
+    revaultd: Arc<dyn Daemon + Send + Sync>,
+    statuses: &[VaultStatus],
+) -> Result<Vec<(Vault, Psbt)>, RevaultDError> {
+    let vaults = revaultd.list_vaults(Some(statuses), None)?;
+    let outpoints: Vec<OutPoint> = vaults.iter().map(outpoint).collect();
+    let txs = revaultd.list_presigned_transactions(&outpoints)?;
+    vaults
+        .into_iter()
+        .map(|vault| {
+            let tx = txs
+                .iter()
+                .find_map(|txs| {
+                    if txs.vault_outpoint == outpoint(&vault) {
+                        Some(txs.unvault.clone().into_psbt())
+                    } else {
+                        None
+                    }
+                })
+                .ok_or_else(|| {
+                    RevaultDError::Unexpected(format!(
+                        "no presigned transactions for vault {}",
+                        outpoint(&vault)
+                    ))
+                })?;
+            Ok((vault, tx))
+        })
+        .collect()
+}
+
+/// Lists the unvault transactions of the given vaults.
+pub async fn list_unvault_txs(
+    revaultd: Arc<dyn Daemon + Send + Sync>,
+    outpoints: Vec<OutPoint>,
+) -> Result<Vec<Psbt>, RevaultDError> {
+    let txs = revaultd.list_presigned_transactions(&outpoints)?;
+    Ok(txs.into_iter().map(|txs| txs.unvault.into_psbt()).collect())
+}
+
+/// Returns the given CPFP outputs of the transactions which are still in the mempool.
+pub async fn load_cpfp_parents(
+    cookie_path: PathBuf,
+    addr: SocketAddr,
+    txs: Vec<(Psbt, usize)>,
+) -> Result<Vec<CpfpParent>, bitcoind::Error> {
+    let mut parents = Vec::new();
+    for (psbt, vout) in txs {
+        let txid = psbt.global.unsigned_tx.txid();
+        if let Some(entry) = bitcoind::get_mempool_entry(&cookie_path, addr, &txid).await? {
+            parents.push(CpfpParent {
+                txid,
+                vout: vout as u32,
+                txout: psbt.global.unsigned_tx.output[vout].clone(),
+                output: psbt.outputs[vout].clone(),
+                entry,
+            });
+        }
+    }
+    Ok(parents)
+}
+
 pub async fn update_spend_tx(
     revaultd: Arc<dyn Daemon + Send + Sync>,
     psbt: Psbt,
@@ -98,3 +168,58 @@ pub async fn get_server_status(
 ) -> Result<ServersStatuses, RevaultDError> {
     revaultd.get_server_status()
 }
+
+pub async fn read_file(path: PathBuf) -> Result<String, Error> {
+    std::fs::read_to_string(&path)
+        .map_err(|e| Error::File(format!("Failed to read {}: {}", path.display(), e)))
+}
+
+pub async fn write_file(path: PathBuf, content: String) -> Result<(), Error> {
+    std::fs::write(&path, content)
+        .map_err(|e| Error::File(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+pub async fn read_psbt_file(path: PathBuf) -> Result<Psbt, Error> {
+    let content = std::fs::read(&path)
+        .map_err(|e| Error::File(format!("Failed to read {}: {}", path.display(), e)))?;
+    psbt_file::decode(&content)
+        .map_err(|e| Error::File(format!("Failed to read {}: {}", path.display(), e)))
+}
+
+pub async fn read_binary_file(path: PathBuf) -> Result<Vec<u8>, Error> {
+    std::fs::read(&path)
+        .map_err(|e| Error::File(format!("Failed to read {}: {}", path.display(), e)))
+}
+
+pub async fn write_psbt_file(path: PathBuf, psbt: Psbt) -> Result<(), Error> {
+    std::fs::write(&path, psbt_file::encode(&psbt))
+        .map_err(|e| Error::File(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+pub async fn load_address_book(network_datadir: Option<PathBuf>) -> Result<AddressBook, Error> {
+    let network_datadir =
+        network_datadir.ok_or_else(|| Error::File("Datadir not found".to_string()))?;
+    AddressBook::load(&network_datadir)
+}
+
+pub async fn save_address_book(
+    network_datadir: Option<PathBuf>,
+    book: AddressBook,
+) -> Result<AddressBook, Error> {
+    let network_datadir =
+        network_datadir.ok_or_else(|| Error::File("Datadir not found".to_string()))?;
+    book.save(&network_datadir)?;
+    Ok(book)
+}
+
+pub async fn load_labels(network_datadir: Option<PathBuf>) -> Result<Labels, Error> {
+    let network_datadir =
+        network_datadir.ok_or_else(|| Error::File("Datadir not found".to_string()))?;
+    Labels::load(&network_datadir)
+}
+
+pub async fn save_labels(network_datadir: Option<PathBuf>, labels: Labels) -> Result<(), Error> {
+    let network_datadir =
+        network_datadir.ok_or_else(|| Error::File("Datadir not found".to_string()))?;
+    labels.save(&network_datadir)
+}
@@ -3,15 +3,17 @@ use std::time::{SystemTime, UNIX_EPOCH};
 
 use iced::{Command, Element};
 
-use super::State;
+use super::{label::LabelEditor, State};
 
 use crate::{
     app::{
         context::Context,
         error::Error,
         message::{HistoryEventMessage, Message},
-        view::LoadingDashboard,
-        view::{HistoryEventListItemView, HistoryEventView, HistoryView},
+        view::{
+            history::{event_label_kind, event_label_reference},
+            HistoryEventListItemView, HistoryEventView, HistoryView, LoadingDashboard,
+        },
     },
     daemon::model::{HistoryEvent, HistoryEventKind, VaultTransactions, ALL_HISTORY_EVENTS},
 };
@@ -93,7 +95,7 @@ impl State for HistoryState {
                 }
                 Message::HistoryEvent(msg) => {
                     if let Some(event) = selected_event {
-                        event.update(msg)
+                        return event.update(ctx, msg);
                     }
                 }
                 Message::Close => {
@@ -285,12 +287,14 @@ pub struct HistoryEventState {
     event: HistoryEvent,
     txs: Vec<VaultTransactions>,
     loading_fail: Option<Error>,
+    label: LabelEditor,
     view: HistoryEventView,
 }
 
 impl HistoryEventState {
     pub fn new(event: HistoryEvent) -> Self {
         Self {
+            label: LabelEditor::new(event_label_kind(&event), event_label_reference(&event)),
             event,
             txs: Vec::new(),
             loading_fail: None,
@@ -298,17 +302,27 @@ impl HistoryEventState {
         }
     }
 
-    pub fn update(&mut self, message: HistoryEventMessage) {
-        let HistoryEventMessage::OnChainTransactions(res) = message;
-        match res {
-            Ok(txs) => self.txs = txs,
-            Err(e) => self.loading_fail = Some(e.into()),
+    pub fn update(&mut self, ctx: &Context, message: HistoryEventMessage) -> Command<Message> {
+        match message {
+            HistoryEventMessage::OnChainTransactions(res) => match res {
+                Ok(txs) => self.txs = txs,
+                Err(e) => self.loading_fail = Some(e.into()),
+            },
+            HistoryEventMessage::Label(msg) => return self.label.update(ctx, msg),
         }
+        Command::none()
     }
 
     pub fn view(&mut self, ctx: &Context) -> Element<Message> {
-        self.view
-            .view(ctx, &self.event, &self.txs, self.loading_fail.as_ref())
+        self.view.view(
+            ctx,
+            &self.event,
+            &self.txs,
+            self.label
+                .view(ctx)
+                .map(|msg| Message::HistoryEvent(HistoryEventMessage::Label(msg))),
+            self.loading_fail.as_ref(),
+        )
     }
 
     pub fn load(&self, ctx: &Context) -> Command<Message> {
@@ -0,0 +1,161 @@
+use std::convert::From;
+use std::path::PathBuf;
+
+use bitcoin::{Amount, OutPoint};
+use iced::{Command, Element};
+use iced_native::{window, Event};
+
+use revault_ui::component::form;
+
+use super::{
+    cmd::{list_vaults, read_binary_file},
+    State,
+};
+
+use crate::{
+    app::{
+        context::Context,
+        error::Error,
+        inspector::{decode, derivation_indexes, Decoded, Inspection},
+        message::{InspectorMessage, Message},
+        view::InspectorView,
+    },
+    daemon::model::outpoint,
+};
+
+/// InspectorState decodes a pasted or imported PSBT or raw transaction
+/// without the network, only the vaults are asked to the daemon.
+#[derive(Debug)]
+pub struct InspectorState {
+    input: form::Value<String>,
+    /// Path of the file to inspect.
+    path: form::Value<String>,
+    /// Deposit outpoint, amount and derivation index of the vaults.
+    vaults: Vec<(OutPoint, Amount, u32)>,
+    decoded: Option<Decoded>,
+    inspection: Option<Inspection>,
+    /// Reason why the input could not be decoded.
+    error: Option<String>,
+    warning: Option<Error>,
+    view: InspectorView,
+}
+
+impl InspectorState {
+    pub fn new() -> Self {
+        Self {
+            input: form::Value::default(),
+            path: form::Value::default(),
+            vaults: Vec::new(),
+            decoded: None,
+            inspection: None,
+            error: None,
+            warning: None,
+            view: InspectorView::default(),
+        }
+    }
+
+    fn inspect(&mut self, ctx: &Context, content: &[u8]) {
+        match decode(content) {
+            Ok(decoded) => {
+                self.error = None;
+                self.decoded = Some(decoded);
+                self.update_inspection(ctx);
+            }
+            Err(e) => {
+                self.error = Some(e);
+                self.decoded = None;
+                self.inspection = None;
+            }
+        }
+    }
+
+    fn update_inspection(&mut self, ctx: &Context) {
+        if let Some(decoded) = &self.decoded {
+            let scripts = ctx.own_scripts(&derivation_indexes(decoded, &self.vaults));
+            self.inspection = Some(Inspection::new(decoded, &self.vaults, &scripts));
+        }
+    }
+}
+
+impl State for InspectorState {
+    fn update(&mut self, ctx: &Context, message: Message) -> Command<Message> {
+        match message {
+            Message::Reload => return self.load(ctx),
+            Message::Inspector(msg) => match msg {
+                InspectorMessage::InputEdited(value) => {
+                    self.input.value = value;
+                    self.input.valid = true;
+                }
+                InspectorMessage::Inspect => {
+                    let input = self.input.value.clone();
+                    self.inspect(ctx, input.trim().as_bytes());
+                    self.input.valid = self.error.is_none();
+                }
+                InspectorMessage::PathEdited(path) => {
+                    self.path.value = path;
+                    self.path.valid = true;
+                }
+                InspectorMessage::ImportFile => {
+                    self.warning = None;
+                    return Command::perform(
+                        read_binary_file(PathBuf::from(&self.path.value)),
+                        |res| Message::Inspector(InspectorMessage::FileImported(res)),
+                    );
+                }
+                InspectorMessage::FileImported(res) => match res {
+                    Ok(content) => {
+                        self.inspect(ctx, &content);
+                        self.path.valid = self.error.is_none();
+                    }
+                    Err(e) => {
+                        self.path.valid = false;
+                        self.warning = Some(e);
+                    }
+                },
+                InspectorMessage::Vaults(res) => match res {
+                    Ok(vaults) => {
+                        self.vaults = vaults
+                            .iter()
+                            .map(|vault| {
+                                (outpoint(vault), vault.amount, vault.derivation_index.into())
+                            })
+                            .collect();
+                        self.update_inspection(ctx);
+                    }
+                    // The transaction is still decoded, without the vaults.
+                    Err(e) => self.warning = Some(e.into()),
+                },
+            },
+            Message::Event(Event::Window(window::Event::FileDropped(path))) => {
+                self.path.value = path.to_string_lossy().to_string();
+                self.path.valid = true;
+                return self.update(ctx, Message::Inspector(InspectorMessage::ImportFile));
+            }
+            _ => {}
+        };
+        Command::none()
+    }
+
+    fn view(&mut self, ctx: &Context) -> Element<Message> {
+        self.view.view(
+            ctx,
+            self.warning.as_ref(),
+            &self.input,
+            &self.path,
+            self.error.as_ref(),
+            self.inspection.as_ref(),
+        )
+    }
+
+    fn load(&self, ctx: &Context) -> Command<Message> {
+        Command::perform(list_vaults(ctx.revaultd.clone(), None, None), |res| {
+            Message::Inspector(InspectorMessage::Vaults(res))
+        })
+    }
+}
+
+impl From<InspectorState> for Box<dyn State> {
+    fn from(s: InspectorState) -> Box<dyn State> {
+        Box::new(s)
+    }
+}
@@ -0,0 +1,73 @@
+use iced::{Command, Element};
+
+use revault_ui::component::form;
+
+use crate::app::{
+    context::Context,
+    labels::{Label, LabelType},
+    message::{LabelMessage, LabelsMessage, Message},
+    view::LabelEditorView,
+};
+
+/// LabelEditor edits the label of a reference, the label is saved
+/// in the store of the context once confirmed.
+#[derive(Debug)]
+pub struct LabelEditor {
+    kind: LabelType,
+    reference: String,
+    /// Value of the label being edited.
+    editing: Option<form::Value<String>>,
+    view: LabelEditorView,
+}
+
+impl LabelEditor {
+    pub fn new(kind: LabelType, reference: String) -> Self {
+        Self {
+            kind,
+            reference,
+            editing: None,
+            view: LabelEditorView::default(),
+        }
+    }
+
+    pub fn update(&mut self, ctx: &Context, message: LabelMessage) -> Command<Message> {
+        match message {
+            LabelMessage::Edit => {
+                self.editing = Some(form::Value {
+                    value: ctx
+                        .labels
+                        .get(self.kind, &self.reference)
+                        .unwrap_or_default()
+                        .to_string(),
+                    valid: true,
+                });
+            }
+            LabelMessage::Edited(value) => {
+                if let Some(editing) = &mut self.editing {
+                    editing.value = value;
+                }
+            }
+            LabelMessage::Cancel => self.editing = None,
+            LabelMessage::Confirm => {
+                if let Some(editing) = self.editing.take() {
+                    let label = Label::new(
+                        self.kind,
+                        self.reference.clone(),
+                        editing.value.trim().to_string(),
+                    );
+                    return Command::perform(async move { label }, |label| {
+                        Message::Labels(LabelsMessage::Set(vec![label]))
+                    });
+                }
+            }
+        }
+        Command::none()
+    }
+
+    pub fn view(&mut self, ctx: &Context) -> Element<LabelMessage> {
+        self.view.view(
+            ctx.labels.get(self.kind, &self.reference),
+            self.editing.as_ref(),
+        )
+    }
+}
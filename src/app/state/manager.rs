@@ -1,14 +1,19 @@
 use std::collections::{BTreeMap, HashMap};
 use std::convert::From;
 use std::convert::TryInto;
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use bitcoin::{consensus::encode, util::psbt::PartiallySignedTransaction as Psbt, OutPoint};
 use iced::{Command, Element, Subscription};
+use iced_native::{window, Event};
 
 use super::{
-    cmd::{list_spend_txs, list_vaults, update_spend_tx},
+    cmd::{
+        list_spend_txs, list_vaults, list_vaults_with_unvault_tx, load_address_book, read_file,
+        read_psbt_file, update_spend_tx, write_file,
+    },
     vault::{Vault, VaultListItem},
     State,
 };
@@ -21,10 +26,21 @@ use crate::daemon::model::{
 use revault_ui::component::form;
 use revaultd::revault_tx::transactions::{RevaultTransaction, SpendTransaction};
 
+use crate::bitcoind::{self, FeeEstimates};
+
 use crate::app::{
+    address_book::AddressBook,
+    coin_selection::{estimate_fees, select_coins, Candidate, CoinSelection},
     context::Context,
     error::Error,
-    message::{InputMessage, Message, RecipientMessage, SpendTxMessage},
+    inspector::{derivation_indexes, Decoded},
+    labels::{Label, LabelType},
+    message::{
+        AddressBookMessage, InputMessage, LabelsMessage, Message, RecipientMessage,
+        RecipientsCsvMessage, SpendTxMessage,
+    },
+    recipients::{parse_csv, to_csv, ImportedRecipients, Recipient},
+    spend_check::{check_spend, SpendReport, SpendableOutput},
     state::{
         history::{HistoryEventListItemState, HistoryEventState},
         sign::{Signer, SpendTransactionTarget},
@@ -201,7 +217,7 @@ impl State for ManagerHomeState {
                 }
                 Message::Vault(msg) => {
                     if let Some(selected) = selected_vault {
-                        return selected.update(ctx, msg).map(Message::Vault);
+                        return selected.update(ctx, msg);
                     }
                 }
                 Message::HistoryEvents(res) => match res {
@@ -381,6 +397,14 @@ impl State for ManagerSendState {
                         return tx.update(ctx, Message::SpendTx(msg));
                     }
                 }
+                Message::Event(Event::Window(window::Event::FileDropped(path))) => {
+                    if let Some(tx) = selected_tx {
+                        return tx.update(
+                            ctx,
+                            Message::Event(Event::Window(window::Event::FileDropped(path))),
+                        );
+                    }
+                }
                 _ => {}
             },
         }
@@ -440,6 +464,14 @@ impl From<ManagerSendState> for Box<dyn State> {
 pub struct ManagerImportSendTransactionState {
     psbt_imported: Option<SpendTransaction>,
     psbt_input: form::Value<String>,
+    /// Path of the .psbt file to import, set as well by a file dropped on the window.
+    psbt_path: form::Value<String>,
+    /// Unvault outputs of the active and unvaulting vaults, once loaded.
+    spendable: Option<Vec<SpendableOutput>>,
+    /// The vaults failed to load, the import waits for a reload.
+    load_failed: bool,
+    /// Report of the checks of the PSBT, kept if it has issues.
+    report: Option<SpendReport>,
     warning: Option<Error>,
 
     view: ManagerImportTransactionView,
@@ -450,6 +482,10 @@ impl ManagerImportSendTransactionState {
         Self {
             psbt_imported: None,
             psbt_input: form::Value::default(),
+            psbt_path: form::Value::default(),
+            spendable: None,
+            load_failed: false,
+            report: None,
             warning: None,
             view: ManagerImportTransactionView::new(),
         }
@@ -462,26 +498,71 @@ impl ManagerImportSendTransactionState {
     pub fn imported_state(&self) -> &Option<SpendTransaction> {
         &self.psbt_imported
     }
+
+    /// Checks the inputs and outputs of the spend transaction against the vaults
+    /// and the descriptors of the setup.
+    fn check(&self, ctx: &Context, psbt: &Psbt, spendable: &[SpendableOutput]) -> SpendReport {
+        let vaults: Vec<(OutPoint, bitcoin::Amount, u32)> = spendable
+            .iter()
+            .map(|output| (output.vault, output.amount, output.derivation_index))
+            .collect();
+        let scripts = ctx.own_scripts(&derivation_indexes(&Decoded::Psbt(psbt.clone()), &vaults));
+        check_spend(psbt, spendable, &scripts)
+    }
+
+    fn import(&self, ctx: &Context, spend_tx: SpendTransaction) -> Command<Message> {
+        Command::perform(
+            update_spend_tx(ctx.revaultd.clone(), spend_tx.into_psbt()),
+            |res| Message::SpendTx(SpendTxMessage::Updated(res)),
+        )
+    }
 }
 
 impl State for ManagerImportSendTransactionState {
     fn update(&mut self, ctx: &Context, message: Message) -> Command<Message> {
         match message {
+            Message::Reload => {
+                self.load_failed = false;
+                self.warning = None;
+                return self.load(ctx);
+            }
+            Message::VaultsWithUnvaultTx(res) => match res {
+                Ok(vaults) => {
+                    self.spendable = Some(
+                        vaults
+                            .iter()
+                            .map(|(vault, unvault_tx)| SpendableOutput::new(vault, unvault_tx))
+                            .collect(),
+                    );
+                }
+                Err(e) => {
+                    self.load_failed = true;
+                    self.warning = Some(Error::from(e));
+                }
+            },
             Message::SpendTx(SpendTxMessage::Updated(res)) => match res {
                 Ok(()) => self.psbt_imported = self.parse_pbst(),
                 Err(e) => self.warning = Some(Error::from(e)),
             },
             Message::SpendTx(SpendTxMessage::PsbtEdited(psbt)) => {
                 self.warning = None;
+                self.report = None;
                 self.psbt_input.value = psbt;
             }
             Message::SpendTx(SpendTxMessage::Import) => {
+                self.report = None;
                 if !self.psbt_input.value.is_empty() {
                     if let Some(spend_tx) = self.parse_pbst() {
-                        return Command::perform(
-                            update_spend_tx(ctx.revaultd.clone(), spend_tx.into_psbt()),
-                            |res| Message::SpendTx(SpendTxMessage::Updated(res)),
-                        );
+                        // The import is disabled while the vaults are loading.
+                        let spendable = match &self.spendable {
+                            Some(spendable) => spendable,
+                            None => return Command::none(),
+                        };
+                        let report = self.check(ctx, spend_tx.psbt(), spendable);
+                        if report.issues.is_empty() {
+                            return self.import(ctx, spend_tx);
+                        }
+                        self.report = Some(report);
                     } else {
                         self.psbt_input.valid = false;
                     }
@@ -489,6 +570,44 @@ impl State for ManagerImportSendTransactionState {
                     self.psbt_input.valid = false;
                 }
             }
+            Message::SpendTx(SpendTxMessage::ConfirmImport) => {
+                if let Some(report) = &self.report {
+                    if !report.is_blocking() {
+                        if let Some(spend_tx) = self.parse_pbst() {
+                            return self.import(ctx, spend_tx);
+                        }
+                    }
+                }
+            }
+            Message::SpendTx(SpendTxMessage::PsbtPathEdited(path)) => {
+                self.psbt_path.value = path;
+                self.psbt_path.valid = true;
+            }
+            Message::SpendTx(SpendTxMessage::ImportPsbtFile) => {
+                self.warning = None;
+                return Command::perform(
+                    read_psbt_file(PathBuf::from(&self.psbt_path.value)),
+                    |res| Message::SpendTx(SpendTxMessage::PsbtFileImported(res)),
+                );
+            }
+            Message::SpendTx(SpendTxMessage::PsbtFileImported(res)) => match res {
+                Ok(psbt) => {
+                    self.psbt_input.value = psbt.to_string();
+                    self.psbt_input.valid = true;
+                    return self.update(ctx, Message::SpendTx(SpendTxMessage::Import));
+                }
+                Err(e) => {
+                    self.psbt_path.valid = false;
+                    self.warning = Some(e);
+                }
+            },
+            Message::Event(Event::Window(window::Event::FileDropped(path))) => {
+                if self.psbt_imported.is_none() {
+                    self.psbt_path.value = path.to_string_lossy().to_string();
+                    self.psbt_path.valid = true;
+                    return self.update(ctx, Message::SpendTx(SpendTxMessage::ImportPsbtFile));
+                }
+            }
             _ => {}
         }
         Command::none()
@@ -498,13 +617,23 @@ impl State for ManagerImportSendTransactionState {
         self.view.view(
             ctx,
             &self.psbt_input,
+            &self.psbt_path,
             self.psbt_imported.as_ref().map(|spend_tx| spend_tx.psbt()),
+            self.report.as_ref(),
+            self.spendable.is_none(),
+            self.load_failed,
             self.warning.as_ref(),
         )
     }
 
-    fn load(&self, _ctx: &Context) -> Command<Message> {
-        Command::none()
+    fn load(&self, ctx: &Context) -> Command<Message> {
+        Command::perform(
+            list_vaults_with_unvault_tx(
+                ctx.revaultd.clone(),
+                &[VaultStatus::Active, VaultStatus::Unvaulting],
+            ),
+            Message::VaultsWithUnvaultTx,
+        )
     }
 }
 
@@ -532,7 +661,19 @@ pub struct ManagerCreateSendTransactionState {
 
     inputs: Vec<ManagerSendInput>,
     outputs: Vec<ManagerSendOutput>,
+    /// Known recipients suggested in the recipient form.
+    address_book: AddressBook,
+    /// Path of the CSV file the recipients are imported from or exported to.
+    csv_path: form::Value<String>,
+    /// Summary of the last import.
+    csv_import: Option<ImportedRecipients>,
+    csv_exported: bool,
     feerate: Option<u64>,
+    /// Feerate presets of bitcoind, None until they are received.
+    fee_estimates: Option<Result<FeeEstimates, bitcoind::Error>>,
+    /// Strategy of the current selection of inputs, None if the inputs
+    /// were selected by hand.
+    coin_selection: Option<CoinSelection>,
     processing: bool,
     valid_feerate: bool,
 
@@ -548,7 +689,13 @@ impl ManagerCreateSendTransactionState {
             warning: None,
             inputs: Vec::new(),
             outputs: vec![ManagerSendOutput::new()],
+            address_book: AddressBook::default(),
+            csv_path: form::Value::default(),
+            csv_import: None,
+            csv_exported: false,
             feerate: None,
+            fee_estimates: None,
+            coin_selection: None,
             tx: None,
             processing: false,
             valid_feerate: false,
@@ -584,6 +731,83 @@ impl ManagerCreateSendTransactionState {
         output_amount
     }
 
+    /// Estimated fees of the transaction spending the selected inputs.
+    pub fn estimated_fees(&self) -> u64 {
+        estimate_fees(
+            self.feerate.unwrap_or(0),
+            self.inputs.iter().filter(|input| input.selected).count(),
+            self.outputs.len(),
+        )
+    }
+
+    /// Selects the inputs covering the outputs and the estimated fees with the given strategy,
+    /// the selection is left untouched if the vaults cannot cover them.
+    pub fn select_coins(&mut self, strategy: CoinSelection) {
+        let candidates: Vec<Candidate> = self
+            .inputs
+            .iter()
+            .map(|input| Candidate {
+                amount: input.unvault_output_amount.as_sat(),
+                derivation_index: input.vault.derivation_index.into(),
+            })
+            .collect();
+        let feerate = self.feerate.unwrap_or(0);
+        let number_of_recipients = self.outputs.len();
+        match select_coins(strategy, &candidates, self.output_amount(), |inputs| {
+            estimate_fees(feerate, inputs, number_of_recipients)
+        }) {
+            Some(selected) => {
+                for (i, input) in self.inputs.iter_mut().enumerate() {
+                    input.selected = selected.contains(&i);
+                }
+                self.coin_selection = Some(strategy);
+                self.warning = None;
+            }
+            None => {
+                self.warning = Some(Error::Unexpected(
+                    "The active vaults do not cover the amount and the fees".to_string(),
+                ));
+            }
+        }
+    }
+
+    /// Replaces the empty recipients with the valid ones of the CSV file.
+    pub fn import_recipients(&mut self, ctx: &Context, content: &str) {
+        self.outputs.retain(|output| !output.is_empty());
+        let existing: Vec<String> = self
+            .outputs
+            .iter()
+            .map(|output| output.address.value.clone())
+            .collect();
+        let imported = parse_csv(content, ctx.network(), &existing);
+        self.outputs.extend(
+            imported
+                .recipients
+                .iter()
+                .map(ManagerSendOutput::from_recipient),
+        );
+        if self.outputs.is_empty() {
+            self.outputs.push(ManagerSendOutput::new());
+        }
+        self.csv_import = Some(imported);
+    }
+
+    pub fn export_recipients(&self) -> String {
+        let recipients: Vec<(String, String, String)> = self
+            .outputs
+            .iter()
+            .filter(|output| !output.is_empty())
+            .map(|output| {
+                (
+                    output.address.value.clone(),
+                    output.amount.value.clone(),
+                    output.label.value.clone(),
+                )
+            })
+            .collect();
+        to_csv(&recipients)
+    }
+
     pub fn selected_inputs(&self) -> Vec<model::Vault> {
         self.inputs
             .iter()
@@ -618,6 +842,27 @@ impl State for ManagerCreateSendTransactionState {
                 match res {
                     Ok(spend) => {
                         self.tx = Some(spend);
+                        // The labels of the recipients are kept with their address.
+                        let labels: Vec<Label> = self
+                            .outputs
+                            .iter()
+                            .filter(|output| !output.label.value.trim().is_empty())
+                            .map(|output| {
+                                Label::new(
+                                    LabelType::Addr,
+                                    output.address.value.trim().to_string(),
+                                    output.label.value.trim().to_string(),
+                                )
+                            })
+                            .collect();
+                        if !labels.is_empty() {
+                            return Command::batch(vec![
+                                Command::perform(async move { labels }, |labels| {
+                                    Message::Labels(LabelsMessage::Set(labels))
+                                }),
+                                self.update(ctx, Message::Next),
+                            ]);
+                        }
                     }
                     Err(e) => self.warning = Some(e.into()),
                 }
@@ -649,6 +894,20 @@ impl State for ManagerCreateSendTransactionState {
                     Message::SpendTransaction,
                 );
             }
+            Message::SpendTx(SpendTxMessage::SelectCoins(strategy)) => {
+                self.tx = None;
+                self.select_coins(strategy);
+            }
+            Message::SpendTx(SpendTxMessage::FeesEstimated(res)) => {
+                if let (Ok(estimates), None) = (&res, self.feerate) {
+                    // The normal preset is selected by default.
+                    if let Some(estimate) = estimates.normal {
+                        self.feerate = Some(estimate.feerate);
+                        self.valid_feerate = true;
+                    }
+                }
+                self.fee_estimates = Some(res);
+            }
             Message::SpendTx(SpendTxMessage::FeerateEdited(feerate)) => {
                 if let Ok(f) = feerate.parse::<u64>() {
                     self.feerate = Some(f);
@@ -697,6 +956,12 @@ impl State for ManagerCreateSendTransactionState {
             Message::Next => match self.step {
                 ManagerSendStep::SelectOutputs(_) => {
                     self.step = ManagerSendStep::SelectFee(ManagerSelectFeeView::new());
+                    let cookie_path = ctx.config.daemon.bitcoind_config.cookie_path.clone();
+                    let addr = ctx.config.daemon.bitcoind_config.addr;
+                    return Command::perform(
+                        async move { bitcoind::estimate_fees(&cookie_path, addr).await },
+                        |res| Message::SpendTx(SpendTxMessage::FeesEstimated(res)),
+                    );
                 }
                 ManagerSendStep::SelectInputs(_) => {
                     if let Some((tx, _)) = &self.tx {
@@ -714,6 +979,13 @@ impl State for ManagerCreateSendTransactionState {
                 }
                 ManagerSendStep::SelectFee(_) => {
                     self.step = ManagerSendStep::SelectInputs(ManagerSelectInputsView::new());
+                    // The outputs or the feerate may have changed, the strategy is applied again
+                    // unless the inputs were selected by hand.
+                    if !self.inputs.iter().any(|input| input.selected) {
+                        self.select_coins(CoinSelection::MinimizeVaults);
+                    } else if let Some(strategy) = self.coin_selection {
+                        self.select_coins(strategy);
+                    }
                 }
                 _ => (),
             },
@@ -736,6 +1008,51 @@ impl State for ManagerCreateSendTransactionState {
                 }
             }
             Message::AddRecipient => self.outputs.push(ManagerSendOutput::new()),
+            Message::AddressBook(AddressBookMessage::Loaded(res)) => match res {
+                Ok(book) => self.address_book = book,
+                Err(e) => log::warn!("Failed to load the address book: {}", e),
+            },
+            Message::RecipientsCsv(msg) => match msg {
+                RecipientsCsvMessage::PathEdited(path) => {
+                    self.csv_path.value = path;
+                    self.csv_path.valid = true;
+                    self.csv_exported = false;
+                }
+                RecipientsCsvMessage::Import => {
+                    self.warning = None;
+                    return Command::perform(
+                        read_file(PathBuf::from(&self.csv_path.value)),
+                        |res| Message::RecipientsCsv(RecipientsCsvMessage::Imported(res)),
+                    );
+                }
+                RecipientsCsvMessage::Imported(res) => match res {
+                    Ok(content) => {
+                        self.tx = None;
+                        self.import_recipients(ctx, &content);
+                    }
+                    Err(e) => {
+                        self.csv_path.valid = false;
+                        self.warning = Some(e);
+                    }
+                },
+                RecipientsCsvMessage::Export => {
+                    self.warning = None;
+                    return Command::perform(
+                        write_file(
+                            PathBuf::from(&self.csv_path.value),
+                            self.export_recipients(),
+                        ),
+                        |res| Message::RecipientsCsv(RecipientsCsvMessage::Exported(res)),
+                    );
+                }
+                RecipientsCsvMessage::Exported(res) => match res {
+                    Ok(()) => self.csv_exported = true,
+                    Err(e) => {
+                        self.csv_path.valid = false;
+                        self.warning = Some(e);
+                    }
+                },
+            },
             Message::Recipient(i, RecipientMessage::Delete) => {
                 self.outputs.remove(i);
             }
@@ -743,6 +1060,7 @@ impl State for ManagerCreateSendTransactionState {
                 self.tx = None;
                 if let Some(input) = self.inputs.get_mut(i) {
                     input.update(msg);
+                    self.coin_selection = None;
                 }
             }
             Message::Recipient(i, msg) => {
@@ -764,6 +1082,9 @@ impl State for ManagerCreateSendTransactionState {
         let selected_inputs = self.selected_inputs();
         let input_amount = self.input_amount();
         let output_amount = self.output_amount();
+        let estimated_fees = self.estimated_fees();
+        let address_book = &self.address_book;
+        let network = ctx.network();
         match &mut self.step {
             ManagerSendStep::SelectOutputs(v) => {
                 let mut valid =
@@ -778,13 +1099,22 @@ impl State for ManagerCreateSendTransactionState {
                     }
                 }
                 v.view(
+                    ctx,
                     self.outputs
                         .iter_mut()
                         .enumerate()
-                        .map(|(i, v)| v.view().map(move |msg| Message::Recipient(i, msg)))
+                        .map(|(i, v)| {
+                            v.view(address_book, network)
+                                .map(move |msg| Message::Recipient(i, msg))
+                        })
                         .collect(),
                     valid,
                     no_duplicate,
+                    output_amount,
+                    &self.csv_path,
+                    self.csv_import.as_ref(),
+                    self.csv_exported,
+                    self.warning.as_ref(),
                 )
             }
             ManagerSendStep::SelectInputs(v) => v.view(
@@ -796,11 +1126,16 @@ impl State for ManagerCreateSendTransactionState {
                     .collect(),
                 input_amount,
                 output_amount,
+                estimated_fees,
+                self.coin_selection,
+                self.warning.as_ref(),
+            ),
+            ManagerSendStep::SelectFee(v) => v.view(
+                self.feerate,
+                self.valid_feerate,
+                self.fee_estimates.as_ref(),
                 self.warning.as_ref(),
             ),
-            ManagerSendStep::SelectFee(v) => {
-                v.view(self.feerate, self.valid_feerate, self.warning.as_ref())
-            }
             ManagerSendStep::Sign { signer, view } => {
                 let (tx, feerate) = self.tx.as_ref().unwrap();
                 view.view(
@@ -822,33 +1157,15 @@ impl State for ManagerCreateSendTransactionState {
     }
 
     fn load(&self, ctx: &Context) -> Command<Message> {
-        let revaultd = ctx.revaultd.clone();
-        Command::batch(vec![Command::perform(
-            async move {
-                let vaults = revaultd.list_vaults(Some(&[VaultStatus::Active]), None)?;
-                let outpoints: Vec<OutPoint> =
-                    vaults.iter().map(|vault| outpoint(&vault)).collect();
-                let txs = revaultd.list_presigned_transactions(&outpoints)?;
-                let vaults_with_txs = vaults
-                    .into_iter()
-                    .map(|vault| {
-                        let tx = txs
-                            .iter()
-                            .find_map(|txs| {
-                                if txs.vault_outpoint == outpoint(&vault) {
-                                    Some(txs.unvault.clone().into_psbt())
-                                } else {
-                                    None
-                                }
-                            })
-                            .unwrap();
-                        (vault, tx)
-                    })
-                    .collect();
-                Ok(vaults_with_txs)
-            },
-            Message::VaultsWithUnvaultTx,
-        )])
+        Command::batch(vec![
+            Command::perform(
+                list_vaults_with_unvault_tx(ctx.revaultd.clone(), &[VaultStatus::Active]),
+                Message::VaultsWithUnvaultTx,
+            ),
+            Command::perform(load_address_book(ctx.network_datadir()), |res| {
+                Message::AddressBook(AddressBookMessage::Loaded(res))
+            }),
+        ])
     }
 }
 
@@ -862,6 +1179,7 @@ impl From<ManagerCreateSendTransactionState> for Box<dyn State> {
 struct ManagerSendOutput {
     address: form::Value<String>,
     amount: form::Value<String>,
+    label: form::Value<String>,
 
     view: ManagerSendOutputView,
 }
@@ -871,10 +1189,35 @@ impl ManagerSendOutput {
         Self {
             address: form::Value::default(),
             amount: form::Value::default(),
+            label: form::Value::default(),
+            view: ManagerSendOutputView::new(),
+        }
+    }
+
+    fn from_recipient(recipient: &Recipient) -> Self {
+        Self {
+            address: form::Value {
+                value: recipient.address.to_string(),
+                valid: true,
+            },
+            amount: form::Value {
+                value: recipient
+                    .amount
+                    .to_string_in(bitcoin::Denomination::Bitcoin),
+                valid: true,
+            },
+            label: form::Value {
+                value: recipient.label.clone(),
+                valid: true,
+            },
             view: ManagerSendOutputView::new(),
         }
     }
 
+    fn is_empty(&self) -> bool {
+        self.address.value.is_empty() && self.amount.value.is_empty()
+    }
+
     fn amount(&self) -> Result<u64, Error> {
         if self.amount.value.is_empty() {
             return Err(Error::Unexpected("Amount should be non-zero".to_string()));
@@ -931,12 +1274,38 @@ impl ManagerSendOutput {
                     self.amount.valid = true;
                 }
             }
+            RecipientMessage::LabelEdited(label) => self.label.value = label,
+            RecipientMessage::SuggestionSelected(address, label) => {
+                self.address.value = address;
+                self.address.valid = true;
+                self.label.value = label;
+                if !self.amount.value.is_empty() {
+                    self.amount.valid = self.amount().is_ok();
+                }
+            }
             _ => {}
         };
     }
 
-    fn view(&mut self) -> Element<RecipientMessage> {
-        self.view.view(&self.address, &self.amount)
+    fn view(
+        &mut self,
+        address_book: &AddressBook,
+        network: bitcoin::Network,
+    ) -> Element<RecipientMessage> {
+        let known = address_book.get(&self.address.value, network).is_some();
+        let is_address = bitcoin::Address::from_str(&self.address.value).is_ok();
+        let suggestions = if known || is_address {
+            Vec::new()
+        } else {
+            address_book.search(&self.address.value, network)
+        };
+        self.view.view(
+            &self.address,
+            &self.amount,
+            &self.label,
+            &suggestions,
+            is_address && !known,
+        )
     }
 }
 
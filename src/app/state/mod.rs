@@ -1,7 +1,10 @@
+mod address_book;
 pub mod cmd;
 mod deposit;
 mod emergency;
 pub mod history;
+mod inspector;
+mod label;
 pub mod manager;
 mod revault;
 mod settings;
@@ -13,9 +16,11 @@ mod vaults;
 
 use iced::{Command, Element, Subscription};
 
+pub use address_book::AddressBookState;
 pub use deposit::DepositState;
 pub use emergency::EmergencyState;
 pub use history::HistoryState;
+pub use inspector::InspectorState;
 pub use manager::{
     ManagerCreateSendTransactionState, ManagerHomeState, ManagerImportSendTransactionState,
     ManagerSendState,
@@ -13,18 +13,26 @@ use crate::{
     app::{
         context::Context,
         error::Error,
-        message::{Message, SettingsMessage},
-        state::cmd::get_server_status,
+        labels::ImportedLabels,
+        message::{LabelsMessage, Message, SettingsMessage},
+        state::cmd::{get_server_status, read_file, write_file},
         state::State,
         view::settings::*,
     },
+    bitcoind::{self, BlockchainInfo},
     daemon::model::ServersStatuses,
-    revault::Role,
+    revault::{Role, SetupFingerprint},
+    server,
 };
 
 trait Setting: std::fmt::Debug {
     fn edited(&mut self, success: bool);
     fn update(&mut self, ctx: &Context, message: SettingsMessage) -> Command<Message>;
+    /// Checks the connection with the server of the setting, the resulting
+    /// message is routed back to the setting.
+    fn test_connection(&self, _ctx: &Context) -> Command<SettingsMessage> {
+        Command::none()
+    }
     fn view(
         &mut self,
         ctx: &Context,
@@ -39,9 +47,17 @@ pub struct SettingsState {
     view: SettingsView,
     server_statuses: Option<ServersStatuses>,
     config_updated: bool,
+    fingerprint: SetupFingerprint,
+    show_fingerprint_qr_code: bool,
 
     settings: Vec<Box<dyn Setting>>,
     current: Option<usize>,
+
+    /// Path of the BIP-329 file to import or export the labels.
+    labels_path: form::Value<String>,
+    labels_import: Option<ImportedLabels>,
+    labels_exported: bool,
+    labels_view: LabelsSettingsView,
 }
 
 impl SettingsState {
@@ -62,17 +78,36 @@ impl SettingsState {
                 settings.push(CosignerSettings::new(i).into());
             }
         }
+        let fingerprint = setup_fingerprint(ctx);
+        let mut view = SettingsView::default();
+        view.load(&fingerprint);
         SettingsState {
-            view: SettingsView::default(),
+            view,
             warning: None,
             server_statuses: None,
             config_updated: false,
+            fingerprint,
+            show_fingerprint_qr_code: false,
             settings,
             current: None,
+            labels_path: form::Value::default(),
+            labels_import: None,
+            labels_exported: false,
+            labels_view: LabelsSettingsView::default(),
         }
     }
 }
 
+fn setup_fingerprint(ctx: &Context) -> SetupFingerprint {
+    let cfg = &ctx.config.daemon;
+    SetupFingerprint::new(
+        &cfg.scripts_config.deposit_descriptor,
+        &cfg.scripts_config.unvault_descriptor,
+        &cfg.scripts_config.cpfp_descriptor,
+        &cfg.coordinator_noise_key,
+    )
+}
+
 impl State for SettingsState {
     fn update(&mut self, ctx: &Context, message: Message) -> Command<Message> {
         match message {
@@ -85,6 +120,8 @@ impl State for SettingsState {
             Message::DaemonConfigLoaded(res) => match res {
                 Ok(()) => {
                     self.config_updated = true;
+                    self.fingerprint = setup_fingerprint(ctx);
+                    self.view.load(&self.fingerprint);
                     if let Some(current) = self.current {
                         if let Some(setting) = self.settings.get_mut(current) {
                             setting.edited(true)
@@ -117,11 +154,58 @@ impl State for SettingsState {
                     match msg {
                         SettingsMessage::Edit => self.current = Some(i),
                         SettingsMessage::CancelEdit => self.current = None,
+                        SettingsMessage::TestConnection => {
+                            let cmd = setting.update(ctx, msg);
+                            return Command::batch(vec![
+                                cmd,
+                                setting
+                                    .test_connection(ctx)
+                                    .map(move |msg| Message::Settings(i, msg)),
+                            ]);
+                        }
                         _ => {}
                     };
                     return setting.update(ctx, msg);
                 }
             }
+            Message::Labels(msg) => match msg {
+                LabelsMessage::PathEdited(path) => {
+                    self.labels_path.value = path;
+                    self.labels_path.valid = true;
+                    self.labels_import = None;
+                    self.labels_exported = false;
+                }
+                LabelsMessage::Import => {
+                    self.warning = None;
+                    return Command::perform(
+                        read_file(PathBuf::from(&self.labels_path.value)),
+                        |res| Message::Labels(LabelsMessage::Imported(res)),
+                    );
+                }
+                // The content of the file is merged by the application in the labels
+                // of the context.
+                LabelsMessage::Merged(imported) => self.labels_import = Some(imported),
+                LabelsMessage::Export => {
+                    self.warning = None;
+                    return Command::perform(
+                        write_file(
+                            PathBuf::from(&self.labels_path.value),
+                            ctx.labels.to_jsonl(),
+                        ),
+                        |res| Message::Labels(LabelsMessage::Exported(res)),
+                    );
+                }
+                LabelsMessage::Exported(Ok(())) => self.labels_exported = true,
+                LabelsMessage::Imported(Err(e)) | LabelsMessage::Exported(Err(e)) => {
+                    self.labels_path.valid = false;
+                    self.warning = Some(e);
+                }
+                LabelsMessage::Saved(Err(e)) => self.warning = Some(e),
+                _ => {}
+            },
+            Message::ToggleFingerprintQRCode => {
+                self.show_fingerprint_qr_code = !self.show_fingerprint_qr_code;
+            }
             Message::AddWatchtower => {
                 if ctx.role == Role::Stakeholder {
                     self.settings.push(
@@ -149,6 +233,8 @@ impl State for SettingsState {
             ctx,
             self.warning.as_ref(),
             can_edit,
+            &self.fingerprint,
+            self.show_fingerprint_qr_code,
             self.settings
                 .iter_mut()
                 .enumerate()
@@ -158,6 +244,12 @@ impl State for SettingsState {
                         .map(move |msg| Message::Settings(i, msg))
                 })
                 .collect(),
+            self.labels_view.view(
+                ctx,
+                &self.labels_path,
+                self.labels_import.as_ref(),
+                self.labels_exported,
+            ),
         )
     }
 
@@ -182,6 +274,8 @@ pub enum BitcoindSettings {
         processing: bool,
         cookie_path: form::Value<String>,
         addr: form::Value<String>,
+        testing: bool,
+        connection: Option<Result<BlockchainInfo, bitcoind::Error>>,
         view: BitcoindSettingsEditView,
     },
 }
@@ -199,6 +293,28 @@ impl From<BitcoindSettings> for Box<dyn Setting> {
 }
 
 impl Setting for BitcoindSettings {
+    fn test_connection(&self, ctx: &Context) -> Command<SettingsMessage> {
+        if let Self::Edit {
+            addr,
+            cookie_path,
+            testing: true,
+            ..
+        } = self
+        {
+            if let (Ok(addr), Ok(cookie_path)) = (
+                SocketAddr::from_str(&addr.value),
+                PathBuf::from_str(&cookie_path.value),
+            ) {
+                let network = ctx.network();
+                return Command::perform(
+                    async move { bitcoind::check_connection(&cookie_path, addr, network).await },
+                    SettingsMessage::BitcoindTested,
+                );
+            }
+        }
+        Command::none()
+    }
+
     fn edited(&mut self, success: bool) {
         if success {
             *self = Self::default();
@@ -226,6 +342,8 @@ impl Setting for BitcoindSettings {
                     valid: true,
                     value: ctx.config.daemon.bitcoind_config.addr.to_string(),
                 },
+                testing: false,
+                connection: None,
                 view: BitcoindSettingsEditView::default(),
             };
         }
@@ -233,11 +351,15 @@ impl Setting for BitcoindSettings {
             addr,
             cookie_path,
             processing,
+            testing,
+            connection,
             ..
         } = self
         {
             match message {
-                SettingsMessage::Edit | SettingsMessage::Remove => {}
+                SettingsMessage::Edit
+                | SettingsMessage::Remove
+                | SettingsMessage::ServerTested(_) => {}
                 SettingsMessage::CancelEdit => {
                     if !*processing {
                         *self = Self::default();
@@ -250,8 +372,19 @@ impl Setting for BitcoindSettings {
                             "cookie_file_path" => cookie_path.value = value,
                             _ => {}
                         }
+                        *connection = None;
                     }
                 }
+                SettingsMessage::TestConnection => {
+                    addr.valid = SocketAddr::from_str(&addr.value).is_ok();
+                    cookie_path.valid = PathBuf::from_str(&cookie_path.value).is_ok();
+                    *testing = addr.valid && cookie_path.valid;
+                    *connection = None;
+                }
+                SettingsMessage::BitcoindTested(res) => {
+                    *testing = false;
+                    *connection = Some(res);
+                }
                 SettingsMessage::ConfirmEdit => {
                     let new_addr = SocketAddr::from_str(&addr.value);
                     addr.valid = new_addr.is_ok();
@@ -291,12 +424,16 @@ impl Setting for BitcoindSettings {
                 addr,
                 cookie_path,
                 processing,
+                testing,
+                connection,
             } => view.view(
                 &ctx.config.daemon.bitcoind_config,
                 ctx.blockheight,
                 &addr,
                 &cookie_path,
                 *processing,
+                *testing,
+                connection.as_ref(),
             ),
         }
     }
@@ -309,6 +446,8 @@ pub enum CoordinatorSettings {
         processing: bool,
         host: form::Value<String>,
         key: form::Value<String>,
+        testing: bool,
+        connection: Option<Result<(), server::Error>>,
         view: CoordinatorSettingsEditView,
     },
 }
@@ -326,6 +465,19 @@ impl From<CoordinatorSettings> for Box<dyn Setting> {
 }
 
 impl Setting for CoordinatorSettings {
+    fn test_connection(&self, ctx: &Context) -> Command<SettingsMessage> {
+        if let Self::Edit {
+            host,
+            key,
+            testing: true,
+            ..
+        } = self
+        {
+            return test_server_connection(ctx, host, key);
+        }
+        Command::none()
+    }
+
     fn edited(&mut self, success: bool) {
         if success {
             *self = Self::default();
@@ -346,6 +498,8 @@ impl Setting for CoordinatorSettings {
                     valid: true,
                     value: ctx.config.daemon.coordinator_host.to_string(),
                 },
+                testing: false,
+                connection: None,
                 view: CoordinatorSettingsEditView::default(),
             };
         }
@@ -353,11 +507,23 @@ impl Setting for CoordinatorSettings {
             host,
             key,
             processing,
+            testing,
+            connection,
             ..
         } = self
         {
             match message {
-                SettingsMessage::Edit | SettingsMessage::Remove => {}
+                SettingsMessage::Edit
+                | SettingsMessage::Remove
+                | SettingsMessage::BitcoindTested(_) => {}
+                SettingsMessage::TestConnection => {
+                    *testing = check_server_fields(host, key);
+                    *connection = None;
+                }
+                SettingsMessage::ServerTested(res) => {
+                    *testing = false;
+                    *connection = Some(res);
+                }
                 SettingsMessage::CancelEdit => {
                     if !*processing {
                         *self = Self::default();
@@ -370,6 +536,7 @@ impl Setting for CoordinatorSettings {
                             "key" => key.value = value,
                             _ => {}
                         }
+                        *connection = None;
                     }
                 }
                 SettingsMessage::ConfirmEdit => {
@@ -412,7 +579,9 @@ impl Setting for CoordinatorSettings {
                 host,
                 key,
                 processing,
-            } => view.view(&host, &key, *processing),
+                testing,
+                connection,
+            } => view.view(&host, &key, *processing, *testing, connection.as_ref()),
         }
     }
 }
@@ -476,7 +645,11 @@ impl Setting for WatchtowerSettings {
         } = self
         {
             match message {
-                SettingsMessage::Edit | SettingsMessage::Remove => {}
+                SettingsMessage::Edit
+                | SettingsMessage::Remove
+                | SettingsMessage::TestConnection
+                | SettingsMessage::BitcoindTested(_)
+                | SettingsMessage::ServerTested(_) => {}
                 SettingsMessage::CancelEdit => {
                     if !*processing {
                         *self = Self::new(self.index());
@@ -585,6 +758,8 @@ pub enum CosignerSettings {
         processing: bool,
         host: form::Value<String>,
         key: form::Value<String>,
+        testing: bool,
+        connection: Option<Result<(), server::Error>>,
         view: CosignerSettingsEditView,
     },
 }
@@ -603,6 +778,19 @@ impl CosignerSettings {
 }
 
 impl Setting for CosignerSettings {
+    fn test_connection(&self, ctx: &Context) -> Command<SettingsMessage> {
+        if let Self::Edit {
+            host,
+            key,
+            testing: true,
+            ..
+        } = self
+        {
+            return test_server_connection(ctx, host, key);
+        }
+        Command::none()
+    }
+
     fn edited(&mut self, success: bool) {
         if success {
             *self = Self::new(self.index());
@@ -624,6 +812,8 @@ impl Setting for CosignerSettings {
                     valid: true,
                     value: ctx.config.daemon.coordinator_host.to_string(),
                 },
+                testing: false,
+                connection: None,
                 view: CosignerSettingsEditView::default(),
             };
         }
@@ -632,11 +822,23 @@ impl Setting for CosignerSettings {
             key,
             processing,
             index,
+            testing,
+            connection,
             ..
         } = self
         {
             match message {
-                SettingsMessage::Edit | SettingsMessage::Remove => {}
+                SettingsMessage::Edit
+                | SettingsMessage::Remove
+                | SettingsMessage::BitcoindTested(_) => {}
+                SettingsMessage::TestConnection => {
+                    *testing = check_server_fields(host, key);
+                    *connection = None;
+                }
+                SettingsMessage::ServerTested(res) => {
+                    *testing = false;
+                    *connection = Some(res);
+                }
                 SettingsMessage::CancelEdit => {
                     if !*processing {
                         *self = Self::new(self.index());
@@ -649,6 +851,7 @@ impl Setting for CosignerSettings {
                             "key" => key.value = value,
                             _ => {}
                         }
+                        *connection = None;
                     }
                 }
                 SettingsMessage::ConfirmEdit => {
@@ -708,8 +911,10 @@ impl Setting for CosignerSettings {
                 host,
                 key,
                 processing,
+                testing,
+                connection,
                 ..
-            } => view.view(&host, &key, *processing),
+            } => view.view(&host, &key, *processing, *testing, connection.as_ref()),
         }
     }
 }
@@ -719,3 +924,38 @@ impl From<CosignerSettings> for Box<dyn Setting> {
         Box::new(s)
     }
 }
+
+/// Marks the invalid fields, returns true if the server can be tested.
+fn check_server_fields(host: &mut form::Value<String>, key: &mut form::Value<String>) -> bool {
+    host.valid = SocketAddr::from_str(&host.value).is_ok();
+    key.valid = Vec::from_hex(&key.value)
+        .ok()
+        .and_then(|bytes| NoisePubkey::from_slice(&bytes))
+        .is_some();
+    host.valid && key.valid
+}
+
+/// Tries a noise connection to the server with the noise private key of the daemon.
+fn test_server_connection(
+    ctx: &Context,
+    host: &form::Value<String>,
+    key: &form::Value<String>,
+) -> Command<SettingsMessage> {
+    let host = SocketAddr::from_str(&host.value);
+    let key = Vec::from_hex(&key.value)
+        .ok()
+        .and_then(|bytes| NoisePubkey::from_slice(&bytes));
+    let network_datadir = ctx.network_datadir();
+    if let (Ok(host), Some(key)) = (host, key) {
+        return Command::perform(
+            async move {
+                let network_datadir = network_datadir
+                    .ok_or_else(|| server::Error::NoiseSecret("datadir not found".to_string()))?;
+                let our_key = server::read_noise_secret(&network_datadir)?;
+                server::check_connection(host, our_key, key).await
+            },
+            SettingsMessage::ServerTested,
+        );
+    }
+    Command::none()
+}
@@ -42,6 +42,13 @@ impl SpendTransactionTarget {
     }
 }
 
+/// Child transaction spending CPFP outputs, signed by the key of the user
+/// for the CPFP descriptor.
+#[derive(Debug)]
+pub struct CpfpTransactionTarget {
+    pub child: Psbt,
+}
+
 #[derive(Debug)]
 pub struct Signer<T> {
     device: Device,
@@ -140,6 +147,45 @@ impl Signer<SpendTransactionTarget> {
     }
 }
 
+impl Signer<CpfpTransactionTarget> {
+    pub fn update(&mut self, ctx: &Context, message: SignMessage) -> Command<SignMessage> {
+        match message {
+            SignMessage::SelectSign => {
+                self.processing = true;
+                return Command::perform(
+                    self.device.clone().sign_cpfp_tx(self.target.child.clone()),
+                    |tx| SignMessage::PsbtSigned(tx.map(Box::new)),
+                );
+            }
+            SignMessage::PsbtSigned(res) => {
+                self.processing = false;
+                match res {
+                    Ok(tx) => {
+                        if tx.global.unsigned_tx.txid()
+                            == self.target.child.global.unsigned_tx.txid()
+                        {
+                            // A single signature satisfies the CPFP descriptor.
+                            if tx.inputs.iter().any(|input| input.partial_sigs.is_empty()) {
+                                log::info!("Hardware wallet did not sign the cpfp tx");
+                                self.error = Some(HWIError::DeviceDidNotSign.into());
+                                return Command::none();
+                            }
+                            self.signed = true;
+                            self.target.child = *tx;
+                        }
+                    }
+                    Err(e) => {
+                        log::info!("{:?}", e);
+                        self.error = Some(e.into());
+                    }
+                }
+            }
+            _ => return self.device.update(&ctx, message),
+        };
+        Command::none()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Device {
     channel: Option<Arc<Mutex<Box<dyn RevaultHWI + Send>>>>,
@@ -218,6 +264,14 @@ impl Device {
         }
     }
 
+    pub async fn sign_cpfp_tx(self, child: Psbt) -> Result<Psbt, HWIError> {
+        if let Some(channel) = self.channel {
+            channel.lock().await.sign_tx(&child).await
+        } else {
+            Err(HWIError::DeviceDisconnected)
+        }
+    }
+
     pub async fn secure_batch(
         self,
         deposits: &Vec<Vault>,
@@ -1,27 +1,41 @@
-use bitcoin::util::{bip32::Fingerprint, psbt::PartiallySignedTransaction as Psbt};
+use bitcoin::{
+    util::{bip32::Fingerprint, psbt::PartiallySignedTransaction as Psbt},
+    Txid,
+};
 use std::convert::From;
+use std::path::PathBuf;
 
 use iced::{Command, Element, Subscription};
+use iced_native::{window, Event};
 use revault_ui::component::form;
-use revaultd::revault_tx::{miniscript::DescriptorPublicKey, transactions::RevaultTransaction};
+use revaultd::revault_tx::transactions::RevaultTransaction;
 
 use crate::{
     app::{
         context::Context,
+        cpfp::{cpfp_output, create_child, finalize_child, CpfpError, CpfpParent},
         error::Error,
-        message::{Message, SpendTxMessage},
+        inspector::{derivation_indexes, Decoded},
+        labels::LabelType,
+        message::{CpfpMessage, Message, SpendTxMessage},
+        signatures::SignaturesMatrix,
         state::{
-            cmd::{broadcast_spend_tx, delete_spend_tx, list_vaults, update_spend_tx},
-            sign::{Signer, SpendTransactionTarget},
+            cmd::{
+                broadcast_spend_tx, delete_spend_tx, list_unvault_txs, list_vaults,
+                load_cpfp_parents, read_psbt_file, update_spend_tx, write_psbt_file,
+            },
+            label::LabelEditor,
+            sign::{CpfpTransactionTarget, Signer, SpendTransactionTarget},
             State,
         },
         view::spend_transaction::{
-            spend_tx_confirmed, spend_tx_deprecated, spend_tx_processing,
-            SpendTransactionBroadcastView, SpendTransactionDeleteView,
-            SpendTransactionListItemView, SpendTransactionSharePsbtView, SpendTransactionSignView,
-            SpendTransactionView,
+            spend_tx_confirmed, spend_tx_deprecated, SpendTransactionBroadcastView,
+            SpendTransactionBumpFeeView, SpendTransactionDeleteView, SpendTransactionListItemView,
+            SpendTransactionProcessingView, SpendTransactionSharePsbtView,
+            SpendTransactionSignView, SpendTransactionView,
         },
     },
+    bitcoind,
     daemon::model::{self, outpoint},
 };
 
@@ -31,6 +45,10 @@ pub struct SpendTransactionState {
     psbt: Psbt,
     deposits: Vec<model::Vault>,
     warning: Option<Error>,
+    label: LabelEditor,
+    /// Path of the .psbt file the transaction is exported to or imported from.
+    psbt_path: form::Value<String>,
+    psbt_exported: bool,
 
     action: SpendTransactionAction,
 
@@ -40,31 +58,62 @@ pub struct SpendTransactionState {
 impl SpendTransactionState {
     pub fn new(ctx: &Context, tx: model::SpendTx) -> Self {
         Self {
-            action: SpendTransactionAction::new(
-                ctx.managers_threshold,
-                ctx.user_signed(&tx.psbt.psbt()),
-                &ctx.managers_xpubs(),
-                &tx.psbt.psbt(),
-                &tx.status,
-            ),
+            action: SpendTransactionAction::from_context(ctx, tx.psbt.psbt(), &tx.status),
             psbt: tx.psbt.psbt().clone(),
+            label: LabelEditor::new(LabelType::Tx, tx.psbt.txid().to_string()),
             tx,
             deposits: Vec::new(),
             warning: None,
+            psbt_path: form::Value::default(),
+            psbt_exported: false,
             view: SpendTransactionView::default(),
         }
     }
 
     // TODO: remove it for subscription
     pub fn sub(&self) -> Subscription<Message> {
-        if let SpendTransactionAction::Sign { signer, .. } = &self.action {
-            signer
+        match &self.action {
+            SpendTransactionAction::Sign { signer, .. } => signer
                 .subscription()
-                .map(|msg| Message::SpendTx(SpendTxMessage::Sign(msg)))
-        } else {
-            Subscription::none()
+                .map(|msg| Message::SpendTx(SpendTxMessage::Sign(msg))),
+            SpendTransactionAction::BumpFee {
+                signer: Some(signer),
+                ..
+            } => signer
+                .subscription()
+                .map(|msg| Message::SpendTx(SpendTxMessage::Cpfp(CpfpMessage::Sign(msg)))),
+            _ => Subscription::none(),
         }
     }
+
+    /// Looks in the mempool for the CPFP outputs of the given transactions,
+    /// the unvault transactions or the spend transaction itself.
+    fn load_cpfp_parents(&self, ctx: &Context, txs: Vec<Psbt>) -> Command<Message> {
+        let vaults: Vec<(bitcoin::OutPoint, bitcoin::Amount, u32)> = self
+            .deposits
+            .iter()
+            .map(|vault| (outpoint(vault), vault.amount, vault.derivation_index.into()))
+            .collect();
+        let mut indexes: Vec<u32> = txs
+            .iter()
+            .flat_map(|tx| derivation_indexes(&Decoded::Psbt(tx.clone()), &vaults))
+            .collect();
+        indexes.sort_unstable();
+        indexes.dedup();
+        let scripts = ctx.own_scripts(&indexes);
+        let txs: Vec<(Psbt, usize)> = txs
+            .into_iter()
+            .filter_map(|tx| cpfp_output(&tx, &scripts).map(|vout| (tx, vout)))
+            .collect();
+        Command::perform(
+            load_cpfp_parents(
+                ctx.config.daemon.bitcoind_config.cookie_path.clone(),
+                ctx.config.daemon.bitcoind_config.addr,
+                txs,
+            ),
+            |res| Message::SpendTx(SpendTxMessage::Cpfp(CpfpMessage::Parents(res))),
+        )
+    }
 }
 
 impl State for SpendTransactionState {
@@ -79,6 +128,89 @@ impl State for SpendTransactionState {
                 }
                 Err(e) => self.warning = Error::from(e).into(),
             },
+            Message::SpendTx(SpendTxMessage::Label(msg)) => {
+                return self.label.update(ctx, msg);
+            }
+            Message::SpendTx(SpendTxMessage::PsbtPathEdited(path)) => {
+                self.psbt_path.value = path;
+                self.psbt_path.valid = true;
+                self.psbt_exported = false;
+            }
+            Message::SpendTx(SpendTxMessage::ExportPsbtFile) => {
+                self.warning = None;
+                return Command::perform(
+                    write_psbt_file(PathBuf::from(&self.psbt_path.value), self.psbt.clone()),
+                    |res| Message::SpendTx(SpendTxMessage::PsbtFileExported(res)),
+                );
+            }
+            Message::SpendTx(SpendTxMessage::PsbtFileExported(res)) => match res {
+                Ok(()) => self.psbt_exported = true,
+                Err(e) => {
+                    self.psbt_path.valid = false;
+                    self.warning = Some(e);
+                }
+            },
+            Message::SpendTx(SpendTxMessage::ImportPsbtFile) => {
+                if self.action.can_import_psbt() {
+                    self.warning = None;
+                    return Command::perform(
+                        read_psbt_file(PathBuf::from(&self.psbt_path.value)),
+                        |res| Message::SpendTx(SpendTxMessage::PsbtFileImported(res)),
+                    );
+                }
+            }
+            Message::SpendTx(SpendTxMessage::PsbtFileImported(res)) => match res {
+                // The imported PSBT is added to the PSBTs to combine like a pasted one.
+                Ok(psbt) => {
+                    let _ = self.action.update(
+                        ctx,
+                        &mut self.psbt,
+                        &self.tx.status,
+                        SpendTxMessage::PsbtEdited(psbt.to_string()),
+                    );
+                    return self
+                        .action
+                        .update(
+                            ctx,
+                            &mut self.psbt,
+                            &self.tx.status,
+                            SpendTxMessage::AddPsbt,
+                        )
+                        .map(Message::SpendTx);
+                }
+                Err(e) => {
+                    self.psbt_path.valid = false;
+                    self.warning = Some(e);
+                }
+            },
+            Message::Event(Event::Window(window::Event::FileDropped(path))) => {
+                self.psbt_path.value = path.to_string_lossy().to_string();
+                self.psbt_path.valid = true;
+                self.psbt_exported = false;
+                return self.update(ctx, Message::SpendTx(SpendTxMessage::ImportPsbtFile));
+            }
+            Message::SpendTx(SpendTxMessage::SelectBumpFee) => {
+                if let SpendTransactionAction::Processing { .. } = self.action {
+                    self.action = SpendTransactionAction::bump_fee();
+                    // A pending spend waits for the confirmation of its unvault transactions.
+                    if matches!(self.tx.status, model::ListSpendStatus::Pending) {
+                        return Command::perform(
+                            list_unvault_txs(
+                                ctx.revaultd.clone(),
+                                self.tx.deposit_outpoints.clone(),
+                            ),
+                            |res| {
+                                Message::SpendTx(SpendTxMessage::Cpfp(CpfpMessage::UnvaultTxs(res)))
+                            },
+                        );
+                    }
+                    return self.load_cpfp_parents(ctx, vec![self.psbt.clone()]);
+                }
+            }
+            Message::SpendTx(SpendTxMessage::Cpfp(CpfpMessage::UnvaultTxs(res))) => match res {
+                Ok(txs) => return self.load_cpfp_parents(ctx, txs),
+                Err(e) => self.warning = Error::from(e).into(),
+            },
             Message::SpendTx(msg) => {
                 return self
                     .action
@@ -97,17 +229,32 @@ impl State for SpendTransactionState {
     fn view(&mut self, ctx: &Context) -> Element<Message> {
         let show_delete_button = !matches!(
             self.action,
-            SpendTransactionAction::Delete { .. } | SpendTransactionAction::Processing
+            SpendTransactionAction::Delete { .. }
+                | SpendTransactionAction::Processing { .. }
+                | SpendTransactionAction::BumpFee { .. }
         );
+        let can_import_psbt = self.action.can_import_psbt();
+        let signatures = SignaturesMatrix::new(&ctx.managers_fingerprints(), &self.psbt);
+        let user_signed = ctx
+            .manager_fingerprint()
+            .map(|fingerprint| signatures.signed_all(&fingerprint))
+            .unwrap_or(false);
         self.view.view(
             ctx,
             &self.tx,
             &self.psbt,
+            &signatures,
             &self.deposits,
             self.action.view(ctx, &self.psbt),
+            self.label
+                .view(ctx)
+                .map(|msg| Message::SpendTx(SpendTxMessage::Label(msg))),
+            &self.psbt_path,
+            self.psbt_exported,
+            can_import_psbt,
             self.warning.as_ref(),
             show_delete_button,
-            ctx.user_signed(&self.psbt),
+            user_signed,
         )
     }
 
@@ -125,11 +272,31 @@ impl State for SpendTransactionState {
 
 #[derive(Debug)]
 pub enum SpendTransactionAction {
-    Processing,
+    Processing {
+        view: SpendTransactionProcessingView,
+    },
+    /// Fee bumping of the spend transaction or of its unvault transactions
+    /// with a child transaction spending their CPFP outputs.
+    BumpFee {
+        /// None until the transactions are looked for in the mempool.
+        parents: Option<Vec<CpfpParent>>,
+        feerate: form::Value<String>,
+        signer: Option<Signer<CpfpTransactionTarget>>,
+        processing: bool,
+        broadcasted: Option<Txid>,
+        error: Option<CpfpError>,
+        warning: Option<Error>,
+        view: SpendTransactionBumpFeeView,
+    },
     Deprecated,
     Confirmed,
     SharePsbt {
         psbt_input: form::Value<String>,
+        /// PSBTs of the other managers to combine with the transaction.
+        psbts: Vec<Psbt>,
+        /// Combination of the transaction with the PSBTs, updated when a PSBT is
+        /// added or removed and kept until the daemon accepts it.
+        combined: Option<Psbt>,
         processing: bool,
         success: bool,
         warning: Option<Error>,
@@ -157,10 +324,48 @@ pub enum SpendTransactionAction {
 }
 
 impl SpendTransactionAction {
+    /// A PSBT signed by the other managers can be imported only while the user
+    /// shares the transaction.
+    fn can_import_psbt(&self) -> bool {
+        matches!(
+            self,
+            SpendTransactionAction::SharePsbt {
+                processing: false,
+                ..
+            }
+        )
+    }
+
+    fn bump_fee() -> Self {
+        Self::BumpFee {
+            parents: None,
+            feerate: form::Value::default(),
+            signer: None,
+            processing: false,
+            broadcasted: None,
+            error: None,
+            warning: None,
+            view: SpendTransactionBumpFeeView::new(),
+        }
+    }
+
+    fn from_context(ctx: &Context, psbt: &Psbt, status: &model::ListSpendStatus) -> Self {
+        Self::new(
+            ctx.managers_threshold,
+            ctx.manager_fingerprint(),
+            &ctx.managers_fingerprints(),
+            psbt,
+            status,
+        )
+    }
+
+    /// The action is decided from the signatures of the managers on every input:
+    /// the user signs until they signed all the inputs, then shares the transaction
+    /// until each input has the threshold of managers signatures.
     fn new(
         managers_threshold: usize,
-        user_signed: bool,
-        managers_xpubs: &Vec<DescriptorPublicKey>,
+        user: Option<Fingerprint>,
+        managers: &[Fingerprint],
         psbt: &Psbt,
         status: &model::ListSpendStatus,
     ) -> Self {
@@ -168,13 +373,16 @@ impl SpendTransactionAction {
             model::ListSpendStatus::Deprecated => return Self::Deprecated,
             model::ListSpendStatus::Confirmed => return Self::Confirmed,
             model::ListSpendStatus::Pending | model::ListSpendStatus::Broadcasted => {
-                return Self::Processing
+                return Self::Processing {
+                    view: SpendTransactionProcessingView::new(),
+                }
             }
             _ => {}
         };
 
-        if let Some(input) = psbt.inputs.first() {
-            if input.partial_sigs.len() >= managers_threshold {
+        let signatures = SignaturesMatrix::new(managers, psbt);
+        if signatures.inputs != 0 {
+            if signatures.is_complete(managers_threshold) {
                 return Self::Broadcast {
                     with_priority: false,
                     processing: false,
@@ -182,9 +390,14 @@ impl SpendTransactionAction {
                     warning: None,
                     view: SpendTransactionBroadcastView::new(),
                 };
-            } else if user_signed {
+            } else if user
+                .map(|fingerprint| signatures.signed_all(&fingerprint))
+                .unwrap_or(false)
+            {
                 return Self::SharePsbt {
                     psbt_input: form::Value::default(),
+                    psbts: Vec::new(),
+                    combined: None,
                     processing: false,
                     success: false,
                     warning: None,
@@ -195,10 +408,7 @@ impl SpendTransactionAction {
                     processing: false,
                     warning: None,
                     signer: Signer::new(SpendTransactionTarget::new(
-                        &managers_xpubs
-                            .iter()
-                            .map(|xpub| xpub.master_fingerprint())
-                            .collect(),
+                        &managers.to_vec(),
                         psbt.clone(),
                     )),
                     view: SpendTransactionSignView::new(),
@@ -207,6 +417,8 @@ impl SpendTransactionAction {
         }
         Self::SharePsbt {
             psbt_input: form::Value::default(),
+            psbts: Vec::new(),
+            combined: None,
             processing: false,
             success: false,
             warning: None,
@@ -254,13 +466,7 @@ impl SpendTransactionAction {
                 };
             }
             SpendTxMessage::UnselectDelete => {
-                *self = Self::new(
-                    ctx.managers_threshold,
-                    ctx.user_signed(psbt),
-                    &ctx.managers_xpubs(),
-                    psbt,
-                    status,
-                );
+                *self = Self::from_context(ctx, psbt, status);
             }
             SpendTxMessage::Sign(msg) => {
                 if let Self::Sign {
@@ -293,13 +499,7 @@ impl SpendTransactionAction {
                             // During this step state has a generated psbt
                             // and signer has a signed psbt.
                             *psbt = signer.target.spend_tx.clone();
-                            *self = Self::new(
-                                ctx.managers_threshold,
-                                true,
-                                &ctx.managers_xpubs(),
-                                psbt,
-                                status,
-                            );
+                            *self = Self::from_context(ctx, psbt, status);
                         }
 
                         Err(e) => *warning = Some(e.into()),
@@ -358,45 +558,88 @@ impl SpendTransactionAction {
                     }
                 }
             }
+            SpendTxMessage::AddPsbt => {
+                if let Self::SharePsbt {
+                    psbt_input,
+                    psbts,
+                    combined,
+                    processing,
+                    success,
+                    warning,
+                    ..
+                } = self
+                {
+                    if !*processing {
+                        *success = false;
+                        match parse_managers_psbt(ctx, psbt, &psbt_input.value) {
+                            Some(p) => {
+                                psbts.push(p);
+                                *psbt_input = form::Value::default();
+                                update_combination(psbt, psbts, combined, warning);
+                            }
+                            None => psbt_input.valid = false,
+                        }
+                    }
+                }
+            }
+            SpendTxMessage::RemovePsbt(i) => {
+                if let Self::SharePsbt {
+                    psbts,
+                    combined,
+                    processing,
+                    warning,
+                    ..
+                } = self
+                {
+                    if !*processing && i < psbts.len() {
+                        psbts.remove(i);
+                        update_combination(psbt, psbts, combined, warning);
+                    }
+                }
+            }
             SpendTxMessage::Update => {
                 if let Self::SharePsbt {
                     psbt_input,
+                    psbts,
+                    combined,
                     processing,
                     warning,
                     ..
                 } = self
                 {
-                    let p: Option<Psbt> = bitcoin::base64::decode(&psbt_input.value)
-                        .ok()
-                        .and_then(|bytes| bitcoin::consensus::encode::deserialize(&bytes).ok());
-                    if let Some(p) = p {
-                        if p.global.unsigned_tx.txid() != psbt.global.unsigned_tx.txid() {
-                            psbt_input.valid = false;
-                        } else if is_unknown_sig(
-                            &ctx.managers_xpubs()
-                                .into_iter()
-                                .map(|xpub| xpub.master_fingerprint())
-                                .collect(),
-                            &p,
-                        ) {
-                            psbt_input.valid = false;
-                        } else {
-                            *processing = true;
-                            *warning = None;
-                            psbt_input.valid = true;
-                            return Command::perform(
-                                update_spend_tx(ctx.revaultd.clone(), p),
-                                SpendTxMessage::Updated,
-                            );
+                    // A pasted PSBT is combined with the ones already added.
+                    if !psbt_input.value.is_empty() {
+                        match parse_managers_psbt(ctx, psbt, &psbt_input.value) {
+                            Some(p) => {
+                                psbts.push(p);
+                                *psbt_input = form::Value::default();
+                                update_combination(psbt, psbts, combined, warning);
+                            }
+                            None => {
+                                psbt_input.valid = false;
+                                return Command::none();
+                            }
                         }
-                    } else {
+                    }
+                    if psbts.is_empty() {
                         psbt_input.valid = false;
+                        return Command::none();
+                    }
+                    // The warning tells why the PSBTs could not be combined.
+                    if let Some(p) = combined {
+                        *processing = true;
+                        *warning = None;
+                        return Command::perform(
+                            update_spend_tx(ctx.revaultd.clone(), p.clone()),
+                            SpendTxMessage::Updated,
+                        );
                     }
                 }
             }
             SpendTxMessage::Updated(res) => {
                 if let Self::SharePsbt {
-                    psbt_input,
+                    psbts,
+                    combined,
                     processing,
                     success,
                     warning,
@@ -407,49 +650,14 @@ impl SpendTransactionAction {
                         Ok(()) => {
                             *success = true;
                             *processing = false;
-                            *psbt = bitcoin::consensus::encode::deserialize(
-                                &bitcoin::base64::decode(&psbt_input.value)
-                                    .expect("psbt was successfully updated with the given input"),
-                            )
-                            .expect("psbt was successfully updated with the given input");
-                            if let Some(input) = psbt.inputs.first() {
-                                if input.partial_sigs.len() == ctx.managers_threshold {
-                                    *self = Self::Broadcast {
-                                        with_priority: false,
-                                        processing: false,
-                                        success: false,
-                                        warning: None,
-                                        view: SpendTransactionBroadcastView::new(),
-                                    };
-                                } else if !input.partial_sigs.keys().any(|key| {
-                                    input
-                                        .bip32_derivation
-                                        .get(key)
-                                        .map(|(fingerprint, _)| {
-                                            ctx.config
-                                                .daemon
-                                                .manager_config
-                                                .as_ref()
-                                                .expect("User is a manager")
-                                                .xpub
-                                                .fingerprint()
-                                                == *fingerprint
-                                        })
-                                        .unwrap_or(false)
-                                }) {
-                                    *self = Self::Sign {
-                                        processing: false,
-                                        warning: None,
-                                        signer: Signer::new(SpendTransactionTarget::new(
-                                            &ctx.managers_xpubs()
-                                                .iter()
-                                                .map(|xpub| xpub.master_fingerprint())
-                                                .collect(),
-                                            psbt.clone(),
-                                        )),
-                                        view: SpendTransactionSignView::new(),
-                                    };
-                                }
+                            psbts.clear();
+                            *psbt = combined
+                                .take()
+                                .expect("psbt was successfully updated with the combination");
+                            // The user keeps sharing the transaction until it can be broadcasted.
+                            let action = Self::from_context(ctx, psbt, status);
+                            if !matches!(action, Self::SharePsbt { .. }) {
+                                *self = action;
                             }
                         }
                         Err(e) => {
@@ -459,6 +667,106 @@ impl SpendTransactionAction {
                     };
                 }
             }
+            SpendTxMessage::UnselectBumpFee => {
+                if let Self::BumpFee {
+                    processing: false, ..
+                } = self
+                {
+                    *self = Self::from_context(ctx, psbt, status);
+                }
+            }
+            SpendTxMessage::Cpfp(msg) => {
+                if let Self::BumpFee {
+                    parents,
+                    feerate,
+                    signer,
+                    processing,
+                    broadcasted,
+                    error,
+                    warning,
+                    ..
+                } = self
+                {
+                    match msg {
+                        CpfpMessage::Parents(res) => match res {
+                            Ok(txs) => {
+                                if txs.is_empty() {
+                                    *error = Some(CpfpError::NothingToBump);
+                                }
+                                *parents = Some(txs);
+                            }
+                            Err(e) => *warning = Some(e.into()),
+                        },
+                        CpfpMessage::FeerateEdited(value) => {
+                            if signer.is_none() {
+                                feerate.value = value;
+                                feerate.valid = true;
+                                *error = None;
+                            }
+                        }
+                        CpfpMessage::Create => {
+                            if let (Some(parents), true) = (parents, signer.is_none()) {
+                                match feerate.value.parse::<u64>() {
+                                    Ok(rate) => match create_child(parents, rate) {
+                                        Ok(child) => {
+                                            *error = None;
+                                            *signer =
+                                                Some(Signer::new(CpfpTransactionTarget { child }));
+                                        }
+                                        Err(e) => *error = Some(e),
+                                    },
+                                    Err(_) => feerate.valid = false,
+                                }
+                            }
+                        }
+                        CpfpMessage::Sign(msg) => {
+                            if let Some(signer) = signer {
+                                let cmd = signer.update(ctx, msg);
+                                if signer.signed() && !*processing && broadcasted.is_none() {
+                                    match finalize_child(signer.target.child.clone()) {
+                                        Ok(tx) => {
+                                            *processing = true;
+                                            let cookie_path = ctx
+                                                .config
+                                                .daemon
+                                                .bitcoind_config
+                                                .cookie_path
+                                                .clone();
+                                            let addr = ctx.config.daemon.bitcoind_config.addr;
+                                            return Command::perform(
+                                                async move {
+                                                    bitcoind::send_raw_transaction(
+                                                        &cookie_path,
+                                                        addr,
+                                                        &tx,
+                                                    )
+                                                    .await
+                                                },
+                                                |res| {
+                                                    SpendTxMessage::Cpfp(CpfpMessage::Broadcasted(
+                                                        res,
+                                                    ))
+                                                },
+                                            );
+                                        }
+                                        Err(e) => *error = Some(e),
+                                    }
+                                }
+                                return cmd.map(|msg| SpendTxMessage::Cpfp(CpfpMessage::Sign(msg)));
+                            }
+                        }
+                        CpfpMessage::Broadcasted(res) => {
+                            *processing = false;
+                            match res {
+                                Ok(txid) => *broadcasted = Some(txid),
+                                Err(e) => *warning = Some(e.into()),
+                            }
+                        }
+                        // The unvault transactions are handled by the state.
+                        CpfpMessage::UnvaultTxs(_) => {}
+                    }
+                }
+            }
             _ => {}
         }
         Command::none()
@@ -466,7 +774,34 @@ impl SpendTransactionAction {
 
     fn view(&mut self, ctx: &Context, psbt: &Psbt) -> Element<Message> {
         match self {
-            Self::Processing => spend_tx_processing(),
+            Self::Processing { view } => view.view(),
+            Self::BumpFee {
+                parents,
+                feerate,
+                signer,
+                processing,
+                broadcasted,
+                error,
+                warning,
+                view,
+            } => {
+                let child = signer.as_ref().map(|signer| signer.target.child.clone());
+                view.view(
+                    ctx,
+                    parents.as_ref(),
+                    feerate,
+                    child.as_ref(),
+                    signer.as_mut().map(|signer| {
+                        signer.view(ctx).map(|msg| {
+                            Message::SpendTx(SpendTxMessage::Cpfp(CpfpMessage::Sign(msg)))
+                        })
+                    }),
+                    *processing,
+                    broadcasted.as_ref(),
+                    error.as_ref(),
+                    warning.as_ref(),
+                )
+            }
             Self::Deprecated => spend_tx_deprecated(),
             Self::Confirmed => spend_tx_confirmed(),
             Self::Sign {
@@ -483,11 +818,26 @@ impl SpendTransactionAction {
             Self::SharePsbt {
                 view,
                 psbt_input,
+                psbts,
+                combined,
                 processing,
                 success,
                 warning,
-                ..
-            } => view.view(&psbt_input, &processing, &success, psbt, warning.as_ref()),
+            } => {
+                let managers = ctx.managers_fingerprints();
+                let signed = signers(combined.as_ref().unwrap_or(psbt));
+                view.view(
+                    ctx,
+                    &psbt_input,
+                    psbts.iter().map(signers).collect(),
+                    &managers,
+                    &signed,
+                    &processing,
+                    &success,
+                    psbt,
+                    warning.as_ref(),
+                )
+            }
             Self::Broadcast {
                 with_priority,
                 view,
@@ -505,6 +855,75 @@ impl SpendTransactionAction {
     }
 }
 
+/// Parses the base64 PSBT of a manager, None if it is not the given spend transaction
+/// or if it has signatures of unknown keys.
+fn parse_managers_psbt(ctx: &Context, psbt: &Psbt, input: &str) -> Option<Psbt> {
+    let p: Psbt = bitcoin::base64::decode(input.trim())
+        .ok()
+        .and_then(|bytes| bitcoin::consensus::encode::deserialize(&bytes).ok())?;
+    if p.global.unsigned_tx.txid() != psbt.global.unsigned_tx.txid()
+        || is_unknown_sig(&ctx.managers_fingerprints(), &p)
+    {
+        return None;
+    }
+    Some(p)
+}
+
+/// Merges the partial signatures of the PSBTs in the spend transaction psbt.
+/// The PSBTs must share its unsigned transaction.
+pub fn combine_psbts(psbt: &Psbt, others: &[Psbt]) -> Result<Psbt, String> {
+    let mut combined = psbt.clone();
+    for (i, other) in others.iter().enumerate() {
+        if other.global.unsigned_tx != psbt.global.unsigned_tx {
+            return Err(format!(
+                "PSBT {} is not for transaction {}",
+                i + 1,
+                psbt.global.unsigned_tx.txid()
+            ));
+        }
+        combined
+            .merge(other.clone())
+            .map_err(|e| format!("Failed to combine PSBT {}: {}", i + 1, e))?;
+    }
+    Ok(combined)
+}
+
+/// Combines the PSBTs with the spend transaction psbt, the combination is reset if
+/// there is no PSBT or if they cannot be combined.
+fn update_combination(
+    psbt: &Psbt,
+    psbts: &[Psbt],
+    combined: &mut Option<Psbt>,
+    warning: &mut Option<Error>,
+) {
+    *combined = None;
+    *warning = None;
+    if !psbts.is_empty() {
+        match combine_psbts(psbt, psbts) {
+            Ok(p) => *combined = Some(p),
+            Err(e) => *warning = Some(Error::Unexpected(e)),
+        }
+    }
+}
+
+/// Master fingerprints of the keys that signed every input of the psbt.
+pub fn signers(psbt: &Psbt) -> Vec<Fingerprint> {
+    let mut fingerprints: Vec<Fingerprint> = psbt
+        .inputs
+        .iter()
+        .flat_map(|input| {
+            input
+                .partial_sigs
+                .keys()
+                .filter_map(move |key| input.bip32_derivation.get(key))
+                .map(|(fingerprint, _)| *fingerprint)
+        })
+        .collect();
+    fingerprints.sort();
+    fingerprints.dedup();
+    SignaturesMatrix::new(&fingerprints, psbt).signers()
+}
+
 /// Returns true if the psbt has a signature from a key with a master fingerprint
 /// not contained in the given list of fingerprints
 pub fn is_unknown_sig(fingerprints: &Vec<Fingerprint>, psbt: &Psbt) -> bool {
@@ -569,6 +988,7 @@ impl SpendTransactionListItem {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::app::fixtures::PSBT;
     use bitcoin::util::{
         bip32::ExtendedPubKey, ecdsa::PublicKey, psbt::PartiallySignedTransaction as Psbt,
     };
@@ -576,18 +996,31 @@ mod tests {
 
     #[test]
     fn test_new_spend_transaction_action() {
-        let mut psbt = Psbt::from_str("cHNidP8BALQCAAAAAc1946BSKWX5trghNlBq/IIYScLPYqr9Bqs2LfqOYuqcAAAAAAAIAAAAA+BAAAAAAAAAIgAgCOQxrx6W/t0dSZikMBNYG2Yyam/3LIoVrAy6e8ZDUAyA8PoCAAAAACIAIMuwqNTx88KHHtIR0EeURzEu9pUmbnUxd22KzYKi25A2CBH6AgAAAAAiACB18mkXdMgWd4MYRrAoIgDiiLLFlxC1j3Qxg9SSVQfbxQAAAAAAAQEruFn1BQAAAAAiACBI6M9l6zams92tyCK/4gbWyNfJMJzgoOv34L0X7GTovAEDBAEAAAABBWEhAgKTOrEDfq0KpKeFjG1J1nBeH7O8X2awCRive58A7NUmrFGHZHapFHKpXyKvmhuuuFL5qVJy+MIdmPJkiKxrdqkUtsmtuJyMk3Jsg+KhtdlHidd7lWGIrGyTUodnWLJoIgYCApM6sQN+rQqkp4WMbUnWcF4fs7xfZrAJGK97nwDs1SYIJR1gCQAAAAAAIgICUHL04HZXilyJ1B118e1Smr+S8c1qtja46Le7DzMCaUMI+93szQAAAAAAACICAlgt7b9E9GVk5djNsGdTbWDr40zR0YAc/1G7+desKJtDCNZ9f+kAAAAAIgIDRwTey1W1qoj/0e9dBjZiSMExThllURNv8U6ri7pKSQ4IcqlfIgAAAAAA").unwrap();
+        let mut psbt = Psbt::from_str(PSBT).unwrap();
         let _user_manager_xpub = ExtendedPubKey::from_str("xpub6CZFHPW1GiB8YgV7zGpeQDB6mMHZYPQyUaHrM1nMvKMgLxwok4xCtnzjuxQ3p1LHJUkz5i1Y7bRy5fmGrdg8UBVb39XdXNtWWd2wTsNd7T9").unwrap();
 
+        let manager = Fingerprint::from_str("251d6009").unwrap();
+        let other = Fingerprint::from_str("d67d7fe9").unwrap();
+        let managers = vec![manager, other];
         let action = SpendTransactionAction::new(
             2,
-            false,
-            &Vec::new(),
+            Some(manager),
+            &managers,
             &psbt,
             &model::ListSpendStatus::NonFinal,
         );
         assert!(matches!(action, SpendTransactionAction::Sign { .. }));
 
+        // A broadcasted spend can only have its fees bumped.
+        let action = SpendTransactionAction::new(
+            2,
+            Some(manager),
+            &managers,
+            &psbt,
+            &model::ListSpendStatus::Broadcasted,
+        );
+        assert!(matches!(action, SpendTransactionAction::Processing { .. }));
+
         psbt.inputs[0].partial_sigs.insert(
             PublicKey::from_str(
                 "0202933ab1037ead0aa4a7858c6d49d6705e1fb3bc5f66b00918af7b9f00ecd526",
@@ -598,17 +1031,37 @@ mod tests {
 
         let action = SpendTransactionAction::new(
             2,
-            true,
-            &Vec::new(),
+            Some(manager),
+            &managers,
             &psbt,
             &model::ListSpendStatus::NonFinal,
         );
         assert!(matches!(action, SpendTransactionAction::SharePsbt { .. }));
 
+        // The other manager did not sign yet.
+        let action = SpendTransactionAction::new(
+            2,
+            Some(other),
+            &managers,
+            &psbt,
+            &model::ListSpendStatus::NonFinal,
+        );
+        assert!(matches!(action, SpendTransactionAction::Sign { .. }));
+
+        // A signature of a key which is not a manager's one is not counted.
         let action = SpendTransactionAction::new(
             1,
-            true,
-            &Vec::new(),
+            Some(other),
+            &[other],
+            &psbt,
+            &model::ListSpendStatus::NonFinal,
+        );
+        assert!(matches!(action, SpendTransactionAction::Sign { .. }));
+
+        let action = SpendTransactionAction::new(
+            1,
+            Some(manager),
+            &managers,
             &psbt,
             &model::ListSpendStatus::NonFinal,
         );
@@ -616,11 +1069,64 @@ mod tests {
 
         let action = SpendTransactionAction::new(
             0,
-            true,
-            &Vec::new(),
+            Some(manager),
+            &managers,
             &psbt,
             &model::ListSpendStatus::NonFinal,
         );
         assert!(matches!(action, SpendTransactionAction::Broadcast { .. }));
     }
+
+    #[test]
+    fn test_combine_psbts() {
+        let psbt = Psbt::from_str(PSBT).unwrap();
+        assert!(signers(&psbt).is_empty());
+
+        // The key of the first input derived from the fingerprint 251d6009.
+        let mut signed_by_known_key = psbt.clone();
+        signed_by_known_key.inputs[0].partial_sigs.insert(
+            PublicKey::from_str(
+                "0202933ab1037ead0aa4a7858c6d49d6705e1fb3bc5f66b00918af7b9f00ecd526",
+            )
+            .unwrap(),
+            "3044".as_bytes().to_vec(),
+        );
+        let mut signed_by_other_key = psbt.clone();
+        signed_by_other_key.inputs[0].partial_sigs.insert(
+            PublicKey::from_str(
+                "025072f4e076578a5c89d41d75f1ed529abf92f1cd6ab636b8e8b7bb0f33026943",
+            )
+            .unwrap(),
+            "3045".as_bytes().to_vec(),
+        );
+
+        let combined = combine_psbts(&psbt, &[signed_by_known_key, signed_by_other_key]).unwrap();
+        assert_eq!(combined.inputs[0].partial_sigs.len(), 2);
+        assert_eq!(
+            signers(&combined),
+            vec![Fingerprint::from_str("251d6009").unwrap()]
+        );
+        assert_eq!(combine_psbts(&psbt, &[]).unwrap(), psbt);
+
+        // A manager signing only one of the inputs is not a signer.
+        let mut two_inputs = combined.clone();
+        two_inputs
+            .global
+            .unsigned_tx
+            .input
+            .push(two_inputs.global.unsigned_tx.input[0].clone());
+        two_inputs.inputs.push(psbt.inputs[0].clone());
+        assert!(signers(&two_inputs).is_empty());
+        two_inputs.inputs[1] = two_inputs.inputs[0].clone();
+        assert_eq!(
+            signers(&two_inputs),
+            vec![Fingerprint::from_str("251d6009").unwrap()]
+        );
+
+        let mut other_tx = psbt.clone();
+        other_tx.global.unsigned_tx.lock_time += 1;
+        assert!(combine_psbts(&psbt, &[psbt.clone(), other_tx])
+            .unwrap_err()
+            .contains("PSBT 2"));
+    }
 }
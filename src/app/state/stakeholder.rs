@@ -176,7 +176,7 @@ impl State for StakeholderHomeState {
                 }
                 Message::Vault(msg) => {
                     if let Some(selected) = selected_vault {
-                        return selected.update(ctx, msg).map(Message::Vault);
+                        return selected.update(ctx, msg);
                     }
                 }
                 Message::SelectHistoryEvent(i) => {
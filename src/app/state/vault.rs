@@ -5,8 +5,9 @@ use crate::{
     app::{
         context::Context,
         error::Error,
+        labels::LabelType,
         message::{Message, VaultMessage},
-        state::cmd::get_onchain_txs,
+        state::{cmd::get_onchain_txs, label::LabelEditor},
         view::{
             vault::{VaultModal, VaultView},
             LoadingModal,
@@ -49,6 +50,7 @@ pub enum Vault {
     Loaded {
         txs: VaultTransactions,
         vault: model::Vault,
+        label: LabelEditor,
         view: VaultModal,
     },
 }
@@ -69,18 +71,29 @@ impl Vault {
         }
     }
 
-    pub fn update(&mut self, _ctx: &Context, message: VaultMessage) -> Command<VaultMessage> {
-        if let Self::Loading { fail, vault, .. } = self {
-            if let VaultMessage::OnChainTransactions(res) = message {
-                match res {
-                    Ok(txs) => {
-                        *self = Self::Loaded {
-                            vault: vault.clone(),
-                            txs,
-                            view: VaultModal::new(),
+    pub fn update(&mut self, ctx: &Context, message: VaultMessage) -> Command<Message> {
+        match self {
+            Self::Loading { fail, vault, .. } => {
+                if let VaultMessage::OnChainTransactions(res) = message {
+                    match res {
+                        Ok(txs) => {
+                            *self = Self::Loaded {
+                                label: LabelEditor::new(
+                                    LabelType::Output,
+                                    outpoint(vault).to_string(),
+                                ),
+                                vault: vault.clone(),
+                                txs,
+                                view: VaultModal::new(),
+                            }
                         }
+                        Err(e) => *fail = Some(e.into()),
                     }
-                    Err(e) => *fail = Some(e.into()),
+                }
+            }
+            Self::Loaded { label, .. } => {
+                if let VaultMessage::Label(msg) = message {
+                    return label.update(ctx, msg);
                 }
             }
         }
@@ -90,7 +103,19 @@ impl Vault {
     pub fn view(&mut self, ctx: &Context) -> Element<Message> {
         match self {
             Self::Loading { view, fail, .. } => view.view(ctx, fail.as_ref(), Message::Close),
-            Self::Loaded { view, vault, txs } => view.view(ctx, vault, &txs),
+            Self::Loaded {
+                view,
+                vault,
+                txs,
+                label,
+            } => view.view(
+                ctx,
+                vault,
+                &txs,
+                label
+                    .view(ctx)
+                    .map(|msg| Message::Vault(VaultMessage::Label(msg))),
+            ),
         }
     }
 
@@ -133,7 +133,7 @@ impl State for VaultsState {
             Message::Vault(msg) => {
                 if let Self::Loaded { selected_vault, .. } = self {
                     if let Some(selected) = selected_vault {
-                        return selected.update(ctx, msg).map(Message::Vault);
+                        return selected.update(ctx, msg);
                     }
                 }
             }
@@ -0,0 +1,139 @@
+use iced::{text_input, Alignment, Column, Container, Element, Length, Row};
+
+use revault_ui::{
+    color,
+    component::{button, card, form, separation, text::Text},
+    icon::{pencil_icon, trash_icon},
+};
+
+use crate::app::{
+    address_book::Entry,
+    context::Context,
+    error::Error,
+    message::{AddressBookMessage, Message},
+    view::layout,
+};
+
+#[derive(Debug, Default)]
+pub struct AddressBookView {
+    dashboard: layout::Dashboard,
+    label_input: text_input::State,
+    address_input: text_input::State,
+    notes_input: text_input::State,
+    add_button: iced::button::State,
+    /// Edit and delete buttons of each entry.
+    entries_buttons: Vec<(iced::button::State, iced::button::State)>,
+}
+
+impl AddressBookView {
+    #[allow(clippy::too_many_arguments)]
+    pub fn view<'a>(
+        &'a mut self,
+        ctx: &Context,
+        warning: Option<&Error>,
+        entries: &[Entry],
+        label: &form::Value<String>,
+        address: &form::Value<String>,
+        notes: &form::Value<String>,
+        form_error: Option<&String>,
+        processing: bool,
+    ) -> Element<'a, Message> {
+        let mut add_button = button::primary(
+            &mut self.add_button,
+            button::button_content(None, if processing { "Saving..." } else { "Save" }),
+        )
+        .width(Length::Units(200));
+        if !processing {
+            add_button = add_button.on_press(Message::AddressBook(AddressBookMessage::Add));
+        }
+
+        let mut form_col = Column::new()
+            .push(Text::new("Add or edit an address").bold())
+            .push(
+                Row::new()
+                    .push(
+                        form::Form::new(&mut self.label_input, "Label", label, |value| {
+                            Message::AddressBook(AddressBookMessage::LabelEdited(value))
+                        })
+                        .padding(10)
+                        .render()
+                        .width(Length::FillPortion(1)),
+                    )
+                    .push(
+                        form::Form::new(&mut self.address_input, "Address", address, |value| {
+                            Message::AddressBook(AddressBookMessage::AddressEdited(value))
+                        })
+                        .padding(10)
+                        .render()
+                        .width(Length::FillPortion(2)),
+                    )
+                    .spacing(10),
+            )
+            .push(
+                form::Form::new(&mut self.notes_input, "Notes (optional)", notes, |value| {
+                    Message::AddressBook(AddressBookMessage::NotesEdited(value))
+                })
+                .padding(10)
+                .render(),
+            )
+            .push(add_button)
+            .spacing(10);
+        if let Some(error) = form_error {
+            form_col = form_col.push(Text::new(error).small().color(color::ALERT));
+        }
+
+        self.entries_buttons
+            .resize_with(entries.len(), Default::default);
+        let mut entries_col = Column::new()
+            .push(Text::new(&format!(
+                "{} addresses for {}",
+                entries
+                    .iter()
+                    .filter(|entry| entry.network == ctx.network())
+                    .count(),
+                ctx.network()
+            )))
+            .spacing(10);
+        for (i, (entry, (edit_button, delete_button))) in entries
+            .iter()
+            .zip(self.entries_buttons.iter_mut())
+            .enumerate()
+        {
+            // Entries of the other networks are kept in the file but hidden.
+            if entry.network != ctx.network() {
+                continue;
+            }
+            let mut description = Column::new()
+                .push(Text::new(&entry.label).bold())
+                .push(Text::new(&entry.address).small());
+            if !entry.notes.is_empty() {
+                description =
+                    description.push(Text::new(&entry.notes).small().color(color::DARK_GREY));
+            }
+            entries_col = entries_col.push(card::white(Container::new(
+                Row::new()
+                    .push(description.width(Length::Fill))
+                    .push(
+                        button::transparent(edit_button, Container::new(pencil_icon()))
+                            .on_press(Message::AddressBook(AddressBookMessage::Edit(i))),
+                    )
+                    .push(
+                        button::transparent(delete_button, Container::new(trash_icon()))
+                            .on_press(Message::AddressBook(AddressBookMessage::Delete(i))),
+                    )
+                    .align_items(Alignment::Center)
+                    .spacing(10),
+            )));
+        }
+
+        self.dashboard.view(
+            ctx,
+            warning,
+            Column::new()
+                .push(card::white(Container::new(form_col)))
+                .push(separation().width(Length::Fill))
+                .push(entries_col)
+                .spacing(20),
+        )
+    }
+}
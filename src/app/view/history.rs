@@ -8,7 +8,7 @@ use revault_ui::{
 };
 
 use crate::{
-    app::{context::Context, error::Error, message::Message, view::layout},
+    app::{context::Context, error::Error, labels::LabelType, message::Message, view::layout},
     daemon::model::{transaction_from_hex, HistoryEvent, HistoryEventKind, VaultTransactions},
 };
 
@@ -154,6 +154,15 @@ impl HistoryEventListItemView {
                                 .bold(),
                         )
                         .push(Text::new(&format!("{}", date)).small())
+                        .push(
+                            Text::new(
+                                ctx.labels
+                                    .get(event_label_kind(event), &event_label_reference(event))
+                                    .unwrap_or_default(),
+                            )
+                            .small()
+                            .bold(),
+                        )
                         .align_items(Alignment::Center)
                         .spacing(10),
                 )
@@ -219,6 +228,22 @@ impl HistoryEventListItemView {
     }
 }
 
+/// A deposit event is labelled with the label of its vault,
+/// the other events with the label of their transaction.
+pub fn event_label_kind(event: &HistoryEvent) -> LabelType {
+    match event.kind {
+        HistoryEventKind::Deposit => LabelType::Output,
+        HistoryEventKind::Cancel | HistoryEventKind::Spend => LabelType::Tx,
+    }
+}
+
+pub fn event_label_reference(event: &HistoryEvent) -> String {
+    match event.kind {
+        HistoryEventKind::Deposit => event.vaults[0].to_string(),
+        HistoryEventKind::Cancel | HistoryEventKind::Spend => event.txid.to_string(),
+    }
+}
+
 /// event_badge returns a badge headlining the event kind.
 fn event_badge<'a, T: 'a>(event: &HistoryEvent) -> Container<'a, T> {
     match &event.kind {
@@ -245,6 +270,7 @@ impl HistoryEventView {
         ctx: &Context,
         event: &HistoryEvent,
         txs: &Vec<VaultTransactions>,
+        label: Element<'a, Message>,
         warning: Option<&Error>,
     ) -> Element<'a, Message> {
         if txs.is_empty() {
@@ -262,6 +288,13 @@ impl HistoryEventView {
             HistoryEventKind::Cancel => cancel(ctx, event),
             HistoryEventKind::Spend => spend(ctx, event, txs),
         };
+        let content = content.push(card::white(
+            Row::new()
+                .push(Text::new("Label:").bold())
+                .push(Container::new(label).width(Length::Fill))
+                .spacing(20)
+                .align_items(Alignment::Center),
+        ));
 
         self.modal.view(
             ctx,
@@ -412,7 +445,11 @@ fn spend<'a, T: 'a>(
         let addr = bitcoin::Address::from_script(&output.script_pubkey, ctx.network());
         let mut row = Row::new();
         if let Some(a) = addr {
-            row = row.push(Text::new(&a.to_string()).small().width(Length::Fill))
+            let mut col = Column::new().push(Text::new(&a.to_string()).small());
+            if let Some(label) = ctx.labels.get(LabelType::Addr, &a.to_string()) {
+                col = col.push(Text::new(label).small().bold());
+            }
+            row = row.push(col.width(Length::Fill))
         } else {
             row = row.push(
                 Text::new(&output.script_pubkey.to_string())
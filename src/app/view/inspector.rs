@@ -0,0 +1,301 @@
+use chrono::NaiveDateTime;
+use iced::{text_input, Alignment, Column, Container, Element, Length, Row};
+
+use revault_ui::{
+    color,
+    component::{button, card, form, separation, text::Text},
+};
+
+use crate::app::{
+    context::Context,
+    error::Error,
+    inspector::{InspectedInput, InspectedOutput, Inspection, RelativeTimelock, Timelock},
+    labels::LabelType,
+    message::{InspectorMessage, Message},
+    view::layout,
+};
+
+#[derive(Debug, Default)]
+pub struct InspectorView {
+    dashboard: layout::Dashboard,
+    input: text_input::State,
+    inspect_button: iced::button::State,
+    path_input: text_input::State,
+    import_button: iced::button::State,
+}
+
+impl InspectorView {
+    pub fn view<'a>(
+        &'a mut self,
+        ctx: &Context,
+        warning: Option<&Error>,
+        input: &form::Value<String>,
+        path: &form::Value<String>,
+        error: Option<&String>,
+        inspection: Option<&Inspection>,
+    ) -> Element<'a, Message> {
+        let mut form_col = Column::new()
+            .push(Text::new("Inspect a PSBT or a raw transaction").bold())
+            .push(
+                Row::new()
+                    .push(
+                        form::Form::new(
+                            &mut self.input,
+                            "Base64 PSBT or hex transaction",
+                            input,
+                            |value| Message::Inspector(InspectorMessage::InputEdited(value)),
+                        )
+                        .warning("Please enter a PSBT or a transaction")
+                        .padding(10)
+                        .render(),
+                    )
+                    .push(
+                        button::primary(
+                            &mut self.inspect_button,
+                            button::button_content(None, "Inspect"),
+                        )
+                        .on_press(Message::Inspector(InspectorMessage::Inspect)),
+                    )
+                    .spacing(10)
+                    .align_items(Alignment::Center),
+            )
+            .push(
+                Row::new()
+                    .push(
+                        form::Form::new(&mut self.path_input, "Path to a file", path, |value| {
+                            Message::Inspector(InspectorMessage::PathEdited(value))
+                        })
+                        .warning("Please enter the path of a PSBT or transaction file")
+                        .padding(10)
+                        .render(),
+                    )
+                    .push(
+                        button::white_card_button(
+                            &mut self.import_button,
+                            button::button_content(None, "Import"),
+                        )
+                        .on_press(Message::Inspector(InspectorMessage::ImportFile)),
+                    )
+                    .spacing(10)
+                    .align_items(Alignment::Center),
+            )
+            .push(Text::new("A file can also be dropped on the window").small())
+            .spacing(10);
+        if let Some(error) = error {
+            form_col = form_col.push(Text::new(error).small().color(color::ALERT));
+        }
+
+        let mut content = Column::new()
+            .push(card::white(Container::new(form_col)))
+            .spacing(20);
+        if let Some(inspection) = inspection {
+            content = content
+                .push(separation().width(Length::Fill))
+                .push(inspection_view(ctx, inspection));
+        }
+
+        self.dashboard.view(ctx, warning, content)
+    }
+}
+
+fn inspection_view<'a>(ctx: &Context, inspection: &Inspection) -> Column<'a, Message> {
+    let mut summary = Column::new()
+        .push(
+            Row::new()
+                .push(Text::new("Tx ID:").bold().width(Length::Fill))
+                .push(Text::new(&inspection.txid.to_string()).small())
+                .align_items(Alignment::Center),
+        )
+        .push(
+            Row::new()
+                .push(Text::new("Type:").bold().width(Length::Fill))
+                .push(Text::new(
+                    match (inspection.is_psbt, inspection.finalized) {
+                        (true, true) => "Finalized PSBT",
+                        (true, false) => "PSBT",
+                        (false, true) => "Signed transaction",
+                        (false, false) => "Unsigned transaction",
+                    },
+                )),
+        )
+        .push(
+            Row::new()
+                .push(Text::new("Version:").bold().width(Length::Fill))
+                .push(Text::new(&inspection.version.to_string())),
+        )
+        .push(
+            Row::new()
+                .push(Text::new("Virtual size:").bold().width(Length::Fill))
+                .push(Text::new(&format!("{} vbytes", inspection.vsize))),
+        )
+        .push(
+            Row::new()
+                .push(Text::new("Lock time:").bold().width(Length::Fill))
+                .push(Text::new(&match inspection.lock_time {
+                    None => "None".to_string(),
+                    Some(Timelock::Height(height)) => format!("Block {}", height),
+                    Some(Timelock::Time(time)) => {
+                        NaiveDateTime::from_timestamp(time.into(), 0).to_string()
+                    }
+                })),
+        )
+        .spacing(10);
+    summary = match inspection.fees {
+        Some(fees) => summary
+            .push(
+                Row::new()
+                    .push(Text::new("Miner Fee:").bold().width(Length::Fill))
+                    .push(Text::new(&format!(
+                        "{} {}",
+                        ctx.converter.converts(fees),
+                        ctx.converter.unit,
+                    ))),
+            )
+            .push(
+                Row::new()
+                    .push(Text::new("Feerate:").bold().width(Length::Fill))
+                    .push(Text::new(&format!(
+                        "{} sats/vbyte",
+                        inspection.feerate().unwrap_or(0)
+                    ))),
+            ),
+        None => summary.push(
+            Text::new("The fees are unknown: the amount of an input is missing")
+                .small()
+                .color(color::DARK_GREY),
+        ),
+    };
+
+    let mut inputs = Column::new()
+        .push(Text::new(&format!("{} inputs", inspection.inputs.len())).bold())
+        .spacing(10);
+    for input in &inspection.inputs {
+        inputs = inputs.push(card::white(Container::new(input_view(ctx, input))));
+    }
+
+    let mut outputs = Column::new()
+        .push(Text::new(&format!("{} outputs", inspection.outputs.len())).bold())
+        .spacing(10);
+    for output in &inspection.outputs {
+        outputs = outputs.push(card::white(Container::new(output_view(ctx, output))));
+    }
+
+    Column::new()
+        .push(card::white(Container::new(summary)))
+        .push(inputs)
+        .push(outputs)
+        .spacing(20)
+}
+
+fn input_view<'a>(ctx: &Context, input: &InspectedInput) -> Column<'a, Message> {
+    let mut col = Column::new()
+        .push(
+            Row::new()
+                .push(
+                    Text::new(&input.previous_output.to_string())
+                        .small()
+                        .width(Length::Fill),
+                )
+                .push(Text::new(&match input.amount {
+                    Some(amount) => {
+                        format!("{} {}", ctx.converter.converts(amount), ctx.converter.unit)
+                    }
+                    None => "Unknown amount".to_string(),
+                }))
+                .spacing(10)
+                .align_items(Alignment::Center),
+        )
+        .spacing(5);
+    if let Some((kind, index)) = input.script {
+        col = col.push(
+            Text::new(&format!("Spends a {} output of index {}", kind, index))
+                .small()
+                .success(),
+        );
+    }
+    if let Some(vault) = input.vault {
+        col = col.push(
+            Text::new(
+                &match ctx.labels.get(LabelType::Output, &vault.to_string()) {
+                    Some(label) => format!("Vault {} ({})", vault, label),
+                    None => format!("Vault {}", vault),
+                },
+            )
+            .small()
+            .bold(),
+        );
+    }
+    col = col.push(
+        Text::new(&format!(
+            "Sequence: {:#010x}{}",
+            input.sequence,
+            match input.relative_timelock {
+                Some(RelativeTimelock::Blocks(blocks)) =>
+                    format!(", relative timelock of {} blocks", blocks),
+                Some(RelativeTimelock::Seconds(seconds)) => {
+                    format!(", relative timelock of {} seconds", seconds)
+                }
+                None => String::new(),
+            }
+        ))
+        .small(),
+    );
+    for signature in &input.signatures {
+        col = col.push(
+            Text::new(&format!(
+                "Signed by {} ({})",
+                signature
+                    .fingerprint
+                    .map(|fingerprint| ctx.manager_name(&fingerprint))
+                    .unwrap_or_else(|| "an unknown key".to_string()),
+                signature.pubkey
+            ))
+            .small(),
+        );
+    }
+    if input.witness_signatures > 0 {
+        col = col.push(
+            Text::new(&format!(
+                "{} signatures in the witness",
+                input.witness_signatures
+            ))
+            .small(),
+        );
+    }
+    if input.signatures.is_empty() && input.witness_signatures == 0 {
+        col = col.push(Text::new("No signature").small().color(color::DARK_GREY));
+    }
+    col
+}
+
+fn output_view<'a>(ctx: &Context, output: &InspectedOutput) -> Column<'a, Message> {
+    let mut col = Column::new()
+        .push(
+            Row::new()
+                .push(
+                    Text::new(
+                        &bitcoin::Address::from_script(&output.script_pubkey, ctx.network())
+                            .map(|address| address.to_string())
+                            .unwrap_or_else(|| output.script_pubkey.to_string()),
+                    )
+                    .small()
+                    .width(Length::Fill),
+                )
+                .push(Text::new(&format!(
+                    "{} {}",
+                    ctx.converter.converts(output.amount),
+                    ctx.converter.unit
+                )))
+                .spacing(10)
+                .align_items(Alignment::Center),
+        )
+        .spacing(5);
+    if let Some((kind, index)) = output.script {
+        col = col.push(
+            Text::new(&format!("{} output of index {}", kind, index))
+                .small()
+                .success(),
+        );
+    }
+    col
+}
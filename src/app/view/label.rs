@@ -0,0 +1,68 @@
+use iced::{text_input, Alignment, Container, Element, Length, Row};
+
+use revault_ui::{
+    color,
+    component::{button, form, text::Text},
+    icon::pencil_icon,
+};
+
+use crate::app::message::LabelMessage;
+
+#[derive(Debug, Default)]
+pub struct LabelEditorView {
+    edit_button: iced::button::State,
+    label_input: text_input::State,
+    confirm_button: iced::button::State,
+    cancel_button: iced::button::State,
+}
+
+impl LabelEditorView {
+    pub fn view<'a>(
+        &'a mut self,
+        label: Option<&str>,
+        editing: Option<&form::Value<String>>,
+    ) -> Element<'a, LabelMessage> {
+        if let Some(value) = editing {
+            return Row::new()
+                .push(
+                    form::Form::new(&mut self.label_input, "Label", value, LabelMessage::Edited)
+                        .padding(10)
+                        .render()
+                        .width(Length::Fill),
+                )
+                .push(
+                    button::primary(
+                        &mut self.confirm_button,
+                        button::button_content(None, "Save"),
+                    )
+                    .width(Length::Units(100))
+                    .on_press(LabelMessage::Confirm),
+                )
+                .push(
+                    button::cancel(
+                        &mut self.cancel_button,
+                        button::button_content(None, "Cancel"),
+                    )
+                    .width(Length::Units(100))
+                    .on_press(LabelMessage::Cancel),
+                )
+                .spacing(10)
+                .align_items(Alignment::Center)
+                .into();
+        }
+
+        Row::new()
+            .push(if let Some(label) = label {
+                Text::new(label).bold()
+            } else {
+                Text::new("No label").color(color::DARK_GREY)
+            })
+            .push(
+                button::transparent(&mut self.edit_button, Container::new(pencil_icon()))
+                    .on_press(LabelMessage::Edit),
+            )
+            .spacing(10)
+            .align_items(Alignment::Center)
+            .into()
+    }
+}
@@ -18,12 +18,18 @@ use revault_ui::{
 
 use crate::{
     app::{
+        address_book::Entry,
+        coin_selection::CoinSelection,
         context::Context,
         error::Error,
+        labels::LabelType,
         menu::Menu,
-        message::{InputMessage, Message, RecipientMessage, SpendTxMessage},
+        message::{InputMessage, Message, RecipientMessage, RecipientsCsvMessage, SpendTxMessage},
+        recipients::ImportedRecipients,
+        spend_check::SpendReport,
         view::{layout, warning::warn},
     },
+    bitcoind,
     daemon::model,
 };
 
@@ -32,6 +38,10 @@ pub struct ManagerImportTransactionView {
     modal: layout::Modal,
     psbt_input: iced::text_input::State,
     import_button: iced::button::State,
+    psbt_path_input: iced::text_input::State,
+    import_file_button: iced::button::State,
+    confirm_button: iced::button::State,
+    retry_button: iced::button::State,
 }
 
 impl ManagerImportTransactionView {
@@ -40,6 +50,10 @@ impl ManagerImportTransactionView {
             modal: layout::Modal::default(),
             psbt_input: iced::text_input::State::new(),
             import_button: iced::button::State::new(),
+            psbt_path_input: iced::text_input::State::new(),
+            import_file_button: iced::button::State::new(),
+            confirm_button: iced::button::State::new(),
+            retry_button: iced::button::State::new(),
         }
     }
 
@@ -47,7 +61,11 @@ impl ManagerImportTransactionView {
         &'a mut self,
         ctx: &Context,
         psbt_input: &form::Value<String>,
+        psbt_path: &form::Value<String>,
         psbt_imported: Option<&Psbt>,
+        report: Option<&SpendReport>,
+        loading: bool,
+        load_failed: bool,
         warning: Option<&Error>,
     ) -> Element<'a, Message> {
         let mut col = Column::new()
@@ -76,13 +94,84 @@ impl ManagerImportTransactionView {
                 .width(Length::Fill),
             );
         } else {
-            col = col.push(
-                button::primary(
-                    &mut self.import_button,
-                    button::button_content(None, "Import transaction"),
-                )
-                .on_press(Message::SpendTx(SpendTxMessage::Import)),
+            if let Some(report) = report {
+                let mut report_col = Column::new()
+                    .push(Text::new("The checks of the transaction found issues:").bold())
+                    .spacing(10);
+                for issue in &report.issues {
+                    report_col = report_col.push(Text::new(&format!("- {}", issue)).small());
+                }
+                if report.is_blocking() {
+                    report_col = report_col.push(
+                        Text::new("The transaction cannot be imported.")
+                            .small()
+                            .bold(),
+                    );
+                } else {
+                    report_col = report_col.push(
+                        button::primary(
+                            &mut self.confirm_button,
+                            button::button_content(None, "Import anyway"),
+                        )
+                        .on_press(Message::SpendTx(SpendTxMessage::ConfirmImport)),
+                    );
+                }
+                col = col.push(card::alert_warning(Container::new(report_col)).width(Length::Fill));
+            }
+            // The transaction is checked against the vaults, it cannot be imported
+            // before they are loaded.
+            let mut import_button = button::primary(
+                &mut self.import_button,
+                button::button_content(None, "Import transaction"),
             );
+            let mut import_file_button = button::white_card_button(
+                &mut self.import_file_button,
+                button::button_content(None, "Import file"),
+            );
+            if load_failed {
+                col = col.push(
+                    Row::new()
+                        .push(Text::new("Failed to load the vaults.").small())
+                        .push(
+                            button::white_card_button(
+                                &mut self.retry_button,
+                                button::button_content(None, "Retry"),
+                            )
+                            .on_press(Message::Reload),
+                        )
+                        .spacing(10)
+                        .align_items(Alignment::Center),
+                );
+            } else if loading {
+                col = col.push(Text::new("Loading the vaults...").small());
+            } else {
+                import_button = import_button.on_press(Message::SpendTx(SpendTxMessage::Import));
+                import_file_button =
+                    import_file_button.on_press(Message::SpendTx(SpendTxMessage::ImportPsbtFile));
+            }
+            col = col
+                .push(import_button)
+                .push(separation().width(Length::Fill))
+                .push(Text::new(
+                    "Or import a .psbt file, or drop it on the window:",
+                ))
+                .push(
+                    Row::new()
+                        .push(
+                            form::Form::new(
+                                &mut self.psbt_path_input,
+                                "Path to a .psbt file",
+                                psbt_path,
+                                |path| Message::SpendTx(SpendTxMessage::PsbtPathEdited(path)),
+                            )
+                            .warning("Please enter the path of a readable PSBT file")
+                            .padding(10)
+                            .render(),
+                        )
+                        .push(import_file_button)
+                        .spacing(10)
+                        .align_items(Alignment::Center),
+                );
         }
 
         self.modal.view(
@@ -231,6 +320,9 @@ pub struct ManagerSelectOutputsView {
     cancel_button: iced::button::State,
     next_button: iced::button::State,
     new_output_button: iced::button::State,
+    csv_path_input: text_input::State,
+    import_csv_button: iced::button::State,
+    export_csv_button: iced::button::State,
 }
 
 impl ManagerSelectOutputsView {
@@ -240,14 +332,24 @@ impl ManagerSelectOutputsView {
             next_button: iced::button::State::new(),
             scroll: scrollable::State::new(),
             new_output_button: iced::button::State::new(),
+            csv_path_input: text_input::State::new(),
+            import_csv_button: iced::button::State::new(),
+            export_csv_button: iced::button::State::new(),
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn view<'a>(
         &'a mut self,
+        ctx: &Context,
         selected_outputs: Vec<Element<'a, Message>>,
         valid: bool,
         no_duplicate: bool,
+        output_amount: u64,
+        csv_path: &form::Value<String>,
+        csv_import: Option<&ImportedRecipients>,
+        csv_exported: bool,
+        warning: Option<&Error>,
     ) -> Element<'a, Message> {
         let header = Column::new()
             .push(
@@ -270,6 +372,7 @@ impl ManagerSelectOutputsView {
             .push(ProgressBar::spend_bar().draw(0))
             .align_items(Alignment::Center);
 
+        let number_of_recipients = selected_outputs.len();
         let mut col_outputs = Column::new()
             .spacing(20)
             .width(Length::Fill)
@@ -282,6 +385,69 @@ impl ManagerSelectOutputsView {
         }
         let element: Element<_> = col_outputs.max_width(1000).into();
 
+        let mut csv = Column::new()
+            .push(
+                Row::new()
+                    .push(
+                        form::Form::new(
+                            &mut self.csv_path_input,
+                            "Path to a CSV file: address,amount,label",
+                            csv_path,
+                            |path| Message::RecipientsCsv(RecipientsCsvMessage::PathEdited(path)),
+                        )
+                        .warning("Please enter the path of a readable CSV file")
+                        .padding(10)
+                        .render(),
+                    )
+                    .push(
+                        button::white_card_button(
+                            &mut self.import_csv_button,
+                            button::button_content(None, "Import"),
+                        )
+                        .on_press(Message::RecipientsCsv(RecipientsCsvMessage::Import)),
+                    )
+                    .push(
+                        button::white_card_button(
+                            &mut self.export_csv_button,
+                            button::button_content(None, "Export"),
+                        )
+                        .on_press(Message::RecipientsCsv(RecipientsCsvMessage::Export)),
+                    )
+                    .spacing(10)
+                    .align_items(Alignment::Center),
+            )
+            .spacing(10)
+            .max_width(1000);
+
+        if let Some(imported) = csv_import {
+            let mut summary = Column::new().push(Text::new(&format!(
+                "{} recipients imported for {} {}, {} recipients in total for {} {}",
+                imported.recipients.len(),
+                ctx.converter.converts(imported.total()),
+                ctx.converter.unit,
+                number_of_recipients,
+                ctx.converter.converts(Amount::from_sat(output_amount)),
+                ctx.converter.unit,
+            )));
+            for error in &imported.errors {
+                summary = summary.push(Text::new(&error.to_string()).small().color(color::ALERT));
+            }
+            csv = csv.push(card::simple(Container::new(summary.spacing(5))));
+        }
+
+        if csv_exported {
+            csv = csv.push(card::border_success(Container::new(Text::new(&format!(
+                "Recipients exported to {}",
+                csv_path.value
+            )))));
+        }
+
+        if let Some(error) = warning {
+            csv = csv.push(card::alert_warning(Container::new(
+                Text::new(&error.to_string()).small(),
+            )));
+        }
+
         let mut footer = Row::new()
             .spacing(20)
             .push(Space::with_width(Length::Fill))
@@ -329,6 +495,7 @@ impl ManagerSelectOutputsView {
                                 .width(Length::Fill)
                                 .center_x(),
                         )
+                        .push(Container::new(csv).width(Length::Fill).center_x())
                         .push(scroll(
                             &mut self.scroll,
                             Container::new(
@@ -370,7 +537,9 @@ impl ManagerSelectOutputsView {
 pub struct ManagerSendOutputView {
     address_input: text_input::State,
     amount_input: text_input::State,
+    label_input: text_input::State,
     delete_button: iced::button::State,
+    suggestion_buttons: Vec<iced::button::State>,
 }
 
 impl ManagerSendOutputView {
@@ -378,15 +547,20 @@ impl ManagerSendOutputView {
         Self {
             address_input: text_input::State::focused(),
             amount_input: text_input::State::new(),
+            label_input: text_input::State::new(),
             delete_button: iced::button::State::new(),
+            suggestion_buttons: Vec::new(),
         }
     }
     pub fn view(
         &mut self,
         address: &form::Value<String>,
         amount: &form::Value<String>,
+        label: &form::Value<String>,
+        suggestions: &[&Entry],
+        unknown_address: bool,
     ) -> Element<RecipientMessage> {
-        Row::new()
+        let row = Row::new()
             .push(
                 form::Form::new(
                     &mut self.address_input,
@@ -411,6 +585,17 @@ impl ManagerSendOutputView {
                 .render()
                 .width(Length::FillPortion(1)),
             )
+            .push(
+                form::Form::new(
+                    &mut self.label_input,
+                    "Label (optional)",
+                    label,
+                    RecipientMessage::LabelEdited,
+                )
+                .padding(10)
+                .render()
+                .width(Length::FillPortion(1)),
+            )
             .push(
                 Container::new(
                     button::transparent(&mut self.delete_button, Container::new(trash_icon()))
@@ -419,8 +604,37 @@ impl ManagerSendOutputView {
                 .width(Length::Shrink)
                 .align_x(alignment::Horizontal::Right),
             )
-            .spacing(20)
-            .into()
+            .spacing(20);
+
+        let mut col = Column::new().push(row).spacing(5);
+        if unknown_address {
+            col = col.push(
+                Text::new("This address is not in the address book, please check it twice")
+                    .small()
+                    .color(color::WARNING),
+            );
+        }
+        self.suggestion_buttons
+            .resize_with(suggestions.len(), Default::default);
+        for (entry, state) in suggestions.iter().zip(self.suggestion_buttons.iter_mut()) {
+            col = col.push(
+                button::white_card_button(
+                    state,
+                    Container::new(
+                        Row::new()
+                            .push(Text::new(&entry.label).bold())
+                            .push(Text::new(&entry.address).small())
+                            .spacing(10)
+                            .align_items(Alignment::Center),
+                    ),
+                )
+                .on_press(RecipientMessage::SuggestionSelected(
+                    entry.address.clone(),
+                    entry.label.clone(),
+                )),
+            );
+        }
+        col.into()
     }
 }
 
@@ -430,6 +644,7 @@ pub struct ManagerSelectInputsView {
     back_button: iced::button::State,
     cancel_button: iced::button::State,
     next_button: iced::button::State,
+    pick_coin_selection: pick_list::State<CoinSelection>,
 }
 
 impl ManagerSelectInputsView {
@@ -439,15 +654,19 @@ impl ManagerSelectInputsView {
             back_button: iced::button::State::new(),
             next_button: iced::button::State::new(),
             scroll: scrollable::State::new(),
+            pick_coin_selection: pick_list::State::default(),
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn view<'a>(
         &'a mut self,
         ctx: &Context,
         inputs: Vec<Element<'a, Message>>,
         input_amount: u64,
         output_amount: u64,
+        estimated_fees: u64,
+        coin_selection: Option<CoinSelection>,
         warning: Option<&Error>,
     ) -> Element<'a, Message> {
         let header = Column::new()
@@ -489,6 +708,23 @@ impl ManagerSelectInputsView {
                 Text::new(&error.to_string()).small(),
             )));
         }
+        if input_amount > output_amount + estimated_fees {
+            footer = footer.push(card::simple(Container::new(
+                Text::new(&format!(
+                    "About {} {} of change will go back to a new deposit address of the wallet \
+                    once the miner fees of about {} {} are paid.\n\
+                    The change becomes a new vault which must be secured and activated before \
+                    being spent.",
+                    ctx.converter.converts(Amount::from_sat(
+                        input_amount - output_amount - estimated_fees
+                    )),
+                    ctx.converter.unit,
+                    ctx.converter.converts(Amount::from_sat(estimated_fees)),
+                    ctx.converter.unit,
+                ))
+                .small(),
+            )));
+        }
         if input_amount != 0 {
             footer = footer.push(
                 Container::new(
@@ -565,6 +801,33 @@ impl ManagerSelectInputsView {
                             .width(Length::Fill)
                             .center_x()
                         )
+                        .push(
+                            Container::new(
+                                Row::new()
+                                    .push(Text::new("Select automatically:"))
+                                    .push(
+                                        pick_list::PickList::new(
+                                            &mut self.pick_coin_selection,
+                                            &CoinSelection::ALL[..],
+                                            coin_selection,
+                                            |strategy| {
+                                                Message::SpendTx(SpendTxMessage::SelectCoins(
+                                                    strategy,
+                                                ))
+                                            },
+                                        )
+                                        .placeholder("Selected by hand")
+                                        .text_size(20)
+                                        .padding(10)
+                                        .width(Length::Units(200))
+                                        .style(TransparentPickListStyle),
+                                    )
+                                    .spacing(10)
+                                    .align_items(Alignment::Center),
+                            )
+                            .width(Length::Fill)
+                            .center_x()
+                        )
                         .push(scroll(
                             &mut self.scroll,
                             Container::new(
@@ -659,6 +922,9 @@ pub struct ManagerSelectFeeView {
     back_button: iced::button::State,
     generate_button: iced::button::State,
     feerate_input: iced::text_input::State,
+    slow_button: iced::button::State,
+    normal_button: iced::button::State,
+    fast_button: iced::button::State,
 }
 
 impl ManagerSelectFeeView {
@@ -668,6 +934,9 @@ impl ManagerSelectFeeView {
             back_button: iced::button::State::new(),
             generate_button: iced::button::State::new(),
             feerate_input: iced::text_input::State::new(),
+            slow_button: iced::button::State::new(),
+            normal_button: iced::button::State::new(),
+            fast_button: iced::button::State::new(),
         }
     }
 
@@ -675,6 +944,7 @@ impl ManagerSelectFeeView {
         &'a mut self,
         feerate: Option<u64>,
         valid_feerate: bool,
+        fee_estimates: Option<&Result<bitcoind::FeeEstimates, bitcoind::Error>>,
         warning: Option<&Error>,
     ) -> Element<'a, Message> {
         let header = Column::new()
@@ -742,6 +1012,40 @@ impl ManagerSelectFeeView {
                             .width(Length::Fill)
                             .center_x(),
                     )
+                    .push(match fee_estimates {
+                        Some(Ok(estimates)) => Container::new(
+                            Row::new()
+                                .push(fee_preset(
+                                    &mut self.slow_button,
+                                    "Slow",
+                                    estimates.slow,
+                                    feerate,
+                                ))
+                                .push(fee_preset(
+                                    &mut self.normal_button,
+                                    "Normal",
+                                    estimates.normal,
+                                    feerate,
+                                ))
+                                .push(fee_preset(
+                                    &mut self.fast_button,
+                                    "Fast",
+                                    estimates.fast,
+                                    feerate,
+                                ))
+                                .spacing(20),
+                        ),
+                        Some(Err(e)) => Container::new(
+                            Text::new(&format!("No feerate estimation: {}", e))
+                                .small()
+                                .color(color::SECONDARY),
+                        ),
+                        None => Container::new(
+                            Text::new("Estimating the feerate...")
+                                .small()
+                                .color(color::SECONDARY),
+                        ),
+                    })
                     .push(
                         Row::new()
                             .push(
@@ -760,11 +1064,26 @@ impl ManagerSelectFeeView {
                             .spacing(5)
                             .align_items(Alignment::Center),
                     )
+                    .spacing(20)
                     .align_items(Alignment::Center),
             )
             .height(Length::Fill)
+            .spacing(20)
             .align_items(Alignment::Center);
 
+        if let (Some(Ok(estimates)), Some(feerate)) = (fee_estimates, feerate) {
+            if feerate < estimates.mempool_min_feerate {
+                col_fee = col_fee.push(card::alert_warning(Container::new(
+                    Text::new(&format!(
+                        "The feerate is below the minimum of the bitcoind mempool ({} sats/vbyte), \
+                        the transaction will not be relayed",
+                        estimates.mempool_min_feerate
+                    ))
+                    .small(),
+                )));
+            }
+        }
+
         if let Some(error) = warning {
             col_fee = col_fee.push(card::alert_warning(Container::new(
                 Text::new(&error.to_string()).small(),
@@ -792,6 +1111,46 @@ impl ManagerSelectFeeView {
     }
 }
 
+/// Button setting the feerate to the estimate of bitcoind for the preset target.
+fn fee_preset<'a>(
+    state: &'a mut iced::button::State,
+    name: &str,
+    estimate: Option<bitcoind::FeeEstimate>,
+    feerate: Option<u64>,
+) -> Element<'a, Message> {
+    let estimate = match estimate {
+        Some(estimate) => estimate,
+        None => {
+            return card::simple(Container::new(
+                Column::new()
+                    .push(Text::new(name).bold())
+                    .push(Text::new("Not enough data").small())
+                    .align_items(Alignment::Center),
+            ))
+            .width(Length::Units(150))
+            .into()
+        }
+    };
+    let content = Container::new(
+        Column::new()
+            .push(Text::new(name).bold())
+            .push(Text::new(&format!("{} sats/vbyte", estimate.feerate)))
+            .push(Text::new(&format!("~{} blocks", estimate.blocks)).small())
+            .align_items(Alignment::Center),
+    )
+    .width(Length::Units(150))
+    .center_x();
+    if feerate == Some(estimate.feerate) {
+        button::primary(state, content.padding(10))
+    } else {
+        button::white_card_button(state, content)
+    }
+    .on_press(Message::SpendTx(SpendTxMessage::FeerateEdited(
+        estimate.feerate.to_string(),
+    )))
+    .into()
+}
+
 pub fn spend_tx_with_feerate_view<'a, T: 'a>(
     ctx: &Context,
     inputs: &[model::Vault],
@@ -868,9 +1227,13 @@ pub fn spend_tx_with_feerate_view<'a, T: 'a>(
         }
 
         let addr = bitcoin::Address::from_script(&output.script_pubkey, ctx.network()).unwrap();
+        let mut recipient = Column::new().push(Text::new(&addr.to_string()).small());
+        if let Some(label) = ctx.labels.get(LabelType::Addr, &addr.to_string()) {
+            recipient = recipient.push(Text::new(label).small().bold());
+        }
         col_output = col_output.push(card::simple(Container::new(
             Row::new()
-                .push(Container::new(Text::new(&addr.to_string()).small()).width(Length::Fill))
+                .push(Container::new(recipient).width(Length::Fill))
                 .push(
                     Container::new(
                         Text::new(&format!(
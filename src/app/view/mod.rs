@@ -1,7 +1,10 @@
+mod address_book;
 mod deposit;
 mod emergency;
-mod history;
+pub mod history;
 mod home;
+mod inspector;
+mod label;
 mod layout;
 pub mod manager;
 mod revault;
@@ -14,10 +17,13 @@ pub mod vault;
 mod vaults;
 mod warning;
 
+pub use address_book::AddressBookView;
 pub use deposit::DepositView;
 pub use emergency::{EmergencyTriggeredView, EmergencyView};
 pub use history::{HistoryEventListItemView, HistoryEventView, HistoryView};
 pub use home::{ManagerHomeView, StakeholderHomeView};
+pub use inspector::InspectorView;
+pub use label::LabelEditorView;
 pub use revault::{RevaultSelectVaultsView, RevaultSuccessView, RevaultVaultListItemView};
 pub use settings::SettingsView;
 pub use spend_transaction::{SpendTransactionListItemView, SpendTransactionView};
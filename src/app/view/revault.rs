@@ -7,7 +7,13 @@ use revault_ui::{
     util::Collection,
 };
 
-use crate::app::{context::Context, error::Error, menu::Menu, message::Message, view::layout};
+use crate::app::{
+    context::Context,
+    error::Error,
+    menu::Menu,
+    message::Message,
+    view::{layout, vault::vault_label},
+};
 
 use crate::daemon::model::{outpoint, Vault};
 
@@ -159,6 +165,7 @@ impl RevaultVaultListItemView {
                             } else {
                                 badge::square()
                             })
+                            .push(vault_label(ctx, vault))
                             .spacing(20)
                             .align_items(Alignment::Center),
                     )
@@ -1,4 +1,6 @@
-use iced::{alignment, text_input, Alignment, Column, Container, Element, Length, Row};
+use iced::{
+    alignment, qr_code, text_input, Alignment, Column, Container, Element, Length, QRCode, Row,
+};
 
 use revault_ui::{
     color,
@@ -10,27 +12,82 @@ use crate::{
     app::{
         context::Context,
         error::Error,
-        message::{Message, SettingsMessage},
+        labels::ImportedLabels,
+        message::{LabelsMessage, Message, SettingsMessage},
         view::layout,
     },
-    revault::Role,
+    bitcoind::{self, BlockchainInfo},
+    revault::{Role, SetupFingerprint},
+    server,
 };
 
 #[derive(Debug, Default)]
 pub struct SettingsView {
     dashboard: layout::Dashboard,
     add_watchtower_button: iced::button::State,
+    qr_code_button: iced::button::State,
+    qr_code: Option<qr_code::State>,
 }
 
 impl SettingsView {
+    // Fingerprint is loaded directly in the view in order to cache the created qrcode.
+    pub fn load(&mut self, fingerprint: &SetupFingerprint) {
+        self.qr_code = qr_code::State::new(fingerprint.to_hex()).ok();
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn view<'a>(
         &'a mut self,
         ctx: &Context,
         warning: Option<&Error>,
         can_edit: bool,
+        fingerprint: &SetupFingerprint,
+        show_fingerprint_qr_code: bool,
         settings: Vec<Element<'a, Message>>,
+        labels: Element<'a, Message>,
     ) -> Element<'a, Message> {
-        let mut col = Column::with_children(settings).spacing(20);
+        let mut fingerprint_col = Column::new()
+            .push(
+                Row::new()
+                    .push(
+                        Column::new()
+                            .push(Text::new("Setup fingerprint:"))
+                            .push(Text::new(&fingerprint.words().join(" ")).bold())
+                            .push(Text::new(&fingerprint.to_hex()).small())
+                            .width(Length::Fill),
+                    )
+                    .push(
+                        button::white_card_button(
+                            &mut self.qr_code_button,
+                            button::button_content(
+                                None,
+                                if show_fingerprint_qr_code {
+                                    "Hide QR code"
+                                } else {
+                                    "Show QR code"
+                                },
+                            ),
+                        )
+                        .on_press(Message::ToggleFingerprintQRCode),
+                    )
+                    .align_items(Alignment::Center),
+            )
+            .spacing(20);
+        if show_fingerprint_qr_code {
+            if let Some(qr_code) = self.qr_code.as_mut() {
+                fingerprint_col = fingerprint_col.push(
+                    Container::new(QRCode::new(qr_code).cell_size(5))
+                        .width(Length::Fill)
+                        .center_x(),
+                );
+            }
+        }
+
+        let mut col = Column::new()
+            .push(card::white(fingerprint_col))
+            .push(Column::with_children(settings).spacing(20))
+            .push(labels)
+            .spacing(20);
         if can_edit && ctx.role == Role::Stakeholder {
             col = col.push(
                 Container::new(
@@ -48,8 +105,83 @@ impl SettingsView {
     }
 }
 
+#[derive(Debug, Default)]
+pub struct LabelsSettingsView {
+    path_input: text_input::State,
+    import_button: iced::button::State,
+    export_button: iced::button::State,
+}
+
+impl LabelsSettingsView {
+    pub fn view<'a>(
+        &'a mut self,
+        ctx: &Context,
+        path: &form::Value<String>,
+        imported: Option<&ImportedLabels>,
+        exported: bool,
+    ) -> Element<'a, Message> {
+        let mut col = Column::new()
+            .push(
+                Row::new()
+                    .push(Text::new("Labels").bold().width(Length::Fill))
+                    .push(Text::new(&format!("{} labels", ctx.labels.len())).small())
+                    .align_items(Alignment::Center),
+            )
+            .push(
+                Row::new()
+                    .push(
+                        form::Form::new(
+                            &mut self.path_input,
+                            "Path to a BIP-329 file",
+                            path,
+                            |path| Message::Labels(LabelsMessage::PathEdited(path)),
+                        )
+                        .warning("Please enter the path of a readable BIP-329 file")
+                        .padding(10)
+                        .render(),
+                    )
+                    .push(
+                        button::white_card_button(
+                            &mut self.import_button,
+                            button::button_content(None, "Import"),
+                        )
+                        .on_press(Message::Labels(LabelsMessage::Import)),
+                    )
+                    .push(
+                        button::white_card_button(
+                            &mut self.export_button,
+                            button::button_content(None, "Export"),
+                        )
+                        .on_press(Message::Labels(LabelsMessage::Export)),
+                    )
+                    .spacing(10)
+                    .align_items(Alignment::Center),
+            )
+            .spacing(20);
+
+        if let Some(imported) = imported {
+            let mut summary =
+                Column::new().push(Text::new(&format!("{} labels imported", imported.imported)));
+            for error in &imported.errors {
+                summary = summary.push(Text::new(&error.to_string()).small().color(color::ALERT));
+            }
+            col = col.push(card::simple(Container::new(summary.spacing(5))));
+        }
+
+        if exported {
+            col = col.push(card::border_success(Container::new(Text::new(&format!(
+                "Labels exported to {}",
+                path.value
+            )))));
+        }
+
+        card::white(Container::new(col)).into()
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct BitcoindSettingsEditView {
+    test_button: iced::button::State,
     cancel_button: iced::button::State,
     confirm_button: iced::button::State,
 
@@ -65,6 +197,8 @@ impl BitcoindSettingsEditView {
         addr: &form::Value<String>,
         cookie_path: &form::Value<String>,
         processing: bool,
+        testing: bool,
+        connection: Option<&Result<BlockchainInfo, bitcoind::Error>>,
     ) -> Element<'a, SettingsMessage> {
         let mut col = Column::new().spacing(20);
         if blockheight != 0 {
@@ -130,6 +264,29 @@ impl BitcoindSettingsEditView {
                     .spacing(5),
             );
 
+        match connection {
+            Some(Ok(info)) => {
+                col = col.push(
+                    Text::new(&format!(
+                        "Connected to bitcoind on chain '{}', {} blocks of {} headers, sync progress: {:.2}%",
+                        info.chain,
+                        info.blocks,
+                        info.headers,
+                        info.sync_progress()
+                    ))
+                    .color(color::SUCCESS),
+                )
+            }
+            Some(Err(e)) => {
+                col = col.push(card::alert_warning(Container::new(
+                    Text::new(&e.to_string()).small(),
+                )))
+            }
+            None => {}
+        }
+
+        let test_button = test_connection_button(&mut self.test_button, testing, processing);
+
         let mut cancel_button = button::cancel(
             &mut self.cancel_button,
             Container::new(Text::new(" Cancel ")).padding(5),
@@ -159,6 +316,7 @@ impl BitcoindSettingsEditView {
                 .push(
                     Container::new(
                         Row::new()
+                            .push(test_button)
                             .push(cancel_button)
                             .push(confirm_button)
                             .spacing(10)
@@ -273,6 +431,7 @@ impl BitcoindSettingsView {
 
 #[derive(Debug, Default)]
 pub struct CoordinatorSettingsEditView {
+    test_button: iced::button::State,
     cancel_button: iced::button::State,
     confirm_button: iced::button::State,
 
@@ -286,6 +445,8 @@ impl CoordinatorSettingsEditView {
         host: &form::Value<String>,
         key: &form::Value<String>,
         processing: bool,
+        testing: bool,
+        connection: Option<&Result<(), server::Error>>,
     ) -> Element<'a, SettingsMessage> {
         let mut col = Column::new().spacing(20);
         col = col
@@ -318,6 +479,11 @@ impl CoordinatorSettingsEditView {
                     .spacing(5),
             );
 
+        if let Some(status) = server_connection_status(connection) {
+            col = col.push(status);
+        }
+
+        let test_button = test_connection_button(&mut self.test_button, testing, processing);
         let mut cancel_button = button::cancel(
             &mut self.cancel_button,
             Container::new(Text::new(" Cancel ")).padding(5),
@@ -346,6 +512,7 @@ impl CoordinatorSettingsEditView {
                 .push(
                     Container::new(
                         Row::new()
+                            .push(test_button)
                             .push(cancel_button)
                             .push(confirm_button)
                             .spacing(10)
@@ -598,6 +765,7 @@ impl WatchtowerSettingsView {
 
 #[derive(Debug, Default)]
 pub struct CosignerSettingsEditView {
+    test_button: iced::button::State,
     cancel_button: iced::button::State,
     confirm_button: iced::button::State,
 
@@ -611,6 +779,8 @@ impl CosignerSettingsEditView {
         host: &form::Value<String>,
         key: &form::Value<String>,
         processing: bool,
+        testing: bool,
+        connection: Option<&Result<(), server::Error>>,
     ) -> Element<'a, SettingsMessage> {
         let mut col = Column::new().spacing(20);
         col = col
@@ -643,6 +813,11 @@ impl CosignerSettingsEditView {
                     .spacing(5),
             );
 
+        if let Some(status) = server_connection_status(connection) {
+            col = col.push(status);
+        }
+
+        let test_button = test_connection_button(&mut self.test_button, testing, processing);
         let mut cancel_button = button::cancel(
             &mut self.cancel_button,
             Container::new(Text::new(" Cancel ")).padding(5),
@@ -671,6 +846,7 @@ impl CosignerSettingsEditView {
                 .push(
                     Container::new(
                         Row::new()
+                            .push(test_button)
                             .push(cancel_button)
                             .push(confirm_button)
                             .align_items(Alignment::Center)
@@ -766,3 +942,38 @@ pub fn is_running_label<'a, T: 'a>(is_running: Option<bool>) -> Container<'a, T>
         Container::new(Column::new())
     }
 }
+
+fn test_connection_button(
+    state: &mut iced::button::State,
+    testing: bool,
+    processing: bool,
+) -> iced::Button<SettingsMessage> {
+    let mut test_button = button::white_card_button(
+        state,
+        Container::new(Text::new(if testing {
+            " Testing... "
+        } else {
+            " Test connection "
+        }))
+        .padding(5),
+    );
+    if !processing && !testing {
+        test_button = test_button.on_press(SettingsMessage::TestConnection);
+    }
+    test_button
+}
+
+fn server_connection_status<'a>(
+    connection: Option<&Result<(), server::Error>>,
+) -> Option<Element<'a, SettingsMessage>> {
+    match connection? {
+        Ok(()) => Some(
+            Text::new("Noise handshake succeeded")
+                .color(color::SUCCESS)
+                .into(),
+        ),
+        Err(e) => {
+            Some(card::alert_warning(Container::new(Text::new(&e.to_string()).small())).into())
+        }
+    }
+}
@@ -4,8 +4,8 @@ use revault_ui::{
     color,
     component::{button, separation, text::Text, TransparentPickListStyle},
     icon::{
-        deposit_icon, history_icon, home_icon, person_check_icon, plus_icon, send_icon,
-        settings_icon, vaults_icon, warning_icon,
+        block_icon, deposit_icon, history_icon, home_icon, person_check_icon, person_icon,
+        plus_icon, send_icon, settings_icon, vaults_icon, warning_icon,
     },
 };
 
@@ -27,6 +27,8 @@ pub struct Sidebar {
     history_menu_button: iced::button::State,
     vaults_menu_button: iced::button::State,
     spend_menu_button: iced::button::State,
+    address_book_menu_button: iced::button::State,
+    inspector_menu_button: iced::button::State,
     settings_menu_button: iced::button::State,
 }
 
@@ -89,6 +91,19 @@ impl Sidebar {
             )
             .on_press(Message::Menu(Menu::Vaults(VaultsMenu::Current)))
         };
+        let inspector_button = if context.menu == Menu::Inspector {
+            button::primary(
+                &mut self.inspector_menu_button,
+                button::button_content(Some(block_icon()), "Inspector"),
+            )
+            .on_press(Message::Reload)
+        } else {
+            button::transparent(
+                &mut self.inspector_menu_button,
+                button::button_content(Some(block_icon()), "Inspector"),
+            )
+            .on_press(Message::Menu(Menu::Inspector))
+        };
 
         let settings_button = if context.menu == Menu::Settings {
             button::primary(
@@ -124,6 +139,21 @@ impl Sidebar {
             .width(Length::Units(200))
         };
         if context.role == Role::Manager {
+            let address_book_button = if context.menu == Menu::AddressBook {
+                button::primary(
+                    &mut self.address_book_menu_button,
+                    button::button_content(Some(person_icon()), "Address book"),
+                )
+                .on_press(Message::Reload)
+                .width(Length::Units(200))
+            } else {
+                button::transparent(
+                    &mut self.address_book_menu_button,
+                    button::button_content(Some(person_icon()), "Address book"),
+                )
+                .on_press(Message::Menu(Menu::AddressBook))
+                .width(Length::Units(200))
+            };
             if context.menu == Menu::Send {
                 actions = actions.push(deposit_button).push(Container::new(
                     button::primary(
@@ -143,6 +173,7 @@ impl Sidebar {
                     .width(iced::Length::Units(200)),
                 ));
             }
+            actions = actions.push(Container::new(address_book_button));
         } else {
             let action_delegate = if context.menu == Menu::DelegateFunds {
                 Container::new(
@@ -197,6 +228,7 @@ impl Sidebar {
                 Container::new(home_button.width(Length::Units(200))),
                 Container::new(history_button.width(Length::Units(200))),
                 Container::new(vaults_button.width(Length::Units(200))),
+                Container::new(inspector_button.width(Length::Units(200))),
                 separation().width(Length::Units(200)),
                 Container::new(actions.width(Length::Units(200))),
             ]),
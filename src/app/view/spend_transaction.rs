@@ -1,4 +1,7 @@
-use bitcoin::{util::psbt::PartiallySignedTransaction as Psbt, Amount};
+use bitcoin::{
+    util::{bip32::Fingerprint, psbt::PartiallySignedTransaction as Psbt},
+    Amount, Txid,
+};
 
 use iced::{
     alignment::Horizontal, scrollable, tooltip, Alignment, Checkbox, Column, Container, Element,
@@ -19,8 +22,11 @@ use revault_ui::{
 use crate::{
     app::{
         context::Context,
+        cpfp::{package_feerate, CpfpError, CpfpParent},
         error::Error,
-        message::{Message, SpendTxMessage},
+        labels::LabelType,
+        message::{CpfpMessage, Message, SpendTxMessage},
+        signatures::SignaturesMatrix,
         view::{manager::spend_tx_with_feerate_view, warning::warn},
     },
     daemon::model,
@@ -32,16 +38,25 @@ pub struct SpendTransactionView {
     delete_button: iced::button::State,
     cancel_button: iced::button::State,
     copy_button: iced::button::State,
+    psbt_path_input: iced::text_input::State,
+    export_button: iced::button::State,
+    import_button: iced::button::State,
 }
 
 impl SpendTransactionView {
+    #[allow(clippy::too_many_arguments)]
     pub fn view<'a>(
         &'a mut self,
         ctx: &Context,
         tx: &model::SpendTx,
         psbt: &Psbt,
+        signatures: &SignaturesMatrix,
         spent_vaults: &[model::Vault],
         action: Element<'a, Message>,
+        label: Element<'a, Message>,
+        psbt_path: &form::Value<String>,
+        psbt_exported: bool,
+        can_import_psbt: bool,
         warning: Option<&Error>,
         show_delete_button: bool,
         user_signed: bool,
@@ -87,6 +102,53 @@ impl SpendTransactionView {
 
         let fees = tx.deposit_amount - tx.cpfp_amount - spend_amount - change_amount;
 
+        let mut import_button = button::white_card_button(
+            &mut self.import_button,
+            button::button_content(None, "Import"),
+        );
+        if can_import_psbt {
+            import_button =
+                import_button.on_press(Message::SpendTx(SpendTxMessage::ImportPsbtFile));
+        }
+        let mut psbt_file = Column::new()
+            .push(
+                Row::new()
+                    .push(
+                        form::Form::new(
+                            &mut self.psbt_path_input,
+                            "Path to a .psbt file",
+                            psbt_path,
+                            |path| Message::SpendTx(SpendTxMessage::PsbtPathEdited(path)),
+                        )
+                        .warning("Please enter the path of a PSBT file")
+                        .padding(10)
+                        .render(),
+                    )
+                    .push(
+                        button::white_card_button(
+                            &mut self.export_button,
+                            button::button_content(None, "Export"),
+                        )
+                        .on_press(Message::SpendTx(SpendTxMessage::ExportPsbtFile)),
+                    )
+                    .push(import_button)
+                    .spacing(10)
+                    .align_items(Alignment::Center),
+            )
+            .spacing(10);
+        if can_import_psbt {
+            psbt_file = psbt_file.push(
+                Text::new("A .psbt file signed by the other managers can be dropped on the window")
+                    .small(),
+            );
+        }
+        if psbt_exported {
+            psbt_file = psbt_file.push(card::border_success(Container::new(Text::new(&format!(
+                "Transaction exported to {}",
+                psbt_path.value
+            )))));
+        }
+
         Container::new(scroll(
             &mut self.scroll,
             Container::new(
@@ -155,10 +217,9 @@ impl SpendTransactionView {
                                                                     .bold(),
                                                                 )
                                                                 .push(Text::new(&format!(
-                                                                    "{}",
-                                                                    psbt.inputs[0]
-                                                                        .partial_sigs
-                                                                        .len(),
+                                                                    "{} / {}",
+                                                                    signatures.signatures(),
+                                                                    ctx.managers_threshold,
                                                                 ))),
                                                         )
                                                         .align_items(Alignment::Center)
@@ -199,6 +260,8 @@ impl SpendTransactionView {
                                             .spacing(20),
                                     ))
                                     .push(separation().width(Length::Fill))
+                                    .push(signatures_matrix(ctx, signatures))
+                                    .push(separation().width(Length::Fill))
                                     .push(
                                         Column::new()
                                             .push(
@@ -221,6 +284,13 @@ impl SpendTransactionView {
                                                     )
                                                     .align_items(Alignment::Center),
                                             )
+                                            .push(
+                                                Row::new()
+                                                    .push(Text::new("Label:").bold())
+                                                    .push(Container::new(label).width(Length::Fill))
+                                                    .spacing(20)
+                                                    .align_items(Alignment::Center),
+                                            )
                                             .push(
                                                 Row::new()
                                                     .push(
@@ -234,7 +304,8 @@ impl SpendTransactionView {
                                                         Message::Clipboard(psbt.to_string()),
                                                     ))
                                                     .align_items(Alignment::Center),
-                                            ),
+                                            )
+                                            .push(psbt_file),
                                     )
                                     .spacing(20),
                             ))
@@ -261,10 +332,66 @@ impl SpendTransactionView {
     }
 }
 
+/// Table of the managers against the inputs of the transaction, with the number
+/// of signatures of each input.
+fn signatures_matrix<'a>(ctx: &Context, signatures: &SignaturesMatrix) -> Column<'a, Message> {
+    let mut header = Row::new()
+        .push(Text::new("Manager").bold().width(Length::Units(200)))
+        .spacing(10);
+    let mut count = Row::new()
+        .push(Text::new("Signatures").bold().width(Length::Units(200)))
+        .spacing(10);
+    for input in 0..signatures.inputs {
+        header = header.push(
+            Text::new(&format!("Input {}", input + 1))
+                .small()
+                .bold()
+                .width(Length::Units(60)),
+        );
+        let n_sigs = signatures.input_signatures(input);
+        count = count.push(
+            if n_sigs >= ctx.managers_threshold {
+                Text::new(&format!("{} / {}", n_sigs, ctx.managers_threshold)).success()
+            } else {
+                Text::new(&format!("{} / {}", n_sigs, ctx.managers_threshold))
+            }
+            .small()
+            .width(Length::Units(60)),
+        );
+    }
+
+    let mut col = Column::new().push(header).spacing(10);
+    for (manager, signed) in signatures.managers.iter().zip(signatures.signed.iter()) {
+        let mut row = Row::new()
+            .push(
+                Column::new()
+                    .push(Text::new(&ctx.manager_name(manager)).small().bold())
+                    .push(Text::new(&manager.to_string()).small())
+                    .width(Length::Units(200)),
+            )
+            .spacing(10)
+            .align_items(Alignment::Center);
+        for signed in signed {
+            row = row.push(
+                Container::new(if *signed {
+                    icon::done_icon().color(color::SUCCESS)
+                } else {
+                    icon::cross_icon().color(color::DARK_GREY)
+                })
+                .width(Length::Units(60)),
+            );
+        }
+        col = col.push(row);
+    }
+    col.push(count)
+}
+
 #[derive(Debug)]
 pub struct SpendTransactionSharePsbtView {
     psbt_input: iced::text_input::State,
     copy_button: iced::button::State,
+    add_button: iced::button::State,
+    remove_buttons: Vec<iced::button::State>,
     confirm_button: iced::button::State,
 }
 
@@ -273,13 +400,22 @@ impl SpendTransactionSharePsbtView {
         Self {
             psbt_input: iced::text_input::State::new(),
             copy_button: iced::button::State::new(),
+            add_button: iced::button::State::new(),
+            remove_buttons: Vec::new(),
             confirm_button: iced::button::State::new(),
         }
     }
 
+    /// `psbts` are the signers of each PSBT to combine and `signed` the managers
+    /// who signed once they are combined.
+    #[allow(clippy::too_many_arguments)]
     pub fn view(
         &mut self,
+        ctx: &Context,
         psbt_input: &form::Value<String>,
+        psbts: Vec<Vec<Fingerprint>>,
+        managers: &[Fingerprint],
+        signed: &[Fingerprint],
         processing: &bool,
         success: &bool,
         psbt: &Psbt,
@@ -305,31 +441,101 @@ impl SpendTransactionSharePsbtView {
 
         let mut button_update_action = button::important(
             &mut self.confirm_button,
-            button::button_content(None, "Update transaction"),
+            button::button_content(None, "Combine and update transaction"),
         );
+        let mut add_button =
+            button::white_card_button(&mut self.add_button, button::button_content(None, "Add"));
         if !*processing {
             button_update_action =
                 button_update_action.on_press(Message::SpendTx(SpendTxMessage::Update));
+            add_button = add_button.on_press(Message::SpendTx(SpendTxMessage::AddPsbt));
         }
         if *success {
             col_action = col_action.push(Text::new("Transaction updated").success());
         }
+
+        self.remove_buttons
+            .resize_with(psbts.len(), Default::default);
+        let mut col_psbts = Column::new().spacing(10);
+        for (i, (fingerprints, remove_button)) in
+            psbts.iter().zip(self.remove_buttons.iter_mut()).enumerate()
+        {
+            let mut remove_button =
+                button::transparent(remove_button, Container::new(icon::trash_icon()));
+            if !*processing {
+                remove_button =
+                    remove_button.on_press(Message::SpendTx(SpendTxMessage::RemovePsbt(i)));
+            }
+            col_psbts = col_psbts.push(card::simple(Container::new(
+                Row::new()
+                    .push(
+                        Text::new(&format!(
+                            "PSBT {}: signed by {}",
+                            i + 1,
+                            if fingerprints.is_empty() {
+                                "nobody".to_string()
+                            } else {
+                                fingerprints
+                                    .iter()
+                                    .map(|fingerprint| ctx.manager_name(fingerprint))
+                                    .collect::<Vec<String>>()
+                                    .join(", ")
+                            }
+                        ))
+                        .small()
+                        .width(Length::Fill),
+                    )
+                    .push(remove_button)
+                    .align_items(Alignment::Center),
+            )));
+        }
+
+        let mut signatures = Row::new().spacing(20).align_items(Alignment::Center);
+        for fingerprint in managers {
+            signatures = signatures.push(if signed.contains(fingerprint) {
+                Row::new()
+                    .push(icon::person_icon().color(color::SUCCESS))
+                    .push(Text::new(&ctx.manager_name(fingerprint)).small().success())
+                    .spacing(5)
+            } else {
+                Row::new()
+                    .push(icon::person_icon().color(color::DARK_GREY))
+                    .push(Text::new(&ctx.manager_name(fingerprint)).small())
+                    .spacing(5)
+            });
+        }
+
         Container::new(
             Column::new()
                 .push(card::white(Container::new(
                     col_action
-                        .push(Text::new("Enter PSBT:"))
+                        .push(Text::new("Enter the PSBTs of the other managers:"))
                         .push(
-                            form::Form::new(
-                                &mut self.psbt_input,
-                                "Signed PSBT",
-                                &psbt_input,
-                                |p| Message::SpendTx(SpendTxMessage::PsbtEdited(p)),
-                            )
-                            .warning("PSBT is not valid or signatures are from unknown sources")
-                            .size(20)
-                            .padding(10)
-                            .render(),
+                            Row::new()
+                                .push(
+                                    form::Form::new(
+                                        &mut self.psbt_input,
+                                        "Signed PSBT",
+                                        &psbt_input,
+                                        |p| Message::SpendTx(SpendTxMessage::PsbtEdited(p)),
+                                    )
+                                    .warning(
+                                        "PSBT is not valid or signatures are from unknown sources",
+                                    )
+                                    .size(20)
+                                    .padding(10)
+                                    .render(),
+                                )
+                                .push(add_button)
+                                .spacing(10)
+                                .align_items(Alignment::Center),
+                        )
+                        .push(col_psbts)
+                        .push(
+                            Column::new()
+                                .push(Text::new("Signatures once combined:").bold())
+                                .push(signatures)
+                                .spacing(10),
                         )
                         .push(button_update_action),
                 )))
@@ -352,17 +558,190 @@ pub fn spend_tx_confirmed<'a, T: 'a>() -> Element<'a, T> {
     .into()
 }
 
-pub fn spend_tx_processing<'a, T: 'a>() -> Element<'a, T> {
-    card::white(
-        Row::new()
-            .push(badge::Badge::new(icon::send_icon()).style(badge::Style::Warning))
-            .push(Text::new("Transaction is being processed").color(color::WARNING))
-            .align_items(Alignment::Center)
-            .spacing(20),
-    )
-    .align_x(Horizontal::Center)
-    .width(Length::Fill)
-    .into()
+#[derive(Debug)]
+pub struct SpendTransactionProcessingView {
+    bump_fee_button: iced::button::State,
+}
+
+impl SpendTransactionProcessingView {
+    pub fn new() -> Self {
+        Self {
+            bump_fee_button: iced::button::State::new(),
+        }
+    }
+
+    pub fn view(&mut self) -> Element<Message> {
+        card::white(
+            Row::new()
+                .push(badge::Badge::new(icon::send_icon()).style(badge::Style::Warning))
+                .push(
+                    Text::new("Transaction is being processed")
+                        .color(color::WARNING)
+                        .width(Length::Fill),
+                )
+                .push(
+                    button::white_card_button(
+                        &mut self.bump_fee_button,
+                        button::button_content(None, "Bump fee"),
+                    )
+                    .on_press(Message::SpendTx(SpendTxMessage::SelectBumpFee)),
+                )
+                .align_items(Alignment::Center)
+                .spacing(20),
+        )
+        .width(Length::Fill)
+        .into()
+    }
+}
+
+#[derive(Debug)]
+pub struct SpendTransactionBumpFeeView {
+    feerate_input: iced::text_input::State,
+    create_button: iced::button::State,
+    cancel_button: iced::button::State,
+}
+
+impl SpendTransactionBumpFeeView {
+    pub fn new() -> Self {
+        Self {
+            feerate_input: iced::text_input::State::new(),
+            create_button: iced::button::State::new(),
+            cancel_button: iced::button::State::new(),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn view<'a>(
+        &'a mut self,
+        ctx: &Context,
+        parents: Option<&Vec<CpfpParent>>,
+        feerate: &form::Value<String>,
+        child: Option<&Psbt>,
+        signer: Option<Element<'a, Message>>,
+        processing: bool,
+        broadcasted: Option<&Txid>,
+        error: Option<&CpfpError>,
+        warning: Option<&Error>,
+    ) -> Element<'a, Message> {
+        let mut col_action = Column::new()
+            .push(Text::new("Bump the fees with a child transaction").bold())
+            .spacing(20);
+        if let Some(error) = warning {
+            col_action = col_action.push(card::alert_warning(Container::new(
+                Text::new(&error.to_string()).small(),
+            )));
+        }
+
+        if let Some(txid) = broadcasted {
+            col_action = col_action.push(
+                Text::new(&format!("Child transaction {} broadcasted", txid)).color(color::SUCCESS),
+            );
+        } else if let Some(parents) = parents {
+            let mut col_parents = Column::new().spacing(5);
+            for parent in parents {
+                col_parents = col_parents.push(
+                    Text::new(&format!(
+                        "{}: {} vbytes paying {} sats",
+                        parent.txid, parent.entry.vsize, parent.entry.fees
+                    ))
+                    .small(),
+                );
+            }
+            if !parents.is_empty() {
+                col_action = col_action.push(col_parents).push(Text::new(&format!(
+                    "Current feerate: {} sats/vbyte",
+                    package_feerate(parents)
+                )));
+            }
+
+            match (child, signer) {
+                (Some(child), Some(signer)) => {
+                    let inputs: u64 = child
+                        .inputs
+                        .iter()
+                        .filter_map(|input| input.witness_utxo.as_ref())
+                        .map(|txout| txout.value)
+                        .sum();
+                    let outputs: u64 = child
+                        .global
+                        .unsigned_tx
+                        .output
+                        .iter()
+                        .map(|o| o.value)
+                        .sum();
+                    col_action = col_action.push(Text::new(&format!(
+                        "The child transaction pays {} {}",
+                        ctx.converter.converts(Amount::from_sat(inputs - outputs)),
+                        ctx.converter.unit
+                    )));
+                    if processing {
+                        col_action = col_action.push(Text::new("Broadcasting..."));
+                    } else {
+                        col_action = col_action.push(signer);
+                    }
+                }
+                _ => {
+                    col_action = col_action.push(
+                        Row::new()
+                            .push(
+                                form::Form::new(
+                                    &mut self.feerate_input,
+                                    "Target feerate in sats/vbyte",
+                                    feerate,
+                                    |value| {
+                                        Message::SpendTx(SpendTxMessage::Cpfp(
+                                            CpfpMessage::FeerateEdited(value),
+                                        ))
+                                    },
+                                )
+                                .warning("Please enter a feerate in sats/vbyte")
+                                .padding(10)
+                                .render(),
+                            )
+                            .push(
+                                button::primary(
+                                    &mut self.create_button,
+                                    button::button_content(None, "Create child"),
+                                )
+                                .on_press(Message::SpendTx(
+                                    SpendTxMessage::Cpfp(CpfpMessage::Create),
+                                )),
+                            )
+                            .spacing(10)
+                            .align_items(Alignment::Center),
+                    );
+                }
+            }
+        } else {
+            col_action =
+                col_action.push(Text::new("Looking for the transactions in the mempool..."));
+        }
+
+        if let Some(error) = error {
+            col_action = col_action.push(Text::new(&error.to_string()).small().color(color::ALERT));
+        }
+
+        if !processing {
+            col_action = col_action.push(
+                button::cancel(
+                    &mut self.cancel_button,
+                    button::button_content(
+                        None,
+                        if broadcasted.is_some() {
+                            "Close"
+                        } else {
+                            "Cancel"
+                        },
+                    ),
+                )
+                .on_press(Message::SpendTx(SpendTxMessage::UnselectBumpFee)),
+            );
+        }
+
+        card::white(Container::new(col_action))
+            .width(Length::Fill)
+            .into()
+    }
 }
 
 pub fn spend_tx_deprecated<'a, T: 'a>() -> Element<'a, T> {
@@ -580,13 +959,8 @@ impl SpendTransactionListItemView {
         spend_amount: Amount,
         fees: Amount,
     ) -> Element<SpendTxMessage> {
-        let n_sigs = tx
-            .psbt
-            .psbt()
-            .inputs
-            .get(0)
-            .map(|output| output.partial_sigs.len())
-            .unwrap_or(0);
+        let signatures = SignaturesMatrix::new(&ctx.managers_fingerprints(), tx.psbt.psbt());
+        let n_sigs = signatures.signatures();
 
         let row = Row::new()
             .push(match tx.status {
@@ -606,7 +980,7 @@ impl SpendTransactionListItemView {
             .push(
                 match tx.status {
                     model::ListSpendStatus::NonFinal => {
-                        if n_sigs < ctx.managers_threshold {
+                        if !signatures.is_complete(ctx.managers_threshold) {
                             Text::new("Non final ")
                         } else {
                             Text::new("Ready    ").success()
@@ -636,6 +1010,15 @@ impl SpendTransactionListItemView {
                     .spacing(5)
                     .align_items(Alignment::Center),
             )
+            .push(
+                Text::new(
+                    ctx.labels
+                        .get(LabelType::Tx, &tx.psbt.txid().to_string())
+                        .unwrap_or_default(),
+                )
+                .small()
+                .bold(),
+            )
             .align_items(Alignment::Center)
             .spacing(20);
 
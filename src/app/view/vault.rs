@@ -8,7 +8,7 @@ use revault_ui::{
     icon,
 };
 
-use crate::app::{context::Context, message::Message, view::layout};
+use crate::app::{context::Context, labels::LabelType, message::Message, view::layout};
 
 use crate::daemon::model::{
     outpoint, transaction_from_hex, Vault, VaultStatus, VaultTransactions, WalletTransaction,
@@ -33,6 +33,7 @@ impl VaultModal {
         ctx: &Context,
         vlt: &Vault,
         txs: &VaultTransactions,
+        label: Element<'a, Message>,
     ) -> Element<'a, Message> {
         let mut col = Column::new().spacing(20);
         col = col.push(Container::new(Text::new("Onchain transactions:").bold()));
@@ -59,6 +60,7 @@ impl VaultModal {
             Container::new(
                 Column::new()
                     .push(Container::new(vault(ctx, &mut self.copy_button, vlt)))
+                    .push(label)
                     .push(col)
                     .max_width(1000)
                     .spacing(20),
@@ -210,6 +212,19 @@ fn input_and_outputs<'a, T: 'a>(
 }
 
 /// vault_badge returns a badge headlining the vault status.
+/// Label of the vault displayed in the lists, empty if the vault has no label.
+pub fn vault_label<'a, T: 'a>(ctx: &Context, vault: &Vault) -> Container<'a, T> {
+    Container::new(
+        Text::new(
+            ctx.labels
+                .get(LabelType::Output, &outpoint(vault).to_string())
+                .unwrap_or_default(),
+        )
+        .small()
+        .bold(),
+    )
+}
+
 fn vault_badge<'a, T: 'a>(vault: &Vault) -> Element<'a, T> {
     match &vault.status {
         VaultStatus::Unconfirmed => badge::vault_unconfirmed().into(),
@@ -269,6 +284,7 @@ impl VaultView for VaultListItemView {
                                 } else {
                                     Text::new(&format!("{}", &vault.status)).bold().small()
                                 })
+                                .push(vault_label(ctx, vault))
                                 .align_items(Alignment::Center)
                                 .spacing(20),
                         )
@@ -330,6 +346,7 @@ fn vault_ack_signed<'a, T: 'a>(ctx: &Context, deposit: &Vault) -> Element<'a, T>
                             )
                             .center_y(),
                         )
+                        .push(vault_label(ctx, deposit))
                         .spacing(20)
                         .align_items(Alignment::Center),
                 )
@@ -374,6 +391,7 @@ fn vault_ack_pending<'a>(
                                     )
                                     .center_y(),
                                 )
+                                .push(vault_label(ctx, deposit))
                                 .spacing(20)
                                 .align_items(Alignment::Center),
                         )
@@ -459,6 +477,7 @@ impl DelegateVaultListItemView {
                                 badge::square()
                             })
                             .push(sigs_row)
+                            .push(vault_label(ctx, vault))
                             .spacing(20)
                             .align_items(Alignment::Center),
                     )
@@ -23,6 +23,8 @@ impl From<&Error> for WarningMessage {
                 _ => WarningMessage(e.to_string()),
             },
             Error::Config(e) => WarningMessage(e.to_owned()),
+            Error::File(e) => WarningMessage(e.to_owned()),
+            Error::Bitcoind(e) => WarningMessage(e.to_string()),
             // TODO: change when ConfigError is enum again.
             // Error::ConfigError(e) => match e {
             //     ConfigError::NotFound => WarningMessage("Configuration file not fund".to_string()),
@@ -0,0 +1,534 @@
+use std::net::SocketAddr;
+use std::path::Path;
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde_json::json;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+/// Time given to bitcoind to answer a request.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Subset of the `getblockchaininfo` result.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct BlockchainInfo {
+    pub chain: String,
+    pub blocks: u64,
+    pub headers: u64,
+    #[serde(rename = "verificationprogress")]
+    pub verification_progress: f64,
+    #[serde(rename = "initialblockdownload")]
+    pub initial_block_download: bool,
+}
+
+impl BlockchainInfo {
+    /// Synchronization progress in percent.
+    pub fn sync_progress(&self) -> f64 {
+        (self.verification_progress * 100.0).min(100.0)
+    }
+
+    pub fn is_synced(&self) -> bool {
+        !self.initial_block_download && self.blocks == self.headers
+    }
+}
+
+/// Reads the cookie file and runs `getblockchaininfo` against bitcoind,
+/// returns an error if the node chain does not match the given network.
+pub async fn check_connection(
+    cookie_path: &Path,
+    addr: SocketAddr,
+    network: bitcoin::Network,
+) -> Result<BlockchainInfo, Error> {
+    let cookie = read_cookie(cookie_path)?;
+    let info: BlockchainInfo =
+        call_with_timeout(addr, &cookie, "getblockchaininfo", json!([])).await?;
+
+    let expected = chain_name(network);
+    if info.chain != expected {
+        return Err(Error::WrongNetwork {
+            expected: expected.to_string(),
+            found: info.chain,
+        });
+    }
+    Ok(info)
+}
+
+/// Confirmation targets in blocks of the feerate presets.
+pub const SLOW_TARGET: u32 = 144;
+pub const NORMAL_TARGET: u32 = 6;
+pub const FAST_TARGET: u32 = 2;
+
+/// Feerate estimated by bitcoind for a confirmation target.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FeeEstimate {
+    /// Feerate in sats/vbyte.
+    pub feerate: u64,
+    /// Number of blocks within which the transaction is expected to confirm,
+    /// it may be higher than the requested target.
+    pub blocks: u32,
+}
+
+/// Feerate presets, an estimate is missing if bitcoind does not have enough data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeEstimates {
+    pub slow: Option<FeeEstimate>,
+    pub normal: Option<FeeEstimate>,
+    pub fast: Option<FeeEstimate>,
+    /// Minimum feerate in sats/vbyte for a transaction to be accepted in the mempool.
+    pub mempool_min_feerate: u64,
+}
+
+#[derive(Deserialize)]
+struct SmartFee {
+    /// Feerate in BTC/kvB.
+    feerate: Option<f64>,
+    blocks: u32,
+}
+
+#[derive(Deserialize)]
+struct MempoolInfo {
+    /// Feerate in BTC/kvB.
+    mempoolminfee: f64,
+}
+
+/// Runs `estimatesmartfee` for each preset target and `getmempoolinfo` against bitcoind.
+pub async fn estimate_fees(cookie_path: &Path, addr: SocketAddr) -> Result<FeeEstimates, Error> {
+    let cookie = read_cookie(cookie_path)?;
+    let mut estimates = Vec::new();
+    for target in &[SLOW_TARGET, NORMAL_TARGET, FAST_TARGET] {
+        let estimate: SmartFee =
+            call_with_timeout(addr, &cookie, "estimatesmartfee", json!([target])).await?;
+        estimates.push(estimate.feerate.map(|feerate| FeeEstimate {
+            feerate: btc_per_kvb_to_sat_per_vb(feerate),
+            blocks: estimate.blocks,
+        }));
+    }
+    let info: MempoolInfo = call_with_timeout(addr, &cookie, "getmempoolinfo", json!([])).await?;
+    Ok(FeeEstimates {
+        slow: estimates[0],
+        normal: estimates[1],
+        fast: estimates[2],
+        mempool_min_feerate: btc_per_kvb_to_sat_per_vb(info.mempoolminfee),
+    })
+}
+
+/// Subset of the `getmempoolentry` result, the ancestors include the transaction itself.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MempoolEntry {
+    pub vsize: u64,
+    /// Fees in satoshis.
+    pub fees: u64,
+    /// Virtual size of the transaction and its unconfirmed ancestors.
+    pub ancestor_vsize: u64,
+    /// Fees in satoshis of the transaction and its unconfirmed ancestors.
+    pub ancestor_fees: u64,
+}
+
+#[derive(Deserialize)]
+struct RawMempoolEntry {
+    vsize: u64,
+    #[serde(rename = "ancestorsize")]
+    ancestor_size: u64,
+    fees: RawMempoolFees,
+}
+
+#[derive(Deserialize)]
+struct RawMempoolFees {
+    /// Fees in BTC.
+    base: f64,
+    /// Fees in BTC of the transaction and its ancestors.
+    ancestor: f64,
+}
+
+/// Runs `getmempoolentry` against bitcoind, returns None if the transaction
+/// is not in the mempool.
+pub async fn get_mempool_entry(
+    cookie_path: &Path,
+    addr: SocketAddr,
+    txid: &bitcoin::Txid,
+) -> Result<Option<MempoolEntry>, Error> {
+    let cookie = read_cookie(cookie_path)?;
+    match call_with_timeout::<RawMempoolEntry>(
+        addr,
+        &cookie,
+        "getmempoolentry",
+        json!([txid.to_string()]),
+    )
+    .await
+    {
+        Ok(entry) => Ok(Some(MempoolEntry {
+            vsize: entry.vsize,
+            fees: btc_to_sat(entry.fees.base),
+            ancestor_vsize: entry.ancestor_size,
+            ancestor_fees: btc_to_sat(entry.fees.ancestor),
+        })),
+        Err(Error::Rpc(RPC_INVALID_ADDRESS_OR_KEY, _)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Runs `sendrawtransaction` against bitcoind with the serialized transaction.
+pub async fn send_raw_transaction(
+    cookie_path: &Path,
+    addr: SocketAddr,
+    tx: &bitcoin::Transaction,
+) -> Result<bitcoin::Txid, Error> {
+    let cookie = read_cookie(cookie_path)?;
+    let txid: String = call_with_timeout(
+        addr,
+        &cookie,
+        "sendrawtransaction",
+        json!([bitcoin::consensus::encode::serialize_hex(tx)]),
+    )
+    .await?;
+    txid.parse()
+        .map_err(|_| Error::Http(format!("invalid txid {}", txid)))
+}
+
+fn btc_to_sat(amount: f64) -> u64 {
+    (amount * 100_000_000.0).round() as u64
+}
+
+/// Converts a bitcoind feerate to sats/vbyte, rounded up.
+fn btc_per_kvb_to_sat_per_vb(feerate: f64) -> u64 {
+    let sat_per_kvb = (feerate * 100_000_000.0).round() as u64;
+    (sat_per_kvb + 999) / 1000
+}
+
+/// Name of the network in the bitcoind `chain` field.
+fn chain_name(network: bitcoin::Network) -> &'static str {
+    match network {
+        bitcoin::Network::Bitcoin => "main",
+        bitcoin::Network::Testnet => "test",
+        bitcoin::Network::Signet => "signet",
+        bitcoin::Network::Regtest => "regtest",
+    }
+}
+
+#[derive(Deserialize)]
+struct Response<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    code: i32,
+    message: String,
+}
+
+/// Error code of bitcoind for an unknown transaction or address, `getmempoolentry`
+/// answers it for a transaction that is not in the mempool.
+const RPC_INVALID_ADDRESS_OR_KEY: i32 = -5;
+
+fn read_cookie(cookie_path: &Path) -> Result<String, Error> {
+    std::fs::read_to_string(cookie_path)
+        .map(|cookie| cookie.trim().to_string())
+        .map_err(|e| Error::Cookie(e.to_string()))
+}
+
+async fn call_with_timeout<T: serde::de::DeserializeOwned>(
+    addr: SocketAddr,
+    cookie: &str,
+    method: &str,
+    params: serde_json::Value,
+) -> Result<T, Error> {
+    tokio::time::timeout(TIMEOUT, call(addr, cookie, method, params))
+        .await
+        .map_err(|_| Error::Connection("timed out".to_string()))?
+}
+
+/// Sends a single JSON-RPC request with a HTTP/1.1 POST and reads the response
+/// until the connection is closed.
+async fn call<T: serde::de::DeserializeOwned>(
+    addr: SocketAddr,
+    cookie: &str,
+    method: &str,
+    params: serde_json::Value,
+) -> Result<T, Error> {
+    let body = json!({
+        "jsonrpc": "1.0",
+        "id": "revault-gui",
+        "method": method,
+        "params": params,
+    })
+    .to_string();
+    let request = format!(
+        "POST / HTTP/1.1\r\nHost: {}\r\nAuthorization: Basic {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        addr,
+        bitcoin::base64::encode(cookie),
+        body.len(),
+        body
+    );
+
+    let mut stream = TcpStream::connect(addr)
+        .await
+        .map_err(|e| Error::Connection(e.to_string()))?;
+    stream
+        .write_all(request.as_bytes())
+        .await
+        .map_err(|e| Error::Connection(e.to_string()))?;
+    let mut response = Vec::new();
+    stream
+        .read_to_end(&mut response)
+        .await
+        .map_err(|e| Error::Connection(e.to_string()))?;
+
+    let response = String::from_utf8_lossy(&response);
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| Error::Http("malformed response".to_string()))?;
+    let status = head
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|code| code.parse::<u16>().ok())
+        .ok_or_else(|| Error::Http("malformed status line".to_string()))?;
+    if status == 401 || status == 403 {
+        return Err(Error::Unauthorized);
+    }
+
+    // bitcoind answers rpc errors with a 500 status and a json body.
+    let response: Response<T> = serde_json::from_str(body)
+        .map_err(|_| Error::Http(format!("unexpected response with status {}", status)))?;
+    if let Some(e) = response.error {
+        return Err(Error::Rpc(e.code, e.message));
+    }
+    response
+        .result
+        .ok_or_else(|| Error::Http("empty result".to_string()))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    Cookie(String),
+    Connection(String),
+    Unauthorized,
+    Http(String),
+    Rpc(i32, String),
+    WrongNetwork { expected: String, found: String },
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Cookie(e) => write!(f, "Failed to read the cookie file: {}", e),
+            Self::Connection(e) => write!(f, "Failed to connect to bitcoind: {}", e),
+            Self::Unauthorized => write!(f, "Bitcoind refused the cookie credentials"),
+            Self::Http(e) => write!(f, "Bitcoind http error: {}", e),
+            Self::Rpc(code, e) => write!(f, "Bitcoind rpc error: [{}] {}", code, e),
+            Self::WrongNetwork { expected, found } => write!(
+                f,
+                "Bitcoind runs on chain '{}' instead of '{}'",
+                found, expected
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    const COOKIE: &str = "__cookie__:password";
+
+    /// Answers a single getblockchaininfo request with the given status and body,
+    /// after checking the credentials of the request.
+    async fn bitcoind_stand_in(status: &'static str, body: &'static str) -> SocketAddr {
+        rpc_stand_in(vec![("getblockchaininfo", status, body)]).await
+    }
+
+    /// Answers each request in order with the status and body of the expected method.
+    async fn rpc_stand_in(
+        responses: Vec<(&'static str, &'static str, &'static str)>,
+    ) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            for (method, status, body) in responses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0; 4096];
+                let mut request = String::new();
+                while !request.contains(&format!("\"method\":\"{}\"", method)) {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    request.push_str(&String::from_utf8_lossy(&buf[..n]));
+                }
+                let (status, body) = if request.contains(&format!(
+                    "Authorization: Basic {}",
+                    bitcoin::base64::encode(COOKIE)
+                )) {
+                    (status, body)
+                } else {
+                    ("401 Unauthorized", "")
+                };
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        addr
+    }
+
+    fn cookie_file(name: &str, content: &str) -> std::path::PathBuf {
+        let mut path = std::env::temp_dir();
+        path.push(format!("revault-gui-{}-{}", name, std::process::id()));
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    const INFO: &str = r#"{"result":{"chain":"regtest","blocks":90,"headers":100,"verificationprogress":0.9,"initialblockdownload":true},"error":null,"id":"revault-gui"}"#;
+
+    #[tokio::test]
+    async fn check_connection_ok() {
+        let addr = bitcoind_stand_in("200 OK", INFO).await;
+        let cookie_path = cookie_file("check_connection_ok", COOKIE);
+        let info = check_connection(&cookie_path, addr, bitcoin::Network::Regtest)
+            .await
+            .unwrap();
+        assert_eq!(info.blocks, 90);
+        assert_eq!(info.sync_progress(), 90.0);
+        assert!(!info.is_synced());
+        std::fs::remove_file(cookie_path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn check_connection_wrong_network() {
+        let addr = bitcoind_stand_in("200 OK", INFO).await;
+        let cookie_path = cookie_file("check_connection_wrong_network", COOKIE);
+        assert_eq!(
+            check_connection(&cookie_path, addr, bitcoin::Network::Bitcoin).await,
+            Err(Error::WrongNetwork {
+                expected: "main".to_string(),
+                found: "regtest".to_string()
+            })
+        );
+        std::fs::remove_file(cookie_path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn check_connection_unauthorized() {
+        let addr = bitcoind_stand_in("200 OK", INFO).await;
+        let cookie_path = cookie_file("check_connection_unauthorized", "__cookie__:wrong");
+        assert_eq!(
+            check_connection(&cookie_path, addr, bitcoin::Network::Regtest).await,
+            Err(Error::Unauthorized)
+        );
+        std::fs::remove_file(cookie_path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn check_connection_rpc_error() {
+        let addr = bitcoind_stand_in(
+            "500 Internal Server Error",
+            r#"{"result":null,"error":{"code":-28,"message":"Loading block index..."},"id":"revault-gui"}"#,
+        )
+        .await;
+        let cookie_path = cookie_file("check_connection_rpc_error", COOKIE);
+        assert_eq!(
+            check_connection(&cookie_path, addr, bitcoin::Network::Regtest).await,
+            Err(Error::Rpc(-28, "Loading block index...".to_string()))
+        );
+        std::fs::remove_file(cookie_path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn check_connection_missing_cookie() {
+        let addr = bitcoind_stand_in("200 OK", INFO).await;
+        let mut cookie_path = std::env::temp_dir();
+        cookie_path.push("revault-gui-missing-cookie");
+        assert!(matches!(
+            check_connection(&cookie_path, addr, bitcoin::Network::Regtest).await,
+            Err(Error::Cookie(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn estimate_fees_ok() {
+        let addr = rpc_stand_in(vec![
+            (
+                "estimatesmartfee",
+                "200 OK",
+                r#"{"result":{"feerate":0.00001,"blocks":144},"error":null,"id":"revault-gui"}"#,
+            ),
+            (
+                "estimatesmartfee",
+                "200 OK",
+                r#"{"result":{"feerate":0.00012345,"blocks":6},"error":null,"id":"revault-gui"}"#,
+            ),
+            (
+                "estimatesmartfee",
+                "200 OK",
+                r#"{"result":{"errors":["Insufficient data or no feerate found"],"blocks":0},"error":null,"id":"revault-gui"}"#,
+            ),
+            (
+                "getmempoolinfo",
+                "200 OK",
+                r#"{"result":{"loaded":true,"size":3,"mempoolminfee":0.00001000},"error":null,"id":"revault-gui"}"#,
+            ),
+        ])
+        .await;
+        let cookie_path = cookie_file("estimate_fees_ok", COOKIE);
+        assert_eq!(
+            estimate_fees(&cookie_path, addr).await,
+            Ok(FeeEstimates {
+                slow: Some(FeeEstimate {
+                    feerate: 1,
+                    blocks: 144
+                }),
+                normal: Some(FeeEstimate {
+                    feerate: 13,
+                    blocks: 6
+                }),
+                fast: None,
+                mempool_min_feerate: 1,
+            })
+        );
+        std::fs::remove_file(cookie_path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn get_mempool_entry_ok() {
+        let addr = rpc_stand_in(vec![
+            (
+                "getmempoolentry",
+                "200 OK",
+                r#"{"result":{"vsize":180,"weight":720,"ancestorcount":2,"ancestorsize":350,"fees":{"base":0.00005968,"modified":0.00005968,"ancestor":0.00008968,"descendant":0.00005968}},"error":null,"id":"revault-gui"}"#,
+            ),
+            (
+                "getmempoolentry",
+                "500 Internal Server Error",
+                r#"{"result":null,"error":{"code":-5,"message":"Transaction not in mempool"},"id":"revault-gui"}"#,
+            ),
+        ])
+        .await;
+        let cookie_path = cookie_file("get_mempool_entry_ok", COOKIE);
+        let txid = bitcoin::Txid::default();
+        assert_eq!(
+            get_mempool_entry(&cookie_path, addr, &txid).await,
+            Ok(Some(MempoolEntry {
+                vsize: 180,
+                fees: 5968,
+                ancestor_vsize: 350,
+                ancestor_fees: 8968,
+            }))
+        );
+        assert_eq!(get_mempool_entry(&cookie_path, addr, &txid).await, Ok(None));
+        std::fs::remove_file(cookie_path).unwrap();
+    }
+
+    #[test]
+    fn test_btc_per_kvb_to_sat_per_vb() {
+        assert_eq!(btc_per_kvb_to_sat_per_vb(0.00001), 1);
+        assert_eq!(btc_per_kvb_to_sat_per_vb(0.00001001), 2);
+        assert_eq!(btc_per_kvb_to_sat_per_vb(0.0002), 20);
+        assert_eq!(btc_per_kvb_to_sat_per_vb(0.0), 0);
+    }
+}
@@ -0,0 +1,501 @@
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::{
+    installer::{
+        config::Config as DaemonConfig,
+        install,
+        message::{self, Message},
+        read_bundle,
+        step::Context,
+        steps, Error,
+    },
+    revault::Role,
+};
+
+/// Answers of a non-interactive installation, each field is the input
+/// of an installer step.
+#[derive(Debug, Deserialize)]
+pub struct Answers {
+    pub network: bitcoin::Network,
+    pub role: AnswersRole,
+    /// A new key is generated if missing.
+    pub private_noise_key: Option<String>,
+    /// Federation bundle holding the descriptors, the coordinator and the cosigners.
+    pub federation_bundle: Option<PathBuf>,
+    /// Descriptors of the federation, replacing the other participants xpubs,
+    /// the threshold, the spending delay and the cosigners keys.
+    pub descriptors: Option<DescriptorsAnswers>,
+    pub stakeholder_xpub: Option<String>,
+    pub manager_xpub: Option<String>,
+    /// Xpubs of the other stakeholders.
+    #[serde(default)]
+    pub stakeholders_xpubs: Vec<String>,
+    /// Xpubs of the other managers.
+    #[serde(default)]
+    pub managers_xpubs: Vec<String>,
+    pub managers_threshold: Option<usize>,
+    pub spending_delay: Option<u32>,
+    #[serde(default)]
+    pub cosigners: Vec<CosignerAnswers>,
+    pub coordinator: Option<ServerAnswers>,
+    /// The default cookie path and address of the network are used if missing.
+    pub bitcoind: Option<BitcoindAnswers>,
+    pub emergency_address: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnswersRole {
+    Stakeholder,
+    Manager,
+    StakeholderManager,
+}
+
+impl AnswersRole {
+    pub fn roles(&self) -> &'static [Role] {
+        match self {
+            Self::Stakeholder => &Role::STAKEHOLDER_ONLY,
+            Self::Manager => &Role::MANAGER_ONLY,
+            Self::StakeholderManager => &Role::STAKEHOLDER_AND_MANAGER,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DescriptorsAnswers {
+    pub deposit_descriptor: String,
+    pub unvault_descriptor: String,
+    pub cpfp_descriptor: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ServerAnswers {
+    pub host: String,
+    pub noise_key: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CosignerAnswers {
+    /// Public key of the cosigning server in the unvault descriptor.
+    pub key: String,
+    pub host: String,
+    pub noise_key: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BitcoindAnswers {
+    pub cookie_path: Option<String>,
+    pub addr: Option<String>,
+}
+
+impl Answers {
+    pub fn from_file(path: &Path) -> Result<Self, Error> {
+        let content = std::fs::read(path).map_err(|e| Error::Answers(vec![e.to_string()]))?;
+        toml::from_slice(&content).map_err(|e| Error::Answers(vec![e.to_string()]))
+    }
+
+    /// Messages the user would send to the welcome step, the federation bundle
+    /// is read here.
+    pub fn welcome_messages(&self) -> Result<Vec<Message>, Error> {
+        let mut messages = vec![Message::Network(self.network)];
+        if let Some(path) = &self.federation_bundle {
+            messages.push(Message::BundleImported(Ok(read_bundle(path)?)));
+        }
+        Ok(messages)
+    }
+
+    pub fn private_noise_key_messages(&self) -> Vec<Message> {
+        let mut messages = Vec::new();
+        if let Some(key) = &self.private_noise_key {
+            messages.push(Message::DefinePrivateNoiseKey(
+                message::DefinePrivateNoiseKey::ManualEntry(true),
+            ));
+            messages.push(Message::DefinePrivateNoiseKey(
+                message::DefinePrivateNoiseKey::KeyEdited(key.clone()),
+            ));
+        }
+        messages
+    }
+
+    pub fn import_descriptors_messages(&self) -> Vec<Message> {
+        let mut messages = Vec::new();
+        if let Some(descriptors) = &self.descriptors {
+            messages.push(Message::ImportDescriptors(
+                message::ImportDescriptors::Enabled(true),
+            ));
+            messages.push(Message::ImportDescriptors(
+                message::ImportDescriptors::DepositDescriptorEdited(
+                    descriptors.deposit_descriptor.clone(),
+                ),
+            ));
+            messages.push(Message::ImportDescriptors(
+                message::ImportDescriptors::UnvaultDescriptorEdited(
+                    descriptors.unvault_descriptor.clone(),
+                ),
+            ));
+            messages.push(Message::ImportDescriptors(
+                message::ImportDescriptors::CpfpDescriptorEdited(
+                    descriptors.cpfp_descriptor.clone(),
+                ),
+            ));
+        }
+        messages
+    }
+
+    pub fn our_xpubs_messages(&self) -> Vec<Message> {
+        let mut messages = Vec::new();
+        if let Some(xpub) = &self.stakeholder_xpub {
+            messages.push(Message::DefineOurXpubs(
+                message::DefineOurXpubs::StakeholderXpubEdited(xpub.clone()),
+            ));
+        }
+        if let Some(xpub) = &self.manager_xpub {
+            messages.push(Message::DefineOurXpubs(
+                message::DefineOurXpubs::ManagerXpubEdited(xpub.clone()),
+            ));
+        }
+        messages
+    }
+
+    pub fn stakeholder_xpubs_messages(&self) -> Vec<Message> {
+        let mut messages = Vec::new();
+        if let Some(xpub) = &self.stakeholder_xpub {
+            messages.push(Message::DefineStakeholderXpubs(
+                message::DefineStakeholderXpubs::OurXpubEdited(xpub.clone()),
+            ));
+        }
+        for (i, xpub) in self.stakeholders_xpubs.iter().enumerate() {
+            messages.push(Message::DefineStakeholderXpubs(
+                message::DefineStakeholderXpubs::AddXpub,
+            ));
+            messages.push(Message::DefineStakeholderXpubs(
+                message::DefineStakeholderXpubs::StakeholderXpub(
+                    i,
+                    message::ParticipantXpub::XpubEdited(xpub.clone()),
+                ),
+            ));
+        }
+        messages
+    }
+
+    pub fn manager_xpubs_messages(&self) -> Vec<Message> {
+        let mut messages = Vec::new();
+        if let Some(xpub) = &self.manager_xpub {
+            messages.push(Message::DefineManagerXpubs(
+                message::DefineManagerXpubs::OurXpubEdited(xpub.clone()),
+            ));
+        }
+        for (i, xpub) in self.managers_xpubs.iter().enumerate() {
+            messages.push(Message::DefineManagerXpubs(
+                message::DefineManagerXpubs::AddXpub,
+            ));
+            messages.push(Message::DefineManagerXpubs(
+                message::DefineManagerXpubs::ManagerXpub(
+                    i,
+                    message::ParticipantXpub::XpubEdited(xpub.clone()),
+                ),
+            ));
+        }
+
+        // The threshold starts at 1 and the spending delay at 10 in the steps.
+        if let Some(threshold) = self.managers_threshold {
+            messages.extend(counter(
+                1,
+                threshold as i64,
+                message::DefineManagerXpubs::ManagersThreshold,
+            ));
+        }
+        if let Some(delay) = self.spending_delay {
+            messages.extend(counter(
+                10,
+                delay as i64,
+                message::DefineManagerXpubs::SpendingDelay,
+            ));
+        }
+
+        messages.push(Message::DefineManagerXpubs(
+            message::DefineManagerXpubs::CosignersEnabled(!self.cosigners.is_empty()),
+        ));
+        for (i, cosigner) in self.cosigners.iter().enumerate() {
+            messages.push(Message::DefineManagerXpubs(
+                message::DefineManagerXpubs::CosignerKey(i, cosigner.key.clone()),
+            ));
+        }
+        messages
+    }
+
+    /// The cpfp descriptor has the keys of all the managers.
+    pub fn cpfp_descriptor_messages(&self) -> Vec<Message> {
+        self.manager_xpub
+            .iter()
+            .chain(self.managers_xpubs.iter())
+            .enumerate()
+            .map(|(i, xpub)| {
+                Message::DefineCpfpDescriptor(message::DefineCpfpDescriptor::ManagerXpub(
+                    i,
+                    xpub.clone(),
+                ))
+            })
+            .collect()
+    }
+
+    pub fn coordinator_messages(&self) -> Vec<Message> {
+        let mut messages = Vec::new();
+        if let Some(coordinator) = &self.coordinator {
+            messages.push(Message::DefineCoordinator(
+                message::DefineCoordinator::HostEdited(coordinator.host.clone()),
+            ));
+            messages.push(Message::DefineCoordinator(
+                message::DefineCoordinator::NoiseKeyEdited(coordinator.noise_key.clone()),
+            ));
+        }
+        messages
+    }
+
+    pub fn cosigners_messages(&self) -> Vec<Message> {
+        let mut messages = Vec::new();
+        for (i, cosigner) in self.cosigners.iter().enumerate() {
+            messages.push(Message::DefineCosigners(
+                i,
+                message::DefineCosigner::HostEdited(cosigner.host.clone()),
+            ));
+            messages.push(Message::DefineCosigners(
+                i,
+                message::DefineCosigner::NoiseKeyEdited(cosigner.noise_key.clone()),
+            ));
+        }
+        messages
+    }
+
+    pub fn bitcoind_messages(&self) -> Vec<Message> {
+        let mut messages = Vec::new();
+        if let Some(bitcoind) = &self.bitcoind {
+            if let Some(path) = &bitcoind.cookie_path {
+                messages.push(Message::DefineBitcoind(
+                    message::DefineBitcoind::CookiePathEdited(path.clone()),
+                ));
+            }
+            if let Some(addr) = &bitcoind.addr {
+                messages.push(Message::DefineBitcoind(
+                    message::DefineBitcoind::AddressEdited(addr.clone()),
+                ));
+            }
+        }
+        messages
+    }
+
+    pub fn emergency_address_messages(&self) -> Vec<Message> {
+        self.emergency_address
+            .iter()
+            .map(|address| Message::DefineEmergencyAddress(address.clone()))
+            .collect()
+    }
+}
+
+/// Increments or decrements a step counter from its default value to the target.
+fn counter(
+    default: i64,
+    target: i64,
+    msg: fn(message::Action) -> message::DefineManagerXpubs,
+) -> Vec<Message> {
+    let action = if target >= default {
+        message::Action::Increment
+    } else {
+        message::Action::Decrement
+    };
+    (0..(target - default).abs())
+        .map(|_| Message::DefineManagerXpubs(msg(action.clone())))
+        .collect()
+}
+
+/// Runs the installer steps with the answers instead of the user inputs and
+/// installs the configuration files, returns the path of the GUI configuration.
+/// Each step only receives the answers to its own inputs, no draft is saved.
+pub async fn install_from_answers(
+    destination_path: PathBuf,
+    answers: Answers,
+) -> Result<PathBuf, Error> {
+    let mut context = Context::new(answers.network);
+    let mut config = DaemonConfig::new();
+    config.bitcoind_config.network = answers.network;
+    config.data_dir = Some(destination_path);
+    config.daemon = Some(true);
+
+    let mut steps = steps(answers.network, answers.role.roles());
+    // The last step is the installation itself.
+    let last = steps.len() - 1;
+    for step in steps.iter_mut().take(last) {
+        if step.skip(&context) {
+            continue;
+        }
+        step.load_context(&context);
+        for message in step.answers(&answers)? {
+            step.update(message);
+        }
+        if !step.apply(&mut context, &mut config) {
+            let errors = step.errors();
+            if errors.is_empty() {
+                return Err(Error::Answers(vec![
+                    "missing or invalid answers".to_string()
+                ]));
+            }
+            return Err(Error::Answers(errors));
+        }
+        context.setup_fingerprint = config.setup_fingerprint();
+    }
+
+    install(context, config).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::installer::draft;
+    use bitcoin::hashes::hex::FromHex;
+
+    #[test]
+    fn test_counter() {
+        assert_eq!(
+            counter(10, 12, message::DefineManagerXpubs::SpendingDelay).len(),
+            2
+        );
+        assert!(matches!(
+            counter(1, 0, message::DefineManagerXpubs::ManagersThreshold).as_slice(),
+            [Message::DefineManagerXpubs(
+                message::DefineManagerXpubs::ManagersThreshold(message::Action::Decrement)
+            )]
+        ));
+        assert!(counter(1, 1, message::DefineManagerXpubs::ManagersThreshold).is_empty());
+    }
+
+    const STAKEHOLDERS_XPUBS: [&str; 2] = [
+        "xpub6DEzq5DNPx2rPiZJ7wvFhxRKUKDoV1GwjFmFdaxFfbsw9HsHyxc9usoRUMxqJaMrwoXh4apahsGEnjAS4cVCBDgqsx5Groww22AdHbgxVDg",
+        "xpub6F7Ltmsut73cbUNAzh44DkxncMeQfPtRzx7aoXjFbUdd7yofR2intU4b6QcsXot1jgmVjHB3iMybCLhtqvhAx3L4VPbGUz5fwuyNeTkypUP",
+    ];
+
+    const MANAGERS_XPUBS: [&str; 2] = [
+        "xpub6CZFHPW1GiB8YgV7zGpeQDB6mMHZYPQyUaHrM1nMvKMgLxwok4xCtnzjuxQ3p1LHJUkz5i1Y7bRy5fmGrdg8UBVb39XdXNtWWd2wTsNd7T9",
+        "xpub6Doj75MBvKp7bgHxF1KeDGxm36rd4wonZWv8sfzTeNoNVX2QZaQdrEcs7NDXvs4Cbsy9TPMx5VDcMK6JjSKepBbYDPiJ9bLBR4bqfdHmxZx",
+    ];
+
+    const PRIVATE_NOISE_KEY: &str =
+        "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a";
+
+    /// Answers of a manager, `coordinator` is the table of the coordinator.
+    fn manager_answers(coordinator: &str) -> Answers {
+        toml::from_str(&format!(
+            r#"
+            network = "bitcoin"
+            role = "manager"
+            private_noise_key = "{}"
+            manager_xpub = "{}"
+            stakeholders_xpubs = ["{}", "{}"]
+            managers_xpubs = ["{}"]
+            managers_threshold = 2
+            spending_delay = 12
+
+            [bitcoind]
+            cookie_path = "/tmp/revault-gui-answers/.cookie"
+            addr = "127.0.0.1:8332"
+
+            {}
+            "#,
+            PRIVATE_NOISE_KEY,
+            MANAGERS_XPUBS[0],
+            STAKEHOLDERS_XPUBS[0],
+            STAKEHOLDERS_XPUBS[1],
+            MANAGERS_XPUBS[1],
+            coordinator,
+        ))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn install_manager_from_answers() {
+        let mut datadir = std::env::temp_dir();
+        datadir.push(format!("revault-gui-answers-{}", std::process::id()));
+
+        let answers = manager_answers(
+            r#"
+            [coordinator]
+            host = "127.0.0.1:8383"
+            noise_key = "d91563973102454a7830137e92d0548bc83b4ea2799f1df04622ca1307381402"
+            "#,
+        );
+        let gui_config_path = install_from_answers(datadir.clone(), answers)
+            .await
+            .unwrap();
+
+        let gui_config = crate::app::config::Config::from_file(&gui_config_path).unwrap();
+        assert_eq!(
+            gui_config.revaultd_config_path,
+            datadir.canonicalize().unwrap().join("revaultd.toml")
+        );
+        let daemon_config =
+            revaultd::config::Config::from_file(Some(gui_config.revaultd_config_path)).unwrap();
+        assert_eq!(
+            daemon_config.bitcoind_config.network,
+            bitcoin::Network::Bitcoin
+        );
+        assert_eq!(daemon_config.coordinator_host.to_string(), "127.0.0.1:8383");
+        assert!(daemon_config.stakeholder_config.is_none());
+        assert_eq!(
+            daemon_config.manager_config.map(|cfg| cfg.xpub.to_string()),
+            Some(MANAGERS_XPUBS[0].to_string())
+        );
+        let unvault_descriptor = daemon_config.scripts_config.unvault_descriptor.to_string();
+        for xpub in STAKEHOLDERS_XPUBS.iter().chain(MANAGERS_XPUBS.iter()) {
+            assert!(unvault_descriptor.contains(xpub));
+        }
+        assert!(unvault_descriptor.contains("older(12)"));
+
+        assert_eq!(
+            std::fs::read(datadir.join("bitcoin").join("noise_secret")).unwrap(),
+            Vec::from_hex(PRIVATE_NOISE_KEY).unwrap()
+        );
+        // The installation is not resumed later, no draft is left behind.
+        assert!(!datadir.join(draft::DEFAULT_FILE_NAME).exists());
+
+        std::fs::remove_dir_all(datadir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn install_from_invalid_answers() {
+        let mut datadir = std::env::temp_dir();
+        datadir.push(format!(
+            "revault-gui-invalid-answers-{}",
+            std::process::id()
+        ));
+
+        // The coordinator is missing.
+        let res = install_from_answers(datadir.clone(), manager_answers("")).await;
+        assert!(matches!(res, Err(Error::Answers(errors)) if !errors.is_empty()));
+        // The steps before the failing one did not write anything.
+        assert!(!datadir.exists());
+    }
+
+    #[test]
+    fn parse_answers() {
+        let answers: Answers = toml::from_str(
+            r#"
+            network = "regtest"
+            role = "stakeholder_manager"
+            stakeholder_xpub = "tpubA"
+            managers_xpubs = ["tpubB"]
+            managers_threshold = 2
+
+            [coordinator]
+            host = "127.0.0.1:8383"
+            noise_key = "d91563973102454a7830137e92d0548bc83b4ea2799f1df04622ca1307381402"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(answers.network, bitcoin::Network::Regtest);
+        assert_eq!(answers.role.roles(), &Role::STAKEHOLDER_AND_MANAGER);
+        assert!(answers.bitcoind.is_none());
+        assert!(answers.cosigners.is_empty());
+        assert!(answers.descriptors.is_none());
+    }
+}
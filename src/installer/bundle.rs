@@ -0,0 +1,343 @@
+use std::net::SocketAddr;
+use std::str::FromStr;
+
+use bitcoin::hashes::{
+    hex::{FromHex, ToHex},
+    sha256, Hash,
+};
+use revaultd::revault_tx::scripts::{CpfpDescriptor, DepositDescriptor, UnvaultDescriptor};
+use serde::{Deserialize, Serialize};
+
+use crate::{installer::config, revault::UnvaultParameters};
+
+/// Version of the federation bundle format.
+pub const VERSION: u32 = 1;
+
+pub const DEFAULT_FILE_NAME: &str = "federation_bundle.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cosigner {
+    pub host: String,
+    pub noise_key: String,
+}
+
+/// Federation is the part of the installation shared by all the participants,
+/// each participant only has to add its own keys and local settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Federation {
+    pub network: bitcoin::Network,
+    pub deposit_descriptor: String,
+    pub unvault_descriptor: String,
+    pub cpfp_descriptor: String,
+    pub coordinator_host: String,
+    pub coordinator_noise_key: String,
+    pub cosigners: Vec<Cosigner>,
+    pub managers_threshold: usize,
+}
+
+/// Bundle is the file exchanged between the participants.
+#[derive(Debug, Serialize, Deserialize)]
+struct Bundle {
+    version: u32,
+    federation: Federation,
+    /// Hex encoded sha256 of the json encoded federation.
+    checksum: String,
+}
+
+impl Federation {
+    /// Only the managers know the hosts of the cosigning servers, the export is refused
+    /// from the installation of a stakeholder if the unvault descriptor has cosigners.
+    pub fn from_config(cfg: &config::Config, managers_threshold: usize) -> Result<Self, Error> {
+        let federation = Self {
+            network: cfg.bitcoind_config.network,
+            deposit_descriptor: cfg.scripts_config.deposit_descriptor.clone(),
+            unvault_descriptor: cfg.scripts_config.unvault_descriptor.clone(),
+            cpfp_descriptor: cfg.scripts_config.cpfp_descriptor.clone(),
+            coordinator_host: cfg.coordinator_host.clone(),
+            coordinator_noise_key: cfg.coordinator_noise_key.clone(),
+            cosigners: cfg
+                .manager_config
+                .as_ref()
+                .map(|manager_config| {
+                    manager_config
+                        .cosigners
+                        .iter()
+                        .map(|cosigner| Cosigner {
+                            host: cosigner.host.to_string(),
+                            noise_key: cosigner.noise_key.0.to_hex(),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            managers_threshold,
+        };
+
+        if cfg.manager_config.is_none() && !federation.parameters()?.cosigners_keys.is_empty() {
+            return Err(Error::MissingCosigners);
+        }
+
+        federation.validate()?;
+        Ok(federation)
+    }
+
+    fn parameters(&self) -> Result<UnvaultParameters, Error> {
+        let deposit = DepositDescriptor::from_str(&self.deposit_descriptor)
+            .map_err(|e| Error::Invalid(format!("deposit descriptor: {}", e)))?;
+        let unvault = UnvaultDescriptor::from_str(&self.unvault_descriptor)
+            .map_err(|e| Error::Invalid(format!("unvault descriptor: {}", e)))?;
+        UnvaultParameters::new(&deposit, &unvault).ok_or_else(|| {
+            Error::Invalid("unvault descriptor does not match the deposit descriptor".to_string())
+        })
+    }
+
+    pub fn checksum(&self) -> String {
+        let json =
+            serde_json::to_vec(self).expect("Federation has a proper Serialize implementation");
+        sha256::Hash::hash(&json).to_hex()
+    }
+
+    /// Checks that the descriptors and the noise keys of the federation are correct,
+    /// and that the threshold and the cosigners match the unvault descriptor.
+    pub fn validate(&self) -> Result<(), Error> {
+        let parameters = self.parameters()?;
+        CpfpDescriptor::from_str(&self.cpfp_descriptor)
+            .map_err(|e| Error::Invalid(format!("cpfp descriptor: {}", e)))?;
+
+        check_host(&self.coordinator_host)
+            .map_err(|e| Error::Invalid(format!("coordinator host: {}", e)))?;
+        check_noise_key(&self.coordinator_noise_key)
+            .map_err(|e| Error::Invalid(format!("coordinator noise key: {}", e)))?;
+        for cosigner in &self.cosigners {
+            check_host(&cosigner.host)
+                .map_err(|e| Error::Invalid(format!("cosigner host: {}", e)))?;
+            check_noise_key(&cosigner.noise_key)
+                .map_err(|e| Error::Invalid(format!("cosigner noise key: {}", e)))?;
+        }
+
+        if self.managers_threshold != parameters.managers_threshold {
+            return Err(Error::Invalid(format!(
+                "managers threshold is {} but the unvault descriptor requires {}",
+                self.managers_threshold, parameters.managers_threshold
+            )));
+        }
+
+        if self.cosigners.len() != parameters.cosigners_keys.len() {
+            return Err(Error::Invalid(format!(
+                "{} cosigners are given but the unvault descriptor has {}",
+                self.cosigners.len(),
+                parameters.cosigners_keys.len()
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub fn to_bundle(&self) -> String {
+        serde_json::to_string_pretty(&Bundle {
+            version: VERSION,
+            federation: self.clone(),
+            checksum: self.checksum(),
+        })
+        .expect("Bundle has a proper Serialize implementation")
+    }
+
+    pub fn from_bundle(content: &str) -> Result<Self, Error> {
+        let version: BundleVersion =
+            serde_json::from_str(content).map_err(|e| Error::Parse(e.to_string()))?;
+        if version.version != VERSION {
+            return Err(Error::UnsupportedVersion(version.version));
+        }
+
+        let bundle: Bundle =
+            serde_json::from_str(content).map_err(|e| Error::Parse(e.to_string()))?;
+        if bundle.federation.checksum() != bundle.checksum {
+            return Err(Error::Checksum);
+        }
+
+        bundle.federation.validate()?;
+        Ok(bundle.federation)
+    }
+}
+
+/// Only the version is read first, in order to refuse the bundles
+/// of another format before parsing them.
+#[derive(Deserialize)]
+struct BundleVersion {
+    version: u32,
+}
+
+fn check_host(host: &str) -> Result<(), String> {
+    SocketAddr::from_str(host)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+fn check_noise_key(key: &str) -> Result<(), String> {
+    match Vec::<u8>::from_hex(key) {
+        Ok(bytes) if bytes.len() == 32 => Ok(()),
+        Ok(_) => Err("key must be 32 bytes".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    Parse(String),
+    UnsupportedVersion(u32),
+    Checksum,
+    Invalid(String),
+    /// The cosigners hosts are not known by a stakeholder only installation.
+    MissingCosigners,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Parse(e) => write!(f, "Failed to parse bundle: {}", e),
+            Self::UnsupportedVersion(v) => write!(f, "Unsupported bundle version: {}", v),
+            Self::Checksum => write!(f, "Bundle checksum does not match its content"),
+            Self::Invalid(e) => write!(f, "Invalid bundle: {}", e),
+            Self::MissingCosigners => write!(
+                f,
+                "The hosts of the cosigning servers are only known by the managers, \
+                 the bundle must be exported from a manager installation"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revaultd::revault_tx::miniscript::DescriptorPublicKey;
+
+    const STAKEHOLDERS_XPUBS: [&str; 2] = [
+        "xpub6DEzq5DNPx2rPiZJ7wvFhxRKUKDoV1GwjFmFdaxFfbsw9HsHyxc9usoRUMxqJaMrwoXh4apahsGEnjAS4cVCBDgqsx5Groww22AdHbgxVDg",
+        "xpub6F7Ltmsut73cbUNAzh44DkxncMeQfPtRzx7aoXjFbUdd7yofR2intU4b6QcsXot1jgmVjHB3iMybCLhtqvhAx3L4VPbGUz5fwuyNeTkypUP",
+    ];
+
+    const MANAGERS_XPUBS: [&str; 2] = [
+        "xpub6CZFHPW1GiB8YgV7zGpeQDB6mMHZYPQyUaHrM1nMvKMgLxwok4xCtnzjuxQ3p1LHJUkz5i1Y7bRy5fmGrdg8UBVb39XdXNtWWd2wTsNd7T9",
+        "xpub6Doj75MBvKp7bgHxF1KeDGxm36rd4wonZWv8sfzTeNoNVX2QZaQdrEcs7NDXvs4Cbsy9TPMx5VDcMK6JjSKepBbYDPiJ9bLBR4bqfdHmxZx",
+    ];
+
+    fn keys(xpubs: &[&str]) -> Vec<DescriptorPublicKey> {
+        xpubs
+            .iter()
+            .map(|xpub| DescriptorPublicKey::from_str(&format!("{}/*", xpub)).unwrap())
+            .collect()
+    }
+
+    const COSIGNERS_KEYS: [&str; 2] = [
+        "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+        "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5",
+    ];
+
+    fn federation() -> Federation {
+        federation_with_cosigners(&[])
+    }
+
+    fn federation_with_cosigners(cosigners_keys: &[&str]) -> Federation {
+        Federation {
+            network: bitcoin::Network::Bitcoin,
+            deposit_descriptor: DepositDescriptor::new(keys(&STAKEHOLDERS_XPUBS))
+                .unwrap()
+                .to_string(),
+            unvault_descriptor: UnvaultDescriptor::new(
+                keys(&STAKEHOLDERS_XPUBS),
+                keys(&MANAGERS_XPUBS),
+                1,
+                cosigners_keys
+                    .iter()
+                    .map(|key| DescriptorPublicKey::from_str(key).unwrap())
+                    .collect(),
+                10,
+            )
+            .unwrap()
+            .to_string(),
+            cpfp_descriptor: CpfpDescriptor::new(keys(&MANAGERS_XPUBS))
+                .unwrap()
+                .to_string(),
+            coordinator_host: "127.0.0.1:8383".to_string(),
+            coordinator_noise_key:
+                "d91563973102454a7830137e92d0548bc83b4ea2799f1df04622ca1307381402".to_string(),
+            cosigners: cosigners_keys
+                .iter()
+                .enumerate()
+                .map(|(i, _)| Cosigner {
+                    host: format!("127.0.0.1:{}", 8100 + i),
+                    noise_key: "d91563973102454a7830137e92d0548bc83b4ea2799f1df04622ca1307381402"
+                        .to_string(),
+                })
+                .collect(),
+            managers_threshold: 1,
+        }
+    }
+
+    fn config(federation: &Federation) -> config::Config {
+        let mut cfg = config::Config::new();
+        cfg.scripts_config = config::ScriptsConfig {
+            deposit_descriptor: federation.deposit_descriptor.clone(),
+            unvault_descriptor: federation.unvault_descriptor.clone(),
+            cpfp_descriptor: federation.cpfp_descriptor.clone(),
+        };
+        cfg.coordinator_host = federation.coordinator_host.clone();
+        cfg.coordinator_noise_key = federation.coordinator_noise_key.clone();
+        cfg
+    }
+
+    #[test]
+    fn bundle_roundtrip() {
+        let federation = federation();
+        let bundle = federation.to_bundle();
+        assert_eq!(Federation::from_bundle(&bundle).unwrap(), federation);
+    }
+
+    #[test]
+    fn bundle_checksum_mismatch() {
+        let federation = federation();
+        let bundle = federation
+            .to_bundle()
+            .replace("127.0.0.1:8383", "127.0.0.1:8384");
+        assert_eq!(Federation::from_bundle(&bundle), Err(Error::Checksum));
+    }
+
+    #[test]
+    fn bundle_unsupported_version() {
+        let bundle = federation()
+            .to_bundle()
+            .replace(&format!("\"version\": {}", VERSION), "\"version\": 42");
+        assert_eq!(
+            Federation::from_bundle(&bundle),
+            Err(Error::UnsupportedVersion(42))
+        );
+    }
+
+    #[test]
+    fn bundle_threshold_mismatch() {
+        let mut federation = federation();
+        federation.managers_threshold = 2;
+        assert_eq!(
+            Federation::from_bundle(&federation.to_bundle()),
+            Err(Error::Invalid(
+                "managers threshold is 2 but the unvault descriptor requires 1".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn export_from_stakeholder_config() {
+        // Without cosigners, a stakeholder has everything to export the bundle.
+        let federation = federation();
+        assert_eq!(
+            Federation::from_config(&config(&federation), 1),
+            Ok(federation)
+        );
+
+        let federation = federation_with_cosigners(&COSIGNERS_KEYS);
+        assert_eq!(
+            Federation::from_config(&config(&federation), 1),
+            Err(Error::MissingCosigners)
+        );
+    }
+}
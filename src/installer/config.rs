@@ -1,18 +1,24 @@
-use bitcoin::{util::bip32, Network};
-use revaultd::config::{BitcoindConfig, ManagerConfig, WatchtowerConfig};
+use bitcoin::{hashes::hex::FromHex, util::bip32, Network};
+use revaultd::{
+    config::{BitcoindConfig, ManagerConfig, WatchtowerConfig},
+    revault_net::noise::PublicKey as NoisePubkey,
+    revault_tx::scripts::{CpfpDescriptor, DepositDescriptor, UnvaultDescriptor},
+};
 
-use serde::Serialize;
-use std::{net::SocketAddr, path::PathBuf, time::Duration};
+use serde::{Deserialize, Serialize};
+use std::{net::SocketAddr, path::PathBuf, str::FromStr, time::Duration};
+
+use crate::revault::SetupFingerprint;
 
 /// If we are a stakeholder, we need to connect to our watchtower(s)
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StakeholderConfig {
     pub xpub: bip32::ExtendedPubKey,
     pub watchtowers: Vec<WatchtowerConfig>,
     pub emergency_address: String,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScriptsConfig {
     pub deposit_descriptor: String,
     pub unvault_descriptor: String,
@@ -20,7 +26,7 @@ pub struct ScriptsConfig {
 }
 
 /// Static informations we require to operate
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     /// Everything we need to know to talk to bitcoind
     pub bitcoind_config: BitcoindConfig,
@@ -73,6 +79,34 @@ impl Config {
             log_level: None,
         }
     }
+
+    /// Returns the configuration of an existing installation, the daemon writes
+    /// its configuration with the same keys as the installer.
+    pub fn from_daemon_config(cfg: &revaultd::config::Config) -> Result<Config, String> {
+        toml::Value::try_from(cfg)
+            .and_then(|value| value.try_into())
+            .map_err(|e| format!("Failed to read the daemon configuration: {}", e))
+    }
+
+    /// Returns None as long as the descriptors and the coordinator noise key
+    /// are not all defined.
+    pub fn setup_fingerprint(&self) -> Option<SetupFingerprint> {
+        let deposit_descriptor =
+            DepositDescriptor::from_str(&self.scripts_config.deposit_descriptor).ok()?;
+        let unvault_descriptor =
+            UnvaultDescriptor::from_str(&self.scripts_config.unvault_descriptor).ok()?;
+        let cpfp_descriptor =
+            CpfpDescriptor::from_str(&self.scripts_config.cpfp_descriptor).ok()?;
+        let coordinator_noise_key = Vec::from_hex(&self.coordinator_noise_key)
+            .ok()
+            .and_then(|bytes| NoisePubkey::from_slice(&bytes))?;
+        Some(SetupFingerprint::new(
+            &deposit_descriptor,
+            &unvault_descriptor,
+            &cpfp_descriptor,
+            &coordinator_noise_key,
+        ))
+    }
 }
 
 impl Default for Config {
@@ -0,0 +1,129 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    installer::{config, step::Context, Error},
+    revault::Role,
+};
+
+pub const DEFAULT_FILE_NAME: &str = "installer_draft.json";
+
+/// Draft is the progress of an unfinished installation, it is saved in the datadir
+/// after each step so that the installation can be resumed if the installer is closed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Draft {
+    pub role: Vec<Role>,
+    /// Index of the step to resume the installation from.
+    pub current: usize,
+    pub context: Context,
+    pub config: config::Config,
+}
+
+impl Draft {
+    pub fn new(role: &[Role], current: usize, ctx: &Context, cfg: &config::Config) -> Self {
+        let mut context = ctx.clone();
+        if !context.save_private_noise_key {
+            context.private_noise_key = "".to_string();
+        }
+        Self {
+            role: role.to_vec(),
+            current,
+            context,
+            config: cfg.clone(),
+        }
+    }
+
+    /// Returns the draft of the datadir, None if there is none or if it cannot be read.
+    pub fn load(datadir_path: &Path) -> Option<Self> {
+        let content = std::fs::read(path(datadir_path)).ok()?;
+        match serde_json::from_slice(&content) {
+            Ok(draft) => Some(draft),
+            Err(e) => {
+                log::warn!("Failed to read the installer draft: {}", e);
+                None
+            }
+        }
+    }
+
+    pub fn save(&self, datadir_path: &Path) -> Result<(), Error> {
+        std::fs::create_dir_all(datadir_path)
+            .map_err(|e| Error::CannotCreateDatadir(e.to_string()))?;
+        let content =
+            serde_json::to_vec_pretty(self).expect("Draft has a proper Serialize implementation");
+        std::fs::write(path(datadir_path), content)
+            .map_err(|e| Error::CannotWriteToFile(e.to_string()))
+    }
+
+    /// The role of the draft, one of the roles the installer steps are built for.
+    pub fn role(&self) -> &'static [Role] {
+        if self.role == Role::MANAGER_ONLY {
+            &Role::MANAGER_ONLY
+        } else if self.role == Role::STAKEHOLDER_ONLY {
+            &Role::STAKEHOLDER_ONLY
+        } else {
+            &Role::STAKEHOLDER_AND_MANAGER
+        }
+    }
+}
+
+pub fn path(datadir_path: &Path) -> PathBuf {
+    let mut path = datadir_path.to_path_buf();
+    path.push(DEFAULT_FILE_NAME);
+    path
+}
+
+/// Removes the draft of the datadir if there is one.
+pub fn remove(datadir_path: &Path) -> Result<(), Error> {
+    match std::fs::remove_file(path(datadir_path)) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(Error::Unexpected(format!(
+            "Failed to remove the draft: {}",
+            e
+        ))),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_and_load_draft() {
+        let mut datadir = std::env::temp_dir();
+        datadir.push(format!("revault-gui-draft-{}", std::process::id()));
+        assert!(Draft::load(&datadir).is_none());
+
+        let mut ctx = Context::new(bitcoin::Network::Regtest);
+        ctx.private_noise_key =
+            "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a".to_string();
+        ctx.managers_threshold = 2;
+        let mut cfg = config::Config::new();
+        cfg.coordinator_host = "127.0.0.1:8383".to_string();
+
+        Draft::new(&Role::MANAGER_ONLY, 4, &ctx, &cfg)
+            .save(&datadir)
+            .unwrap();
+        let draft = Draft::load(&datadir).unwrap();
+        assert_eq!(draft.role(), &Role::MANAGER_ONLY);
+        assert_eq!(draft.current, 4);
+        assert_eq!(draft.context.network, bitcoin::Network::Regtest);
+        assert_eq!(draft.context.managers_threshold, 2);
+        assert_eq!(draft.config.coordinator_host, "127.0.0.1:8383");
+        // The user did not opt in.
+        assert!(draft.context.private_noise_key.is_empty());
+
+        ctx.save_private_noise_key = true;
+        Draft::new(&Role::STAKEHOLDER_AND_MANAGER, 4, &ctx, &cfg)
+            .save(&datadir)
+            .unwrap();
+        let draft = Draft::load(&datadir).unwrap();
+        assert_eq!(draft.role(), &Role::STAKEHOLDER_AND_MANAGER);
+        assert_eq!(draft.context.private_noise_key, ctx.private_noise_key);
+
+        remove(&datadir).unwrap();
+        assert!(Draft::load(&datadir).is_none());
+        remove(&datadir).unwrap();
+        std::fs::remove_dir(datadir).unwrap();
+    }
+}
@@ -0,0 +1,279 @@
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use bitcoin::{hashes::hex::ToHex, util::bip32::ExtendedPubKey};
+use revaultd::revault_tx::{
+    miniscript::DescriptorPublicKey,
+    scripts::{DepositDescriptor, UnvaultDescriptor},
+};
+
+use crate::{
+    installer::{config, step::noise_public_key, xpub_with_origin},
+    revault::UnvaultParameters,
+};
+
+/// PreviousInstallation is the installation edited by the installer or the draft
+/// of an unfinished installation, the steps are pre-filled with its values.
+#[derive(Debug, Clone)]
+pub struct PreviousInstallation {
+    pub config: config::Config,
+    /// Empty if the noise secret of the installation could not be read.
+    pub private_noise_key: String,
+    pub stakeholders_xpubs: Vec<String>,
+    pub managers_xpubs: Vec<String>,
+    pub cosigners_keys: Vec<String>,
+    pub managers_threshold: usize,
+    pub spending_delay: u32,
+    /// False if the installation is a draft, its files were not written yet.
+    pub installed: bool,
+}
+
+impl PreviousInstallation {
+    pub fn new(
+        daemon_config: &revaultd::config::Config,
+        private_noise_key: String,
+    ) -> Result<Self, String> {
+        let config = config::Config::from_daemon_config(daemon_config)?;
+
+        let parameters = UnvaultParameters::new(
+            &daemon_config.scripts_config.deposit_descriptor,
+            &daemon_config.scripts_config.unvault_descriptor,
+        )
+        .ok_or_else(|| "Failed to read the parameters of the unvault descriptor".to_string())?;
+
+        Ok(Self {
+            config,
+            private_noise_key,
+            stakeholders_xpubs: parameters
+                .stakeholders_keys
+                .iter()
+                .map(key_to_string)
+                .collect(),
+            managers_xpubs: parameters.managers_keys.iter().map(key_to_string).collect(),
+            cosigners_keys: parameters
+                .cosigners_keys
+                .iter()
+                .map(key_to_string)
+                .collect(),
+            managers_threshold: parameters.managers_threshold,
+            spending_delay: parameters.spending_delay,
+            installed: true,
+        })
+    }
+
+    /// The descriptors of the draft are not built if the installer was closed
+    /// before the steps defining them.
+    pub fn from_draft(config: config::Config, private_noise_key: String) -> Self {
+        let deposit = DepositDescriptor::from_str(&config.scripts_config.deposit_descriptor).ok();
+        let unvault = UnvaultDescriptor::from_str(&config.scripts_config.unvault_descriptor).ok();
+        let parameters = match (&deposit, &unvault) {
+            (Some(deposit), Some(unvault)) => UnvaultParameters::new(deposit, unvault),
+            _ => None,
+        };
+        Self {
+            stakeholders_xpubs: deposit
+                .map(|deposit| deposit.xpubs().iter().map(key_to_string).collect())
+                .unwrap_or_default(),
+            managers_xpubs: parameters
+                .as_ref()
+                .map(|p| p.managers_keys.iter().map(key_to_string).collect())
+                .unwrap_or_default(),
+            cosigners_keys: parameters
+                .as_ref()
+                .map(|p| p.cosigners_keys.iter().map(key_to_string).collect())
+                .unwrap_or_default(),
+            // Default values of the steps.
+            managers_threshold: parameters.as_ref().map_or(1, |p| p.managers_threshold),
+            spending_delay: parameters.as_ref().map_or(10, |p| p.spending_delay),
+            config,
+            private_noise_key,
+            installed: false,
+        }
+    }
+
+    /// Our stakeholder xpub, with its key origin if the descriptor has one.
+    pub fn stakeholder_xpub(&self) -> Option<String> {
+        self.config
+            .stakeholder_config
+            .as_ref()
+            .map(|cfg| with_origin(&self.stakeholders_xpubs, &cfg.xpub))
+    }
+
+    /// Our manager xpub, with its key origin if the descriptor has one.
+    pub fn manager_xpub(&self) -> Option<String> {
+        self.config
+            .manager_config
+            .as_ref()
+            .map(|cfg| with_origin(&self.managers_xpubs, &cfg.xpub))
+    }
+
+    /// Stakeholders xpubs without ours.
+    pub fn other_stakeholders_xpubs(&self) -> Vec<String> {
+        let ours = self.stakeholder_xpub();
+        self.stakeholders_xpubs
+            .iter()
+            .filter(|xpub| Some(*xpub) != ours.as_ref())
+            .cloned()
+            .collect()
+    }
+
+    /// Managers xpubs without ours.
+    pub fn other_managers_xpubs(&self) -> Vec<String> {
+        let ours = self.manager_xpub();
+        self.managers_xpubs
+            .iter()
+            .filter(|xpub| Some(*xpub) != ours.as_ref())
+            .cloned()
+            .collect()
+    }
+
+    /// Keeps the settings the installer does not ask for.
+    pub fn keep_local_settings(&self, config: &mut config::Config) {
+        config.coordinator_poll_seconds = self.config.coordinator_poll_seconds;
+        config.log_level = self.config.log_level.clone();
+        config.daemon = self.config.daemon;
+        if let (Some(previous), Some(stakeholder_config)) = (
+            &self.config.stakeholder_config,
+            &mut config.stakeholder_config,
+        ) {
+            stakeholder_config.watchtowers = previous.watchtowers.clone();
+        }
+    }
+
+    /// Changes of the daemon configuration and of the noise key.
+    pub fn changes(&self, config: &config::Config, private_noise_key: &str) -> Vec<Change> {
+        let mut changes = diff(&to_toml(&self.config), &to_toml(config));
+        if self.private_noise_key != private_noise_key {
+            let public_key = |key: &str| {
+                noise_public_key(key)
+                    .map(|key| key.0.to_hex())
+                    .unwrap_or_default()
+            };
+            changes.push(Change::Removed(format!(
+                "noise public key = {}",
+                public_key(&self.private_noise_key)
+            )));
+            changes.push(Change::Added(format!(
+                "noise public key = {}",
+                public_key(private_noise_key)
+            )));
+        }
+        changes
+    }
+}
+
+/// Keys are written as in the installer forms: the xpub with its key origin if any.
+fn key_to_string(key: &DescriptorPublicKey) -> String {
+    match key {
+        DescriptorPublicKey::XPub(xpub) => match &xpub.origin {
+            Some((fingerprint, path)) => xpub_with_origin(fingerprint, path, &xpub.xkey),
+            None => xpub.xkey.to_string(),
+        },
+        DescriptorPublicKey::SinglePub(single) => single.key.to_string(),
+    }
+}
+
+/// Finds the xpub in the keys of the descriptor to get its key origin.
+fn with_origin(keys: &[String], xpub: &ExtendedPubKey) -> String {
+    let xpub = xpub.to_string();
+    keys.iter()
+        .find(|key| key.ends_with(&xpub))
+        .cloned()
+        .unwrap_or(xpub)
+}
+
+fn to_toml(config: &config::Config) -> String {
+    // Step needed because of ValueAfterTable error in the toml serialize implementation.
+    toml::Value::try_from(config)
+        .expect("daemon::Config has a proper Serialize implementation")
+        .to_string()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    Removed(String),
+    Added(String),
+}
+
+/// Returns the removed and added lines, in the order of the texts.
+pub fn diff(old: &str, new: &str) -> Vec<Change> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // lcs[i][j] is the length of the longest common subsequence of old[i..] and new[j..].
+    let mut lcs = vec![vec![0; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut changes = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            changes.push(Change::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            changes.push(Change::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    changes
+}
+
+/// Copies the file next to itself with a timestamp suffix if it exists,
+/// returns the path of the copy.
+pub fn backup(path: &Path) -> std::io::Result<Option<PathBuf>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(
+        ".{}.bak",
+        chrono::Utc::now().format("%Y%m%d%H%M%S")
+    ));
+    let backup_path = path.with_file_name(name);
+    std::fs::copy(path, &backup_path)?;
+    Ok(Some(backup_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let old = "a = 1\nb = 2\nc = 3";
+        let new = "a = 1\nb = 4\nc = 3\nd = 5";
+        assert_eq!(
+            diff(old, new),
+            vec![
+                Change::Removed("b = 2".to_string()),
+                Change::Added("b = 4".to_string()),
+                Change::Added("d = 5".to_string()),
+            ]
+        );
+        assert_eq!(diff(old, old), Vec::new());
+    }
+
+    #[test]
+    fn test_backup() {
+        let mut path = std::env::temp_dir();
+        path.push(format!("revault-gui-backup-{}.toml", std::process::id()));
+        assert_eq!(backup(&path).unwrap(), None);
+
+        std::fs::write(&path, "a = 1").unwrap();
+        let backup_path = backup(&path).unwrap().unwrap();
+        assert_eq!(std::fs::read_to_string(&backup_path).unwrap(), "a = 1");
+        std::fs::remove_file(path).unwrap();
+        std::fs::remove_file(backup_path).unwrap();
+    }
+}
@@ -0,0 +1,415 @@
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use bitcoin::{
+    blockdata::{opcodes, script::Builder},
+    hashes::hex::{FromHex, ToHex},
+    secp256k1,
+    util::bip32::{ExtendedPrivKey, ExtendedPubKey},
+    Address,
+};
+use revaultd::{
+    config::{CosignerConfig, ManagerConfig},
+    revault_net::sodiumoxide::{self, randombytes::randombytes},
+    revault_tx::{
+        miniscript::DescriptorPublicKey,
+        scripts::{CpfpDescriptor, DepositDescriptor, UnvaultDescriptor},
+    },
+};
+use serde::Serialize;
+
+use crate::{
+    installer::{
+        bundle, config, export_bundle, install, revault_derivation_path,
+        step::{
+            bitcoind_default_address, bitcoind_default_cookie_path, generate_private_noise_key,
+            noise_public_key, Context,
+        },
+        Error,
+    },
+    revault::Role,
+};
+
+/// The generated federation is for development only.
+pub const NETWORK: bitcoin::Network = bitcoin::Network::Regtest;
+
+const COORDINATOR_HOST: &str = "127.0.0.1:8383";
+/// The cosigning servers listen on the following ports.
+const COSIGNER_FIRST_PORT: u16 = 8484;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FederationParameters {
+    pub stakeholders: usize,
+    pub managers: usize,
+    pub managers_threshold: usize,
+    pub spending_delay: u32,
+}
+
+struct Participant {
+    name: String,
+    xpriv: ExtendedPrivKey,
+    xpub: ExtendedPubKey,
+    private_noise_key: String,
+}
+
+impl Participant {
+    fn generate(role: Role, index: usize) -> Result<Self, Error> {
+        let curve = secp256k1::Secp256k1::new();
+        let master = ExtendedPrivKey::new_master(NETWORK, &randombytes(32))
+            .map_err(|e| Error::Generate(e.to_string()))?;
+        let xpriv = master
+            .derive_priv(&curve, &revault_derivation_path(NETWORK, role))
+            .map_err(|e| Error::Generate(e.to_string()))?;
+        Ok(Self {
+            name: format!("{}_{}", role.to_string().to_lowercase(), index + 1),
+            xpub: ExtendedPubKey::from_private(&curve, &xpriv),
+            xpriv,
+            private_noise_key: generate_private_noise_key()?,
+        })
+    }
+}
+
+/// A cosigning server, its bitcoin key is part of the unvault descriptor.
+struct Cosigner {
+    host: SocketAddr,
+    private_noise_key: String,
+    secret_key: secp256k1::SecretKey,
+}
+
+/// Configuration of the dummysigner of a participant, see contrib/tools/dummysigner.
+#[derive(Serialize)]
+struct DummySignerConfig {
+    emergency_address: String,
+    keys: Vec<DummySignerKey>,
+    descriptors: config::ScriptsConfig,
+}
+
+#[derive(Serialize)]
+struct DummySignerKey {
+    name: String,
+    xpriv: String,
+}
+
+/// Noise public keys the coordinator and the cosigning servers are configured with.
+#[derive(Serialize)]
+struct NoisePublicKeys {
+    coordinator: String,
+    stakeholders: Vec<String>,
+    managers: Vec<String>,
+    cosigners: Vec<String>,
+}
+
+/// Generates a regtest federation in the destination directory:
+/// - `stakeholder_<i>` and `manager_<i>`: the GUI and daemon configurations, the noise
+///   secret and the dummysigner configuration of each participant.
+/// - `coordinator` and `cosigner_<i>`: the noise secret of the servers and the bitcoin
+///   secret of the cosigning servers.
+/// - the noise public keys of everyone, the federation bundle and the emergency secret.
+/// Returns the paths of the GUI configurations.
+pub async fn generate_federation(
+    params: FederationParameters,
+    destination_path: PathBuf,
+) -> Result<Vec<PathBuf>, Error> {
+    if params.managers_threshold == 0 || params.managers_threshold > params.managers {
+        return Err(Error::Generate(format!(
+            "the managers threshold must be between 1 and {}",
+            params.managers
+        )));
+    }
+    sodiumoxide::init().map_err(|_| Error::Generate("Failed to initialize libsodium".into()))?;
+
+    let stakeholders = (0..params.stakeholders)
+        .map(|i| Participant::generate(Role::Stakeholder, i))
+        .collect::<Result<Vec<_>, _>>()?;
+    let managers = (0..params.managers)
+        .map(|i| Participant::generate(Role::Manager, i))
+        .collect::<Result<Vec<_>, _>>()?;
+    // Each stakeholder runs a cosigning server.
+    let cosigners = (0..params.stakeholders)
+        .map(|i| {
+            Ok(Cosigner {
+                host: SocketAddr::from(([127, 0, 0, 1], COSIGNER_FIRST_PORT + i as u16)),
+                private_noise_key: generate_private_noise_key()?,
+                secret_key: secret_key()?,
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    let coordinator_private_noise_key = generate_private_noise_key()?;
+
+    let curve = secp256k1::Secp256k1::new();
+    let stakeholders_keys = descriptor_keys(&stakeholders)?;
+    let managers_keys = descriptor_keys(&managers)?;
+    let cosigners_keys = cosigners
+        .iter()
+        .map(|cosigner| {
+            let public_key = secp256k1::PublicKey::from_secret_key(&curve, &cosigner.secret_key);
+            DescriptorPublicKey::from_str(&public_key.to_string())
+                .map_err(|e| Error::Generate(e.to_string()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let deposit_descriptor = DepositDescriptor::new(stakeholders_keys.clone())
+        .map_err(|e| Error::Generate(e.to_string()))?;
+    let unvault_descriptor = UnvaultDescriptor::new(
+        stakeholders_keys,
+        managers_keys.clone(),
+        params.managers_threshold,
+        cosigners_keys,
+        params.spending_delay,
+    )
+    .map_err(|e| Error::Generate(e.to_string()))?;
+    let cpfp_descriptor =
+        CpfpDescriptor::new(managers_keys).map_err(|e| Error::Generate(e.to_string()))?;
+
+    // The emergency address is a single key p2wsh, its secret is written next to the
+    // participants directories.
+    let emergency_secret_key = secret_key()?;
+    let emergency_public_key = bitcoin::PublicKey {
+        compressed: true,
+        key: secp256k1::PublicKey::from_secret_key(&curve, &emergency_secret_key),
+    };
+    let emergency_address = Address::p2wsh(
+        &Builder::new()
+            .push_key(&emergency_public_key)
+            .push_opcode(opcodes::all::OP_CHECKSIG)
+            .into_script(),
+        NETWORK,
+    )
+    .to_string();
+
+    let mut cfg = config::Config::new();
+    cfg.bitcoind_config.network = NETWORK;
+    cfg.bitcoind_config.cookie_path =
+        PathBuf::from(bitcoind_default_cookie_path(&NETWORK).unwrap_or_default());
+    cfg.bitcoind_config.addr =
+        SocketAddr::from_str(&bitcoind_default_address(&NETWORK)).expect("Hardcoded address");
+    cfg.scripts_config = config::ScriptsConfig {
+        deposit_descriptor: deposit_descriptor.to_string(),
+        unvault_descriptor: unvault_descriptor.to_string(),
+        cpfp_descriptor: cpfp_descriptor.to_string(),
+    };
+    cfg.coordinator_host = COORDINATOR_HOST.to_string();
+    cfg.coordinator_noise_key = public_key_hex(&coordinator_private_noise_key);
+    cfg.daemon = Some(true);
+
+    let manager_config = |xpub: ExtendedPubKey| ManagerConfig {
+        xpub,
+        cosigners: cosigners
+            .iter()
+            .map(|cosigner| CosignerConfig {
+                host: cosigner.host,
+                noise_key: noise_public_key(&cosigner.private_noise_key).expect("Generated key"),
+            })
+            .collect(),
+    };
+
+    let mut gui_config_paths = Vec::new();
+    for (participant, role) in stakeholders
+        .iter()
+        .map(|participant| (participant, Role::Stakeholder))
+        .chain(
+            managers
+                .iter()
+                .map(|participant| (participant, Role::Manager)),
+        )
+    {
+        let mut participant_path = destination_path.clone();
+        participant_path.push(&participant.name);
+
+        let mut participant_cfg = cfg.clone();
+        participant_cfg.data_dir = Some(participant_path.clone());
+        match role {
+            Role::Stakeholder => {
+                participant_cfg.stakeholder_config = Some(config::StakeholderConfig {
+                    xpub: participant.xpub,
+                    watchtowers: Vec::new(),
+                    emergency_address: emergency_address.clone(),
+                })
+            }
+            Role::Manager => {
+                participant_cfg.manager_config = Some(manager_config(participant.xpub))
+            }
+        }
+
+        let mut ctx = Context::new(NETWORK);
+        ctx.private_noise_key = participant.private_noise_key.clone();
+        gui_config_paths.push(install(ctx, participant_cfg).await?);
+
+        let dummysigner_config = DummySignerConfig {
+            emergency_address: emergency_address.clone(),
+            keys: vec![DummySignerKey {
+                name: participant.name.clone(),
+                xpriv: participant.xpriv.to_string(),
+            }],
+            descriptors: cfg.scripts_config.clone(),
+        };
+        participant_path.push("dummysigner.toml");
+        write_file(
+            &participant_path,
+            toml::Value::try_from(&dummysigner_config)
+                .expect("DummySignerConfig has a proper Serialize implementation")
+                .to_string()
+                .as_bytes(),
+        )?;
+    }
+
+    let mut coordinator_path = destination_path.clone();
+    coordinator_path.push("coordinator");
+    write_server_keys(&coordinator_path, &coordinator_private_noise_key)?;
+
+    for (i, cosigner) in cosigners.iter().enumerate() {
+        let mut cosigner_path = destination_path.clone();
+        cosigner_path.push(format!("cosigner_{}", i + 1));
+        write_server_keys(&cosigner_path, &cosigner.private_noise_key)?;
+        cosigner_path.push("bitcoin_secret");
+        write_file(&cosigner_path, &cosigner.secret_key[..])?;
+    }
+
+    let noise_public_keys = NoisePublicKeys {
+        coordinator: public_key_hex(&coordinator_private_noise_key),
+        stakeholders: stakeholders
+            .iter()
+            .map(|participant| public_key_hex(&participant.private_noise_key))
+            .collect(),
+        managers: managers
+            .iter()
+            .map(|participant| public_key_hex(&participant.private_noise_key))
+            .collect(),
+        cosigners: cosigners
+            .iter()
+            .map(|cosigner| public_key_hex(&cosigner.private_noise_key))
+            .collect(),
+    };
+    let mut noise_public_keys_path = destination_path.clone();
+    noise_public_keys_path.push("noise_public_keys.toml");
+    write_file(
+        &noise_public_keys_path,
+        toml::to_string(&noise_public_keys)
+            .expect("NoisePublicKeys has a proper Serialize implementation")
+            .as_bytes(),
+    )?;
+
+    let mut emergency_path = destination_path.clone();
+    emergency_path.push("emergency_secret");
+    write_file(
+        &emergency_path,
+        bitcoin::PrivateKey {
+            compressed: true,
+            network: NETWORK,
+            key: emergency_secret_key,
+        }
+        .to_wif()
+        .as_bytes(),
+    )?;
+
+    let mut bundle_cfg = cfg;
+    bundle_cfg.manager_config = managers
+        .first()
+        .map(|participant| manager_config(participant.xpub));
+    export_bundle(
+        bundle::Federation::from_config(&bundle_cfg, params.managers_threshold)
+            .map_err(|e| Error::Generate(e.to_string()))?,
+        destination_path,
+    )
+    .await?;
+
+    Ok(gui_config_paths)
+}
+
+fn secret_key() -> Result<secp256k1::SecretKey, Error> {
+    secp256k1::SecretKey::from_slice(&randombytes(32)).map_err(|e| Error::Generate(e.to_string()))
+}
+
+/// Keys of the descriptors, sorted the same way as in the installer.
+fn descriptor_keys(participants: &[Participant]) -> Result<Vec<DescriptorPublicKey>, Error> {
+    let mut xpubs: Vec<String> = participants
+        .iter()
+        .map(|participant| format!("{}/*", participant.xpub))
+        .collect();
+    xpubs.sort();
+    xpubs
+        .iter()
+        .map(|xpub| DescriptorPublicKey::from_str(xpub).map_err(|e| Error::Generate(e.to_string())))
+        .collect()
+}
+
+fn public_key_hex(private_noise_key: &str) -> String {
+    noise_public_key(private_noise_key)
+        .expect("Generated key")
+        .0
+        .to_hex()
+}
+
+/// Writes the noise secret as the servers read it and its public key.
+fn write_server_keys(path: &Path, private_noise_key: &str) -> Result<(), Error> {
+    std::fs::create_dir_all(path).map_err(|e| Error::CannotCreateDatadir(e.to_string()))?;
+    let mut secret_path = path.to_path_buf();
+    secret_path.push("noise_secret");
+    write_file(
+        &secret_path,
+        &Vec::from_hex(private_noise_key).expect("Generated key"),
+    )?;
+    let mut public_key_path = path.to_path_buf();
+    public_key_path.push("noise_public_key");
+    write_file(
+        &public_key_path,
+        public_key_hex(private_noise_key).as_bytes(),
+    )
+}
+
+fn write_file(path: &Path, content: &[u8]) -> Result<(), Error> {
+    std::fs::write(path, content).map_err(|e| Error::CannotWriteToFile(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_generate_federation() {
+        let mut path = std::env::temp_dir();
+        path.push(format!("revault-gui-federation-{}", std::process::id()));
+        let params = FederationParameters {
+            stakeholders: 2,
+            managers: 2,
+            managers_threshold: 3,
+            spending_delay: 10,
+        };
+        assert!(generate_federation(params.clone(), path.clone())
+            .await
+            .is_err());
+
+        let params = FederationParameters {
+            managers_threshold: 2,
+            ..params
+        };
+        let gui_config_paths = generate_federation(params, path.clone()).await.unwrap();
+        assert_eq!(gui_config_paths.len(), 4);
+        for gui_config_path in &gui_config_paths {
+            let gui_config = crate::app::config::Config::from_file(gui_config_path).unwrap();
+            revaultd::config::Config::from_file(Some(gui_config.revaultd_config_path)).unwrap();
+        }
+
+        let mut bundle_path = path.clone();
+        bundle_path.push(bundle::DEFAULT_FILE_NAME);
+        let federation = crate::installer::import_bundle(bundle_path).await.unwrap();
+        assert_eq!(federation.managers_threshold, 2);
+        assert_eq!(federation.cosigners.len(), 2);
+
+        for file in &[
+            "stakeholder_1/dummysigner.toml",
+            "manager_2/regtest/noise_secret",
+            "coordinator/noise_secret",
+            "cosigner_2/bitcoin_secret",
+            "noise_public_keys.toml",
+            "emergency_secret",
+        ] {
+            let mut file_path = path.clone();
+            file_path.push(file);
+            assert!(file_path.exists(), "{} is missing", file);
+        }
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+}
@@ -1,7 +1,16 @@
+use std::net::SocketAddr;
 use std::path::PathBuf;
 
-use super::Error;
-use crate::revault::Role;
+use revaultd::{
+    revault_net::noise::PublicKey as NoisePubkey, revault_tx::miniscript::DescriptorPublicKey,
+};
+
+use super::{bundle::Federation, Error};
+use crate::{
+    bitcoind::{self, BlockchainInfo},
+    revault::Role,
+    server,
+};
 
 #[derive(Debug, Clone)]
 pub enum Message {
@@ -12,8 +21,24 @@ pub enum Message {
     Install,
     Installed(Result<PathBuf, Error>),
     Role(&'static [Role]),
-    PrivateNoiseKey(String),
+    /// Resume the unfinished installation saved in the datadir.
+    ResumeDraft,
+    DiscardDraft,
+    DefinePrivateNoiseKey(DefinePrivateNoiseKey),
+    ExportNoisePublicKey(NoisePubkey),
+    NoisePublicKeyExported(Result<PathBuf, Error>),
     Network(bitcoin::Network),
+    /// Import our xpub for the given role from the connected hardware wallet.
+    ImportXpub(Role),
+    XpubImported(Role, Result<DescriptorPublicKey, Error>),
+    BundlePathEdited(String),
+    ImportBundle(PathBuf),
+    BundleImported(Result<Federation, Error>),
+    ExportBundle,
+    BundleExported(Result<PathBuf, Error>),
+    ToggleFingerprintQRCode,
+    ImportDescriptors(ImportDescriptors),
+    DefineOurXpubs(DefineOurXpubs),
     DefineStakeholderXpubs(DefineStakeholderXpubs),
     DefineManagerXpubs(DefineManagerXpubs),
     DefineCpfpDescriptor(DefineCpfpDescriptor),
@@ -23,22 +48,38 @@ pub enum Message {
     DefineBitcoind(DefineBitcoind),
 }
 
+#[derive(Debug, Clone)]
+pub enum DefinePrivateNoiseKey {
+    Generate,
+    /// The key is typed by the user instead of being generated.
+    ManualEntry(bool),
+    KeyEdited(String),
+    /// The private key is written in the installation draft.
+    SaveInDraft(bool),
+}
+
 #[derive(Debug, Clone)]
 pub enum DefineBitcoind {
     CookiePathEdited(String),
     AddressEdited(String),
+    TestConnection,
+    ConnectionTested(Result<BlockchainInfo, bitcoind::Error>),
 }
 
 #[derive(Debug, Clone)]
 pub enum DefineCosigner {
     HostEdited(String),
     NoiseKeyEdited(String),
+    TestConnection(SocketAddr, NoisePubkey),
+    ConnectionTested(Result<(), server::Error>),
 }
 
 #[derive(Debug, Clone)]
 pub enum DefineCoordinator {
     HostEdited(String),
     NoiseKeyEdited(String),
+    TestConnection(SocketAddr, NoisePubkey),
+    ConnectionTested(Result<(), server::Error>),
 }
 
 #[derive(Debug, Clone)]
@@ -53,6 +94,21 @@ pub enum DefineStakeholderXpubs {
     AddXpub,
 }
 
+#[derive(Debug, Clone)]
+pub enum ImportDescriptors {
+    /// The descriptors are given as a whole instead of being built from the xpubs.
+    Enabled(bool),
+    DepositDescriptorEdited(String),
+    UnvaultDescriptorEdited(String),
+    CpfpDescriptorEdited(String),
+}
+
+#[derive(Debug, Clone)]
+pub enum DefineOurXpubs {
+    StakeholderXpubEdited(String),
+    ManagerXpubEdited(String),
+}
+
 #[derive(Debug, Clone)]
 pub enum DefineManagerXpubs {
     ManagersThreshold(Action),
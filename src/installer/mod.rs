@@ -1,4 +1,9 @@
+mod answers;
+mod bundle;
 mod config;
+mod draft;
+mod edit;
+mod generate;
 mod message;
 mod step;
 mod view;
@@ -6,16 +11,36 @@ mod view;
 use iced::{Command, Element, Subscription};
 use iced_native::{window, Event};
 
-use bitcoin::hashes::hex::FromHex;
+use bitcoin::{
+    hashes::hex::{FromHex, ToHex},
+    util::bip32::{ChildNumber, DerivationPath, ExtendedPubKey, Fingerprint},
+};
 use std::io::Write;
-use std::path::PathBuf;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::str::FromStr;
+
+use revault_hwi::HWI;
+use revaultd::{
+    revault_net::noise::{PublicKey as NoisePubkey, SecretKey as NoisePrivkey},
+    revault_tx::miniscript::DescriptorPublicKey,
+};
 
-use crate::{app::config as gui_config, installer::config::Config as DaemonConfig, revault::Role};
+use crate::{
+    app::{config as gui_config, context::HardwareWallet},
+    bitcoind,
+    installer::config::Config as DaemonConfig,
+    revault::Role,
+    server,
+};
 
+pub use answers::{install_from_answers, Answers};
+pub use generate::{generate_federation, FederationParameters};
 pub use message::Message;
 use step::{
     manager, stakeholder, Context, DefineBitcoind, DefineCoordinator, DefineCpfpDescriptor,
-    DefinePrivateNoiseKey, DefineRole, Final, Step, Welcome,
+    DefineOurXpubs, DefinePrivateNoiseKey, DefineRole, Final, ImportDescriptors, Step, Welcome,
 };
 
 pub struct Installer {
@@ -26,6 +51,14 @@ pub struct Installer {
     /// Context is data passed through each step.
     context: Context,
     config: DaemonConfig,
+    /// Some once the role is chosen, the steps depend on it.
+    role: Option<&'static [Role]>,
+    /// Draft of an unfinished installation found in the datadir, the user
+    /// resumes or discards it.
+    draft: Option<draft::Draft>,
+
+    /// Connects to the hardware wallet the participant xpubs are imported from.
+    hardware_wallet: Box<dyn Fn() -> Pin<HardwareWallet> + Send + Sync>,
 }
 
 impl Installer {
@@ -41,50 +74,6 @@ impl Installer {
         }
     }
 
-    fn update_steps(&mut self, network: bitcoin::Network, role: &[Role]) {
-        if role == Role::MANAGER_ONLY {
-            self.steps = vec![
-                Welcome::new(network).into(),
-                DefineRole::new().into(),
-                DefinePrivateNoiseKey::new().into(),
-                manager::DefineStakeholderXpubs::new().into(),
-                manager::DefineManagerXpubs::new().into(),
-                DefineCpfpDescriptor::new().into(),
-                DefineCoordinator::new().into(),
-                manager::DefineCosigners::new().into(),
-                DefineBitcoind::new().into(),
-                Final::new().into(),
-            ];
-        } else if role == Role::STAKEHOLDER_ONLY {
-            self.steps = vec![
-                Welcome::new(network).into(),
-                DefineRole::new().into(),
-                DefinePrivateNoiseKey::new().into(),
-                stakeholder::DefineStakeholderXpubs::new().into(),
-                stakeholder::DefineManagerXpubs::new().into(),
-                DefineCpfpDescriptor::new().into(),
-                DefineCoordinator::new().into(),
-                DefineBitcoind::new().into(),
-                stakeholder::DefineEmergencyAddress::new().into(),
-                Final::new().into(),
-            ];
-        } else {
-            self.steps = vec![
-                Welcome::new(network).into(),
-                DefineRole::new().into(),
-                DefinePrivateNoiseKey::new().into(),
-                stakeholder::DefineStakeholderXpubs::new().into(),
-                manager::DefineManagerXpubs::new().into(),
-                DefineCpfpDescriptor::new().into(),
-                DefineCoordinator::new().into(),
-                manager::DefineCosigners::new().into(),
-                DefineBitcoind::new().into(),
-                stakeholder::DefineEmergencyAddress::new().into(),
-                Final::new().into(),
-            ];
-        }
-    }
-
     fn current_step(&mut self) -> &mut Box<dyn Step> {
         self.steps
             .get_mut(self.current)
@@ -94,7 +83,9 @@ impl Installer {
     pub fn new(
         destination_path: PathBuf,
         network: bitcoin::Network,
+        hardware_wallet: Box<dyn Fn() -> Pin<HardwareWallet> + Send + Sync>,
     ) -> (Installer, Command<Message>) {
+        let draft = draft::Draft::load(&destination_path);
         let mut config = DaemonConfig::new();
         config.data_dir = Some(destination_path);
         config.daemon = Some(true);
@@ -103,13 +94,103 @@ impl Installer {
                 should_exit: false,
                 config,
                 current: 0,
-                steps: vec![Welcome::new(network).into(), DefineRole::new().into()],
+                steps: vec![
+                    Welcome::new(network)
+                        .with_draft(draft.as_ref().map(|draft| draft.context.network))
+                        .into(),
+                    DefineRole::new().into(),
+                ],
                 context: Context::new(network),
+                role: None,
+                draft,
+                hardware_wallet,
             },
             Command::none(),
         )
     }
 
+    /// Restores the context, the configuration and the steps of the draft,
+    /// the steps already done are pre-filled with the draft values.
+    fn resume(&mut self, draft: draft::Draft) {
+        let role = draft.role();
+        let data_dir = self.config.data_dir.clone();
+        self.context = draft.context;
+        self.config = draft.config;
+        self.config.data_dir = data_dir;
+        self.context.previous = Some(edit::PreviousInstallation::from_draft(
+            self.config.clone(),
+            self.context.private_noise_key.clone(),
+        ));
+        self.context.setup_fingerprint = self.config.setup_fingerprint();
+        self.steps = steps(self.context.network, role);
+        self.role = Some(role);
+
+        self.current = draft.current.min(self.steps.len() - 1);
+        // The private noise key was not saved, the user starts again from the
+        // step defining it which follows the role step.
+        if self.context.private_noise_key.is_empty() {
+            self.current = self.current.min(2);
+        }
+        for step in self.steps.iter_mut().take(self.current + 1) {
+            step.load_context(&self.context);
+        }
+    }
+
+    /// The draft is saved in the datadir, except while editing an existing installation.
+    fn save_draft(&self) {
+        if let (Some(role), Some(data_dir)) = (self.role, &self.config.data_dir) {
+            if self
+                .context
+                .previous
+                .as_ref()
+                .map(|previous| previous.installed)
+                .unwrap_or(false)
+            {
+                return;
+            }
+            if let Err(e) =
+                draft::Draft::new(role, self.current, &self.context, &self.config).save(data_dir)
+            {
+                log::warn!("Failed to save the installer draft: {}", e);
+            }
+        }
+    }
+
+    /// Starts the installer pre-filled with an existing installation, the previous
+    /// configuration files are backed up before being replaced.
+    pub fn edit(
+        destination_path: PathBuf,
+        daemon_config: revaultd::config::Config,
+        hardware_wallet: Box<dyn Fn() -> Pin<HardwareWallet> + Send + Sync>,
+    ) -> Result<(Installer, Command<Message>), Error> {
+        let network = daemon_config.bitcoind_config.network;
+        let mut network_datadir = destination_path.clone();
+        network_datadir.push(network.to_string());
+        let private_noise_key = server::read_noise_secret(&network_datadir)
+            .map(|key| key.0.to_hex())
+            .unwrap_or_default();
+        let previous = edit::PreviousInstallation::new(&daemon_config, private_noise_key)
+            .map_err(Error::Unexpected)?;
+
+        let mut config = DaemonConfig::new();
+        config.bitcoind_config.network = network;
+        config.data_dir = Some(destination_path);
+        previous.keep_local_settings(&mut config);
+        Ok((
+            Installer {
+                should_exit: false,
+                config,
+                current: 0,
+                steps: vec![Welcome::new(network).into(), DefineRole::new().into()],
+                context: Context::edit(previous),
+                role: None,
+                draft: None,
+                hardware_wallet,
+            },
+            Command::none(),
+        ))
+    }
+
     pub fn subscription(&self) -> Subscription<Message> {
         iced_native::subscription::events().map(Message::Event)
     }
@@ -130,6 +211,15 @@ impl Installer {
                     .get_mut(self.current)
                     .expect("There is always a step");
                 if current_step.apply(&mut self.context, &mut self.config) {
+                    self.context.setup_fingerprint = self.config.setup_fingerprint();
+                    if let Some(previous) = &self.context.previous {
+                        previous.keep_local_settings(&mut self.config);
+                        if previous.installed {
+                            self.context.changes = Some(
+                                previous.changes(&self.config, &self.context.private_noise_key),
+                            );
+                        }
+                    }
                     self.next();
                     // skip the step according to the current context.
                     while self
@@ -146,10 +236,21 @@ impl Installer {
                         .get_mut(self.current)
                         .expect("There is always a step");
                     current_step.load_context(&self.context);
+                    self.save_draft();
                 }
             }
             Message::Previous => {
                 self.previous();
+                // skip the step according to the current context.
+                while self.current > 0
+                    && self
+                        .steps
+                        .get(self.current)
+                        .expect("There is always a step")
+                        .skip(&self.context)
+                {
+                    self.previous();
+                }
             }
             Message::Role(role) => {
                 // reset config
@@ -157,10 +258,32 @@ impl Installer {
                 config.bitcoind_config.network = self.context.network;
                 config.data_dir = self.config.data_dir.clone();
                 config.daemon = Some(true);
+                if let Some(previous) = &self.context.previous {
+                    previous.keep_local_settings(&mut config);
+                }
                 self.config = config;
 
-                self.update_steps(self.context.network, role);
+                self.steps = steps(self.context.network, role);
+                self.role = Some(role);
                 self.next();
+                self.steps
+                    .get_mut(self.current)
+                    .expect("There is always a step")
+                    .load_context(&self.context);
+            }
+            Message::ResumeDraft => {
+                if let Some(draft) = self.draft.take() {
+                    self.resume(draft);
+                }
+            }
+            Message::DiscardDraft => {
+                self.draft = None;
+                if let Some(data_dir) = &self.config.data_dir {
+                    if let Err(e) = draft::remove(data_dir) {
+                        log::warn!("{}", e);
+                    }
+                }
+                self.current_step().update(message);
             }
             Message::Install => {
                 self.current_step().update(message);
@@ -169,6 +292,85 @@ impl Installer {
                     Message::Installed,
                 );
             }
+            Message::ImportXpub(role) => {
+                self.current_step().update(message);
+                let network = self.context.network;
+                return Command::perform(
+                    import_xpub((self.hardware_wallet)(), network, role),
+                    move |res| Message::XpubImported(role, res),
+                );
+            }
+            Message::ImportBundle(ref path) => {
+                let path = path.clone();
+                self.current_step().update(message);
+                return Command::perform(import_bundle(path), Message::BundleImported);
+            }
+            Message::DefineBitcoind(message::DefineBitcoind::TestConnection) => {
+                // The step checks and writes the bitcoind settings in a copy of the
+                // configuration, the same way it does before going to the next step.
+                let mut context = self.context.clone();
+                let mut config = self.config.clone();
+                if self.current_step().apply(&mut context, &mut config) {
+                    self.current_step().update(message);
+                    let network = self.context.network;
+                    let bitcoind_config = config.bitcoind_config;
+                    return Command::perform(
+                        async move {
+                            bitcoind::check_connection(
+                                &bitcoind_config.cookie_path,
+                                bitcoind_config.addr,
+                                network,
+                            )
+                            .await
+                        },
+                        |res| {
+                            Message::DefineBitcoind(message::DefineBitcoind::ConnectionTested(res))
+                        },
+                    );
+                }
+            }
+            Message::DefineCoordinator(message::DefineCoordinator::TestConnection(host, key)) => {
+                self.current_step().update(message);
+                return Command::perform(
+                    check_server_connection(self.context.private_noise_key.clone(), host, key),
+                    |res| {
+                        Message::DefineCoordinator(message::DefineCoordinator::ConnectionTested(
+                            res,
+                        ))
+                    },
+                );
+            }
+            Message::DefineCosigners(i, message::DefineCosigner::TestConnection(host, key)) => {
+                self.current_step().update(message);
+                return Command::perform(
+                    check_server_connection(self.context.private_noise_key.clone(), host, key),
+                    move |res| {
+                        Message::DefineCosigners(i, message::DefineCosigner::ConnectionTested(res))
+                    },
+                );
+            }
+            Message::ExportNoisePublicKey(key) => {
+                self.current_step().update(message);
+                return Command::perform(
+                    export_noise_public_key(key, self.config.data_dir.clone().unwrap()),
+                    Message::NoisePublicKeyExported,
+                );
+            }
+            Message::ExportBundle => {
+                self.current_step().update(message);
+                match bundle::Federation::from_config(&self.config, self.context.managers_threshold)
+                {
+                    Ok(federation) => {
+                        return Command::perform(
+                            export_bundle(federation, self.config.data_dir.clone().unwrap()),
+                            Message::BundleExported,
+                        );
+                    }
+                    Err(e) => self
+                        .current_step()
+                        .update(Message::BundleExported(Err(Error::Bundle(e.to_string())))),
+                }
+            }
             Message::Event(Event::Window(window::Event::CloseRequested)) => {
                 self.stop();
                 return Command::none();
@@ -185,6 +387,57 @@ impl Installer {
     }
 }
 
+/// Steps of the installation of the given role.
+fn steps(network: bitcoin::Network, role: &[Role]) -> Vec<Box<dyn Step>> {
+    if role == Role::MANAGER_ONLY {
+        vec![
+            Welcome::new(network).into(),
+            DefineRole::new().into(),
+            DefinePrivateNoiseKey::new().into(),
+            ImportDescriptors::new().into(),
+            DefineOurXpubs::new(role).into(),
+            manager::DefineStakeholderXpubs::new().into(),
+            manager::DefineManagerXpubs::new().into(),
+            DefineCpfpDescriptor::new().into(),
+            DefineCoordinator::new().into(),
+            manager::DefineCosigners::new().into(),
+            DefineBitcoind::new().into(),
+            Final::new().into(),
+        ]
+    } else if role == Role::STAKEHOLDER_ONLY {
+        vec![
+            Welcome::new(network).into(),
+            DefineRole::new().into(),
+            DefinePrivateNoiseKey::new().into(),
+            ImportDescriptors::new().into(),
+            DefineOurXpubs::new(role).into(),
+            stakeholder::DefineStakeholderXpubs::new().into(),
+            stakeholder::DefineManagerXpubs::new().into(),
+            DefineCpfpDescriptor::new().into(),
+            DefineCoordinator::new().into(),
+            DefineBitcoind::new().into(),
+            stakeholder::DefineEmergencyAddress::new().into(),
+            Final::new().into(),
+        ]
+    } else {
+        vec![
+            Welcome::new(network).into(),
+            DefineRole::new().into(),
+            DefinePrivateNoiseKey::new().into(),
+            ImportDescriptors::new().into(),
+            DefineOurXpubs::new(role).into(),
+            stakeholder::DefineStakeholderXpubs::new().into(),
+            manager::DefineManagerXpubs::new().into(),
+            DefineCpfpDescriptor::new().into(),
+            DefineCoordinator::new().into(),
+            manager::DefineCosigners::new().into(),
+            DefineBitcoind::new().into(),
+            stakeholder::DefineEmergencyAddress::new().into(),
+            Final::new().into(),
+        ]
+    }
+}
+
 fn append_network_suffix(name: &str, network: &bitcoin::Network) -> String {
     if *network == bitcoin::Network::Bitcoin {
         name.to_string()
@@ -193,6 +446,134 @@ fn append_network_suffix(name: &str, network: &bitcoin::Network) -> String {
     }
 }
 
+/// Derivation path of the participant xpub on the hardware wallet, following BIP48
+/// with the p2wsh script type. Managers use the second account so that a participant
+/// with both roles does not reuse its stakeholder key.
+pub fn revault_derivation_path(network: bitcoin::Network, role: Role) -> DerivationPath {
+    let coin_type = if network == bitcoin::Network::Bitcoin {
+        0
+    } else {
+        1
+    };
+    let account = match role {
+        Role::Stakeholder => 0,
+        Role::Manager => 1,
+    };
+    DerivationPath::from(vec![
+        ChildNumber::from_hardened_idx(48).expect("Hardcoded index"),
+        ChildNumber::from_hardened_idx(coin_type).expect("Hardcoded index"),
+        ChildNumber::from_hardened_idx(account).expect("Hardcoded index"),
+        ChildNumber::from_hardened_idx(2).expect("Hardcoded index"),
+    ])
+}
+
+/// Xpub written with its key origin, the master fingerprint and the derivation path
+/// of the xpub, so that the key sources of the PSBTs refer to the device master key.
+pub fn xpub_with_origin(
+    fingerprint: &Fingerprint,
+    path: &DerivationPath,
+    xpub: &ExtendedPubKey,
+) -> String {
+    let path: String = path
+        .into_iter()
+        .map(|child| format!("/{}", child))
+        .collect();
+    format!("[{}{}]{}", fingerprint, path, xpub)
+}
+
+pub async fn import_xpub(
+    hardware_wallet: Pin<HardwareWallet>,
+    network: bitcoin::Network,
+    role: Role,
+) -> Result<DescriptorPublicKey, Error> {
+    let mut device = hardware_wallet
+        .await
+        .map_err(|e| Error::HardwareWallet(e.to_string()))?;
+    let fingerprint = device
+        .get_master_fingerprint()
+        .await
+        .map_err(|e| Error::HardwareWallet(e.to_string()))?;
+    let path = revault_derivation_path(network, role);
+    let xpub = device
+        .get_extended_pubkey(&path)
+        .await
+        .map_err(|e| Error::HardwareWallet(e.to_string()))?;
+
+    // Testnet, signet and regtest xpubs share the same version bytes.
+    let expected = if network == bitcoin::Network::Bitcoin {
+        bitcoin::Network::Bitcoin
+    } else {
+        bitcoin::Network::Testnet
+    };
+    if xpub.network != expected {
+        return Err(Error::HardwareWallet(format!(
+            "the device xpub is not for the {} network",
+            network
+        )));
+    }
+
+    DescriptorPublicKey::from_str(&xpub_with_origin(&fingerprint, &path, &xpub))
+        .map_err(|e| Error::HardwareWallet(e.to_string()))
+}
+
+pub async fn import_bundle(path: PathBuf) -> Result<bundle::Federation, Error> {
+    read_bundle(&path)
+}
+
+fn read_bundle(path: &Path) -> Result<bundle::Federation, Error> {
+    let content = std::fs::read_to_string(path).map_err(|e| Error::Bundle(e.to_string()))?;
+    bundle::Federation::from_bundle(&content).map_err(|e| Error::Bundle(e.to_string()))
+}
+
+pub async fn export_bundle(
+    federation: bundle::Federation,
+    datadir_path: PathBuf,
+) -> Result<PathBuf, Error> {
+    std::fs::create_dir_all(&datadir_path)
+        .map_err(|e| Error::CannotCreateDatadir(e.to_string()))?;
+
+    let mut bundle_path = datadir_path;
+    bundle_path.push(bundle::DEFAULT_FILE_NAME);
+    let mut bundle_file =
+        std::fs::File::create(&bundle_path).map_err(|e| Error::CannotCreateFile(e.to_string()))?;
+    bundle_file
+        .write_all(federation.to_bundle().as_bytes())
+        .map_err(|e| Error::CannotWriteToFile(e.to_string()))?;
+
+    Ok(bundle_path)
+}
+
+/// Tries a noise connection to the server with the private noise key of the context.
+pub async fn check_server_connection(
+    private_noise_key: String,
+    host: SocketAddr,
+    noise_key: NoisePubkey,
+) -> Result<(), server::Error> {
+    let our_key = Vec::from_hex(&private_noise_key)
+        .ok()
+        .and_then(|bytes| NoisePrivkey::from_slice(&bytes))
+        .ok_or_else(|| server::Error::NoiseSecret("invalid private noise key".to_string()))?;
+    server::check_connection(host, our_key, noise_key).await
+}
+
+pub async fn export_noise_public_key(
+    key: NoisePubkey,
+    datadir_path: PathBuf,
+) -> Result<PathBuf, Error> {
+    std::fs::create_dir_all(&datadir_path)
+        .map_err(|e| Error::CannotCreateDatadir(e.to_string()))?;
+
+    let mut key_path = datadir_path;
+    key_path.push("noise_public_key");
+    let mut key_file =
+        std::fs::File::create(&key_path).map_err(|e| Error::CannotCreateFile(e.to_string()))?;
+    key_file
+        .write_all(key.as_ref().to_hex().as_bytes())
+        .map_err(|e| Error::CannotWriteToFile(e.to_string()))?;
+
+    Ok(key_path)
+}
+
 pub async fn install(ctx: Context, mut cfg: DaemonConfig) -> Result<PathBuf, Error> {
     let datadir_path = cfg.data_dir.clone().unwrap();
     std::fs::create_dir_all(&datadir_path)
@@ -209,6 +590,7 @@ pub async fn install(ctx: Context, mut cfg: DaemonConfig) -> Result<PathBuf, Err
         DaemonConfig::DEFAULT_FILE_NAME,
         &cfg.bitcoind_config.network,
     ));
+    edit::backup(&revaultd_config_path).map_err(|e| Error::Backup(e.to_string()))?;
     let mut revaultd_config_file = std::fs::File::create(&revaultd_config_path)
         .map_err(|e| Error::CannotCreateFile(e.to_string()))?;
 
@@ -229,6 +611,7 @@ pub async fn install(ctx: Context, mut cfg: DaemonConfig) -> Result<PathBuf, Err
     // create noise_secret file
     let mut noise_secret_path = network_datadir;
     noise_secret_path.push("noise_secret");
+    edit::backup(&noise_secret_path).map_err(|e| Error::Backup(e.to_string()))?;
     let mut noise_secret_file = std::fs::File::create(&noise_secret_path)
         .map_err(|e| Error::CannotCreateFile(e.to_string()))?;
 
@@ -245,24 +628,34 @@ pub async fn install(ctx: Context, mut cfg: DaemonConfig) -> Result<PathBuf, Err
         &cfg.bitcoind_config.network,
     ));
 
+    let revaultd_config_path = revaultd_config_path.canonicalize().map_err(|e| {
+        Error::Unexpected(format!(
+            "Failed to canonicalize revaultd config path: {}",
+            e
+        ))
+    })?;
+    // The settings of an existing GUI configuration are kept.
+    let gui_config = match gui_config::Config::from_file(&gui_config_path) {
+        Ok(mut gui_config) => {
+            gui_config.revaultd_config_path = revaultd_config_path;
+            gui_config
+        }
+        Err(_) => gui_config::Config::new(revaultd_config_path),
+    };
+    edit::backup(&gui_config_path).map_err(|e| Error::Backup(e.to_string()))?;
+
     let mut gui_config_file = std::fs::File::create(&gui_config_path)
         .map_err(|e| Error::CannotCreateFile(e.to_string()))?;
 
     gui_config_file
-        .write_all(
-            toml::to_string(&gui_config::Config::new(
-                revaultd_config_path.canonicalize().map_err(|e| {
-                    Error::Unexpected(format!(
-                        "Failed to canonicalize revaultd config path: {}",
-                        e
-                    ))
-                })?,
-            ))
-            .unwrap()
-            .as_bytes(),
-        )
+        .write_all(toml::to_string(&gui_config).unwrap().as_bytes())
         .map_err(|e| Error::CannotWriteToFile(e.to_string()))?;
 
+    // The installation is done, the draft is not needed anymore.
+    if let Err(e) = draft::remove(cfg.data_dir.as_ref().expect("datadir was set")) {
+        log::warn!("{}", e);
+    }
+
     Ok(gui_config_path)
 }
 
@@ -271,6 +664,13 @@ pub enum Error {
     CannotCreateDatadir(String),
     CannotCreateFile(String),
     CannotWriteToFile(String),
+    HardwareWallet(String),
+    Bundle(String),
+    Backup(String),
+    /// Errors of the regtest federation generator.
+    Generate(String),
+    /// Errors of the non-interactive installation.
+    Answers(Vec<String>),
     Unexpected(String),
 }
 
@@ -280,6 +680,17 @@ impl std::fmt::Display for Error {
             Self::CannotCreateDatadir(e) => write!(f, "Failed to create datadir: {}", e),
             Self::CannotWriteToFile(e) => write!(f, "Failed to write to file: {}", e),
             Self::CannotCreateFile(e) => write!(f, "Failed to create file: {}", e),
+            Self::HardwareWallet(e) => write!(f, "Hardware wallet error: {}", e),
+            Self::Bundle(e) => write!(f, "Federation bundle error: {}", e),
+            Self::Backup(e) => write!(f, "Failed to back up the previous file: {}", e),
+            Self::Generate(e) => write!(f, "Failed to generate the federation: {}", e),
+            Self::Answers(errors) => {
+                write!(f, "Invalid answers:")?;
+                for e in errors {
+                    write!(f, "\n- {}", e)?;
+                }
+                Ok(())
+            }
             Self::Unexpected(e) => write!(f, "Unexpected: {}", e),
         }
     }
@@ -2,10 +2,27 @@ use revault_ui::component::form;
 
 use crate::installer::{message, view};
 
+use bitcoin::{hashes::hex::FromHex, util::bip32::ExtendedPubKey};
 use iced::{button::State as Button, text_input, Element};
 
-use revaultd::revault_tx::miniscript::DescriptorPublicKey;
-use std::str::FromStr;
+use revaultd::{revault_net::noise::PublicKey, revault_tx::miniscript::DescriptorPublicKey};
+use std::{net::SocketAddr, str::FromStr};
+
+/// Adds an error to the list if the value was refused by the step.
+pub fn check_value<T>(errors: &mut Vec<String>, value: &form::Value<T>, name: &str) {
+    if !value.valid {
+        errors.push(format!("{} is not valid", name));
+    }
+}
+
+/// Returns the address and the noise key of a server if both are correct.
+pub fn parse_server(host: &str, noise_key: &str) -> Option<(SocketAddr, PublicKey)> {
+    let host = SocketAddr::from_str(host).ok()?;
+    let noise_key = Vec::from_hex(noise_key)
+        .ok()
+        .and_then(|bytes| PublicKey::from_slice(&bytes))?;
+    Some((host, noise_key))
+}
 
 #[derive(Clone)]
 pub struct ParticipantXpub {
@@ -49,6 +66,18 @@ impl ParticipantXpub {
     }
 }
 
+/// Returns the participants of an edited installation.
+pub fn participants(xpubs: &[String]) -> Vec<ParticipantXpub> {
+    xpubs
+        .iter()
+        .map(|xpub| {
+            let mut participant = ParticipantXpub::new();
+            participant.update(message::ParticipantXpub::XpubEdited(xpub.clone()));
+            participant
+        })
+        .collect()
+}
+
 #[derive(Clone)]
 pub struct RequiredXpub {
     pub xpub: form::Value<String>,
@@ -69,6 +98,20 @@ impl RequiredXpub {
         self.xpub.valid = true;
     }
 
+    /// Fill our xpub with the one given by the hardware wallet, with its key origin.
+    pub fn import(&mut self, xpub: &DescriptorPublicKey) {
+        self.xpub.value = xpub.to_string();
+        self.xpub.valid = true;
+    }
+
+    /// Our xpub without its key origin, None if the value is not a valid xpub.
+    pub fn xkey(&self) -> Option<ExtendedPubKey> {
+        match DescriptorPublicKey::from_str(&self.xpub.value) {
+            Ok(DescriptorPublicKey::XPub(xpub)) => Some(xpub.xkey),
+            _ => None,
+        }
+    }
+
     pub fn check_validity(&mut self, network: &bitcoin::Network) {
         if let Ok(DescriptorPublicKey::XPub(xpub)) = DescriptorPublicKey::from_str(&self.xpub.value)
         {
@@ -2,8 +2,7 @@ use std::cmp::Ordering;
 use std::net::SocketAddr;
 use std::str::FromStr;
 
-use bitcoin::hashes::hex::FromHex;
-use bitcoin::util::bip32::ExtendedPubKey;
+use bitcoin::hashes::hex::{FromHex, ToHex};
 use iced::{button::State as Button, scrollable, Element};
 use revaultd::{
     config::{CosignerConfig, ManagerConfig},
@@ -16,14 +15,20 @@ use revaultd::{
 
 use revault_ui::component::form;
 
-use crate::installer::{
-    config,
-    message::{self, Message},
-    step::{
-        common::{CosignerKey, ParticipantXpub, RequiredXpub},
-        Context, Step,
+use crate::{
+    installer::{
+        answers::Answers,
+        config,
+        message::{self, Message},
+        step::{
+            common::{
+                check_value, parse_server, participants, CosignerKey, ParticipantXpub, RequiredXpub,
+            },
+            Context, Step,
+        },
+        view, Error,
     },
-    view,
+    server,
 };
 
 pub struct DefineStakeholderXpubs {
@@ -55,6 +60,23 @@ impl Default for DefineStakeholderXpubs {
 }
 
 impl Step for DefineStakeholderXpubs {
+    fn answers(&self, answers: &Answers) -> Result<Vec<Message>, Error> {
+        Ok(answers.stakeholder_xpubs_messages())
+    }
+
+    /// skip this step if the descriptors were imported
+    fn skip(&self, ctx: &Context) -> bool {
+        ctx.federation.is_some() || ctx.descriptors.is_some()
+    }
+
+    fn load_context(&mut self, ctx: &Context) {
+        if let Some(previous) = &ctx.previous {
+            if self.stakeholder_xpubs.is_empty() {
+                self.stakeholder_xpubs = participants(&previous.stakeholders_xpubs);
+            }
+        }
+    }
+
     fn update(&mut self, message: Message) {
         if let Message::DefineStakeholderXpubs(msg) = message {
             match msg {
@@ -120,6 +142,19 @@ impl Step for DefineStakeholderXpubs {
         true
     }
 
+    fn errors(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        for (i, xpub) in self.stakeholder_xpubs.iter().enumerate() {
+            check_value(
+                &mut errors,
+                &xpub.xpub,
+                &format!("stakeholder xpub #{}", i + 1),
+            );
+        }
+        errors.extend(self.warning.clone());
+        errors
+    }
+
     fn view(&mut self) -> Element<Message> {
         return view::define_stakeholder_xpubs_as_manager_only(
             &mut self.add_xpub_button,
@@ -153,6 +188,8 @@ pub struct DefineManagerXpubs {
     cosigners: Vec<CosignerKey>,
     other_xpubs: Vec<ParticipantXpub>,
     our_xpub: RequiredXpub,
+    /// true while our xpub is requested to the hardware wallet.
+    importing: bool,
     managers_threshold: form::Value<usize>,
     spending_delay: form::Value<u32>,
     warning: Option<String>,
@@ -176,6 +213,7 @@ impl DefineManagerXpubs {
                 valid: true,
             },
             our_xpub: RequiredXpub::new(),
+            importing: false,
             other_xpubs: Vec::new(),
             cosigners: Vec::new(),
             view: view::DefineManagerXpubsAsManager::new(),
@@ -192,6 +230,15 @@ impl Default for DefineManagerXpubs {
 }
 
 impl Step for DefineManagerXpubs {
+    fn answers(&self, answers: &Answers) -> Result<Vec<Message>, Error> {
+        Ok(answers.manager_xpubs_messages())
+    }
+
+    /// skip this step if the descriptors were imported
+    fn skip(&self, ctx: &Context) -> bool {
+        ctx.federation.is_some() || ctx.descriptors.is_some()
+    }
+
     fn load_context(&mut self, ctx: &Context) {
         self.stakeholder_xpubs = ctx.stakeholders_xpubs.clone();
         self.cosigners_enabled = ctx.cosigners_enabled;
@@ -204,11 +251,41 @@ impl Step for DefineManagerXpubs {
                 Ordering::Equal => (),
             }
         }
+        if let Some(previous) = &ctx.previous {
+            if self.our_xpub.xpub.value.is_empty() && self.other_xpubs.is_empty() {
+                if let Some(xpub) = previous.manager_xpub() {
+                    self.our_xpub.update(xpub);
+                }
+                self.other_xpubs = participants(&previous.other_managers_xpubs());
+                self.managers_threshold.value = previous.managers_threshold;
+                self.spending_delay.value = previous.spending_delay;
+            }
+            for (key, previous_key) in self
+                .cosigners
+                .iter_mut()
+                .zip(previous.cosigners_keys.iter())
+            {
+                if key.key.value.is_empty() {
+                    key.update(previous_key.clone());
+                }
+            }
+        }
     }
 
     fn update(&mut self, message: Message) {
-        if let Message::DefineManagerXpubs(msg) = message {
-            match msg {
+        match message {
+            Message::ImportXpub(_) => {
+                self.importing = true;
+                self.warning = None;
+            }
+            Message::XpubImported(_, res) => {
+                self.importing = false;
+                match res {
+                    Ok(xpub) => self.our_xpub.import(&xpub),
+                    Err(e) => self.warning = Some(e.to_string()),
+                }
+            }
+            Message::DefineManagerXpubs(msg) => match msg {
                 message::DefineManagerXpubs::OurXpubEdited(xpub) => {
                     self.our_xpub.update(xpub);
                 }
@@ -255,7 +332,8 @@ impl Step for DefineManagerXpubs {
                         }
                     }
                 },
-            };
+            },
+            _ => {}
         };
     }
 
@@ -330,10 +408,12 @@ impl Step for DefineManagerXpubs {
         };
 
         ctx.number_cosigners = self.cosigners.len();
+        ctx.cosigners_enabled = self.cosigners_enabled;
         ctx.number_managers = managers_keys.len();
+        ctx.managers_threshold = self.managers_threshold.value;
 
         config.manager_config = Some(ManagerConfig {
-            xpub: ExtendedPubKey::from_str(&self.our_xpub.xpub.value).expect("already checked"),
+            xpub: self.our_xpub.xkey().expect("already checked"),
             cosigners: Vec::new(),
         });
 
@@ -354,6 +434,27 @@ impl Step for DefineManagerXpubs {
         self.warning.is_none()
     }
 
+    fn errors(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        check_value(&mut errors, &self.our_xpub.xpub, "our manager xpub");
+        for (i, xpub) in self.other_xpubs.iter().enumerate() {
+            check_value(&mut errors, &xpub.xpub, &format!("manager xpub #{}", i + 1));
+        }
+        if self.cosigners_enabled {
+            for (i, cosigner) in self.cosigners.iter().enumerate() {
+                check_value(
+                    &mut errors,
+                    &cosigner.key,
+                    &format!("cosigner key #{}", i + 1),
+                );
+            }
+        }
+        check_value(&mut errors, &self.managers_threshold, "managers threshold");
+        check_value(&mut errors, &self.spending_delay, "spending delay");
+        errors.extend(self.warning.clone());
+        errors
+    }
+
     fn view(&mut self) -> Element<Message> {
         let cosigners = if self.cosigners_enabled {
             self.cosigners
@@ -374,6 +475,7 @@ impl Step for DefineManagerXpubs {
             &self.managers_threshold,
             &self.spending_delay,
             &self.our_xpub.xpub,
+            self.importing,
             self.other_xpubs
                 .iter_mut()
                 .enumerate()
@@ -401,6 +503,8 @@ impl From<DefineManagerXpubs> for Box<dyn Step> {
 pub struct Cosigner {
     pub host: form::Value<String>,
     pub noise_key: form::Value<String>,
+    testing: bool,
+    connection: Option<Result<(), server::Error>>,
 
     view: view::Cosigner,
 }
@@ -410,6 +514,8 @@ impl Cosigner {
         Self {
             host: form::Value::default(),
             noise_key: form::Value::default(),
+            testing: false,
+            connection: None,
             view: view::Cosigner::new(),
         }
     }
@@ -419,16 +525,32 @@ impl Cosigner {
             message::DefineCosigner::HostEdited(host) => {
                 self.host.value = host;
                 self.host.valid = true;
+                self.connection = None;
             }
             message::DefineCosigner::NoiseKeyEdited(key) => {
                 self.noise_key.value = key;
                 self.noise_key.valid = true;
+                self.connection = None;
+            }
+            message::DefineCosigner::TestConnection(..) => {
+                self.testing = true;
+                self.connection = None;
+            }
+            message::DefineCosigner::ConnectionTested(res) => {
+                self.testing = false;
+                self.connection = Some(res);
             }
         }
     }
 
     pub fn view(&mut self) -> Element<message::DefineCosigner> {
-        self.view.render(&self.host, &self.noise_key)
+        self.view.render(
+            &self.host,
+            &self.noise_key,
+            parse_server(&self.host.value, &self.noise_key.value),
+            self.testing,
+            self.connection.as_ref(),
+        )
     }
 }
 
@@ -453,6 +575,10 @@ impl DefineCosigners {
 }
 
 impl Step for DefineCosigners {
+    fn answers(&self, answers: &Answers) -> Result<Vec<Message>, Error> {
+        Ok(answers.cosigners_messages())
+    }
+
     fn load_context(&mut self, ctx: &Context) {
         while self.cosigners.len() != ctx.number_cosigners {
             match self.cosigners.len().cmp(&ctx.number_cosigners) {
@@ -463,6 +589,22 @@ impl Step for DefineCosigners {
                 Ordering::Equal => (),
             }
         }
+        if let Some(manager_config) = ctx
+            .previous
+            .as_ref()
+            .and_then(|previous| previous.config.manager_config.as_ref())
+        {
+            for (cosigner, previous_cosigner) in self
+                .cosigners
+                .iter_mut()
+                .zip(manager_config.cosigners.iter())
+            {
+                if cosigner.host.value.is_empty() && cosigner.noise_key.value.is_empty() {
+                    cosigner.host.value = previous_cosigner.host.to_string();
+                    cosigner.noise_key.value = previous_cosigner.noise_key.0.to_hex();
+                }
+            }
+        }
     }
 
     fn update(&mut self, message: Message) {
@@ -473,9 +615,9 @@ impl Step for DefineCosigners {
         };
     }
 
-    /// skip this step if cosigners are disabled
+    /// skip this step if cosigners are disabled or imported from a bundle
     fn skip(&self, ctx: &Context) -> bool {
-        !ctx.cosigners_enabled
+        !ctx.cosigners_enabled || ctx.federation.is_some()
     }
 
     fn apply(&mut self, _ctx: &mut Context, config: &mut config::Config) -> bool {
@@ -516,6 +658,23 @@ impl Step for DefineCosigners {
         true
     }
 
+    fn errors(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        for (i, cosigner) in self.cosigners.iter().enumerate() {
+            check_value(
+                &mut errors,
+                &cosigner.host,
+                &format!("cosigner host #{}", i + 1),
+            );
+            check_value(
+                &mut errors,
+                &cosigner.noise_key,
+                &format!("cosigner noise key #{}", i + 1),
+            );
+        }
+        errors
+    }
+
     fn view(&mut self) -> Element<Message> {
         self.view.render(
             self.cosigners
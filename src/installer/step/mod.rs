@@ -7,17 +7,39 @@ use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::str::FromStr;
 
-use bitcoin::hashes::hex::FromHex;
+use bitcoin::hashes::hex::{FromHex, ToHex};
 use iced::Element;
-use revaultd::revault_tx::{miniscript::DescriptorPublicKey, scripts::CpfpDescriptor};
+use revaultd::{
+    config::{CosignerConfig, ManagerConfig},
+    revault_net::{
+        noise::PublicKey,
+        sodiumoxide::{
+            self,
+            crypto::{box_, scalarmult::curve25519},
+        },
+    },
+    revault_tx::{
+        miniscript::DescriptorPublicKey,
+        scripts::{CpfpDescriptor, DepositDescriptor, UnvaultDescriptor},
+    },
+};
 
 use revault_ui::component::form;
-
-use crate::installer::{
-    config,
-    message::{self, Message},
-    step::common::RequiredXpub,
-    view,
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bitcoind::{self, BlockchainInfo},
+    installer::{
+        answers::Answers,
+        bundle::Federation,
+        config,
+        edit::{Change, PreviousInstallation},
+        message::{self, Message},
+        step::common::{check_value, parse_server, RequiredXpub},
+        view, Error,
+    },
+    revault::{Role, SetupFingerprint, UnvaultParameters},
+    server,
 };
 
 pub trait Step {
@@ -30,16 +52,42 @@ pub trait Step {
     fn apply(&mut self, _ctx: &mut Context, _config: &mut config::Config) -> bool {
         true
     }
+    /// Reasons of the last refused apply, reported by the non-interactive installation.
+    fn errors(&self) -> Vec<String> {
+        Vec::new()
+    }
+    /// Messages answering the inputs of the step in a non-interactive installation.
+    fn answers(&self, _answers: &Answers) -> Result<Vec<Message>, Error> {
+        Ok(Vec::new())
+    }
 }
 
-#[derive(Clone)]
+/// Context is saved in the installation draft, except the fields
+/// that are set again when the draft is resumed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Context {
     pub network: bitcoin::Network,
     pub private_noise_key: String,
+    /// The private noise key is only written in the draft if the user opted in.
+    pub save_private_noise_key: bool,
     pub number_managers: usize,
     pub number_cosigners: usize,
     pub cosigners_enabled: bool,
     pub stakeholders_xpubs: Vec<String>,
+    pub managers_threshold: usize,
+    /// Some if the shared part of the installation was imported from a bundle.
+    pub federation: Option<Federation>,
+    /// Some if the descriptors were imported as a whole instead of being built from the xpubs.
+    pub descriptors: Option<config::ScriptsConfig>,
+    /// Some once the descriptors and the coordinator are defined.
+    #[serde(skip)]
+    pub setup_fingerprint: Option<SetupFingerprint>,
+    /// Some if the installer edits an existing installation or resumes a draft.
+    #[serde(skip)]
+    pub previous: Option<PreviousInstallation>,
+    /// Changes of the edited installation, updated after each step.
+    #[serde(skip)]
+    pub changes: Option<Vec<Change>>,
 }
 
 impl Context {
@@ -47,12 +95,30 @@ impl Context {
         Self {
             network,
             private_noise_key: "".to_string(),
+            save_private_noise_key: false,
             number_managers: 0,
             number_cosigners: 0,
             stakeholders_xpubs: Vec::new(),
             cosigners_enabled: false,
+            managers_threshold: 0,
+            federation: None,
+            descriptors: None,
+            setup_fingerprint: None,
+            previous: None,
+            changes: None,
         }
     }
+
+    /// Context of the installer editing the given installation.
+    pub fn edit(previous: PreviousInstallation) -> Self {
+        let mut ctx = Self::new(previous.config.bitcoind_config.network);
+        ctx.private_noise_key = previous.private_noise_key.clone();
+        ctx.cosigners_enabled = !previous.cosigners_keys.is_empty();
+        ctx.number_cosigners = previous.cosigners_keys.len();
+        ctx.managers_threshold = previous.managers_threshold;
+        ctx.previous = Some(previous);
+        ctx
+    }
 }
 
 impl Default for Context {
@@ -63,6 +129,12 @@ impl Default for Context {
 
 pub struct Welcome {
     network: bitcoin::Network,
+    bundle_path: form::Value<String>,
+    importing: bool,
+    federation: Option<Federation>,
+    /// Network of the unfinished installation found in the datadir.
+    draft: Option<bitcoin::Network>,
+    warning: Option<String>,
     view: view::Welcome,
 }
 
@@ -70,24 +142,80 @@ impl Welcome {
     pub fn new(network: bitcoin::Network) -> Self {
         Self {
             network,
+            bundle_path: form::Value::default(),
+            importing: false,
+            federation: None,
+            draft: None,
+            warning: None,
             view: view::Welcome::new(),
         }
     }
+
+    pub fn with_draft(mut self, draft: Option<bitcoin::Network>) -> Self {
+        self.draft = draft;
+        self
+    }
 }
 
 impl Step for Welcome {
+    fn answers(&self, answers: &Answers) -> Result<Vec<Message>, Error> {
+        answers.welcome_messages()
+    }
+
+    /// Restores the choices of a resumed draft.
+    fn load_context(&mut self, ctx: &Context) {
+        self.network = ctx.network;
+        self.federation = ctx.federation.clone();
+    }
+
     fn update(&mut self, message: Message) {
-        if let message::Message::Network(network) = message {
-            self.network = network;
+        match message {
+            message::Message::DiscardDraft => self.draft = None,
+            message::Message::Network(network) => {
+                self.network = network;
+                // The imported federation is for another network.
+                self.federation = None;
+            }
+            message::Message::BundlePathEdited(path) => {
+                self.bundle_path.value = path;
+                self.bundle_path.valid = true;
+            }
+            message::Message::ImportBundle(_) => {
+                self.importing = true;
+                self.warning = None;
+            }
+            message::Message::BundleImported(res) => {
+                self.importing = false;
+                match res {
+                    Ok(federation) => {
+                        self.network = federation.network;
+                        self.federation = Some(federation);
+                    }
+                    Err(e) => {
+                        self.bundle_path.valid = false;
+                        self.federation = None;
+                        self.warning = Some(e.to_string());
+                    }
+                }
+            }
+            _ => {}
         }
     }
     fn apply(&mut self, ctx: &mut Context, config: &mut config::Config) -> bool {
         ctx.network = self.network;
+        ctx.federation = self.federation.clone();
         config.bitcoind_config.network = self.network;
         true
     }
     fn view(&mut self) -> Element<Message> {
-        self.view.render(&self.network)
+        self.view.render(
+            &self.network,
+            &self.bundle_path,
+            self.importing,
+            self.federation.as_ref(),
+            self.draft.as_ref(),
+            self.warning.as_ref(),
+        )
     }
 }
 
@@ -132,48 +260,131 @@ impl From<DefineRole> for Box<dyn Step> {
 
 pub struct DefinePrivateNoiseKey {
     key: form::Value<String>,
+    manual_entry: bool,
+    save_in_draft: bool,
+    exporting: bool,
+    public_key_path: Option<PathBuf>,
+    warning: Option<String>,
     view: view::DefinePrivateNoiseKey,
 }
 
 impl DefinePrivateNoiseKey {
     pub fn new() -> Self {
-        Self {
+        let mut step = Self {
             key: form::Value::default(),
+            manual_entry: false,
+            save_in_draft: false,
+            exporting: false,
+            public_key_path: None,
+            warning: None,
             view: view::DefinePrivateNoiseKey::new(),
+        };
+        step.generate();
+        step
+    }
+
+    fn generate(&mut self) {
+        self.public_key_path = None;
+        match generate_private_noise_key() {
+            Ok(key) => {
+                self.key.value = key;
+                self.key.valid = true;
+                self.warning = None;
+            }
+            Err(e) => {
+                self.key.valid = false;
+                self.warning = Some(e.to_string());
+            }
         }
     }
 }
 
+/// Returns a new hex encoded noise private key.
+pub fn generate_private_noise_key() -> Result<String, Error> {
+    sodiumoxide::init()
+        .map_err(|_| Error::Unexpected("Failed to initialize libsodium".to_string()))?;
+    let (_, secret_key) = box_::gen_keypair();
+    Ok(secret_key.0.to_hex())
+}
+
+/// Returns the noise public key of the hex encoded private key,
+/// None if the private key is not valid.
+pub fn noise_public_key(private_key: &str) -> Option<PublicKey> {
+    let bytes = Vec::from_hex(private_key).ok()?;
+    let scalar = curve25519::Scalar::from_slice(&bytes)?;
+    Some(PublicKey(curve25519::scalarmult_base(&scalar).0))
+}
+
 impl Step for DefinePrivateNoiseKey {
-    fn update(&mut self, message: Message) {
-        if let Message::PrivateNoiseKey(msg) = message {
-            self.key.value = msg;
+    fn answers(&self, answers: &Answers) -> Result<Vec<Message>, Error> {
+        Ok(answers.private_noise_key_messages())
+    }
+
+    /// Keeps the key of the edited installation or the one defined before
+    /// the role was chosen again.
+    fn load_context(&mut self, ctx: &Context) {
+        self.save_in_draft = ctx.save_private_noise_key;
+        if !ctx.private_noise_key.is_empty() && ctx.private_noise_key != self.key.value {
+            self.key.value = ctx.private_noise_key.clone();
+            self.key.valid = noise_public_key(&self.key.value).is_some();
+            self.public_key_path = None;
+        }
+    }
 
-            self.key.valid = true;
-            if let Ok(bytes) = Vec::from_hex(&self.key.value) {
-                if bytes.len() != 32 {
-                    self.key.valid = false;
+    fn update(&mut self, message: Message) {
+        match message {
+            Message::DefinePrivateNoiseKey(msg) => match msg {
+                message::DefinePrivateNoiseKey::Generate => self.generate(),
+                message::DefinePrivateNoiseKey::ManualEntry(manual_entry) => {
+                    self.manual_entry = manual_entry;
+                    if !manual_entry && !self.key.valid {
+                        self.generate();
+                    }
                 }
-            } else {
-                self.key.valid = false;
+                message::DefinePrivateNoiseKey::KeyEdited(key) => {
+                    self.key.value = key;
+                    self.key.valid = noise_public_key(&self.key.value).is_some();
+                    self.public_key_path = None;
+                }
+                message::DefinePrivateNoiseKey::SaveInDraft(save) => self.save_in_draft = save,
+            },
+            Message::ExportNoisePublicKey(_) => {
+                self.exporting = true;
+                self.public_key_path = None;
+                self.warning = None;
             }
+            Message::NoisePublicKeyExported(res) => {
+                self.exporting = false;
+                match res {
+                    Err(e) => self.warning = Some(e.to_string()),
+                    Ok(path) => self.public_key_path = Some(path),
+                }
+            }
+            _ => {}
         }
     }
     fn apply(&mut self, ctx: &mut Context, _config: &mut config::Config) -> bool {
-        self.key.valid = true;
-        if let Ok(bytes) = Vec::from_hex(&self.key.value) {
-            if bytes.len() != 32 {
-                self.key.valid = false;
-            }
-        } else {
-            self.key.valid = false;
-        }
-
+        self.key.valid = noise_public_key(&self.key.value).is_some();
         ctx.private_noise_key = self.key.value.clone();
+        ctx.save_private_noise_key = self.save_in_draft;
         self.key.valid
     }
+    fn errors(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        check_value(&mut errors, &self.key, "private noise key");
+        errors
+    }
     fn view(&mut self) -> Element<Message> {
-        self.view.render(&self.key)
+        let public_key = noise_public_key(&self.key.value);
+        self.view.render(
+            &self.key,
+            public_key.as_ref(),
+            self.manual_entry,
+            self.save_in_draft,
+            self.exporting,
+            self.public_key_path.as_ref(),
+            self.warning.as_ref(),
+        )
     }
 }
 
@@ -189,6 +400,382 @@ impl From<DefinePrivateNoiseKey> for Box<dyn Step> {
     }
 }
 
+/// ImportDescriptors lets the participants who already have the descriptors of the
+/// federation give them directly instead of the xpubs they are built from.
+pub struct ImportDescriptors {
+    enabled: bool,
+    deposit_descriptor: form::Value<String>,
+    unvault_descriptor: form::Value<String>,
+    cpfp_descriptor: form::Value<String>,
+    warning: Option<String>,
+    view: view::ImportDescriptors,
+}
+
+impl ImportDescriptors {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            deposit_descriptor: form::Value::default(),
+            unvault_descriptor: form::Value::default(),
+            cpfp_descriptor: form::Value::default(),
+            warning: None,
+            view: view::ImportDescriptors::new(),
+        }
+    }
+}
+
+impl Step for ImportDescriptors {
+    fn answers(&self, answers: &Answers) -> Result<Vec<Message>, Error> {
+        Ok(answers.import_descriptors_messages())
+    }
+
+    /// skip this step if the descriptors were imported from a bundle
+    fn skip(&self, ctx: &Context) -> bool {
+        ctx.federation.is_some()
+    }
+
+    /// Restores the descriptors of a resumed draft.
+    fn load_context(&mut self, ctx: &Context) {
+        if let Some(descriptors) = &ctx.descriptors {
+            if self.deposit_descriptor.value.is_empty() {
+                self.enabled = true;
+                self.deposit_descriptor.value = descriptors.deposit_descriptor.clone();
+                self.unvault_descriptor.value = descriptors.unvault_descriptor.clone();
+                self.cpfp_descriptor.value = descriptors.cpfp_descriptor.clone();
+            }
+        }
+    }
+
+    fn update(&mut self, message: Message) {
+        if let Message::ImportDescriptors(msg) = message {
+            match msg {
+                message::ImportDescriptors::Enabled(enabled) => self.enabled = enabled,
+                message::ImportDescriptors::DepositDescriptorEdited(descriptor) => {
+                    self.deposit_descriptor.value = descriptor;
+                    self.deposit_descriptor.valid = true;
+                }
+                message::ImportDescriptors::UnvaultDescriptorEdited(descriptor) => {
+                    self.unvault_descriptor.value = descriptor;
+                    self.unvault_descriptor.valid = true;
+                }
+                message::ImportDescriptors::CpfpDescriptorEdited(descriptor) => {
+                    self.cpfp_descriptor.value = descriptor;
+                    self.cpfp_descriptor.valid = true;
+                }
+            }
+        }
+    }
+
+    fn apply(&mut self, ctx: &mut Context, _config: &mut config::Config) -> bool {
+        self.warning = None;
+        if !self.enabled {
+            ctx.descriptors = None;
+            return true;
+        }
+
+        let deposit = DepositDescriptor::from_str(self.deposit_descriptor.value.trim());
+        let unvault = UnvaultDescriptor::from_str(self.unvault_descriptor.value.trim());
+        let cpfp = CpfpDescriptor::from_str(self.cpfp_descriptor.value.trim());
+        self.deposit_descriptor.valid = deposit.is_ok();
+        self.unvault_descriptor.valid = unvault.is_ok();
+        self.cpfp_descriptor.valid = cpfp.is_ok();
+        let (deposit, unvault, cpfp) = match (deposit, unvault, cpfp) {
+            (Ok(deposit), Ok(unvault), Ok(cpfp)) => (deposit, unvault, cpfp),
+            _ => return false,
+        };
+
+        let parameters = match UnvaultParameters::new(&deposit, &unvault) {
+            Some(parameters) => parameters,
+            None => {
+                self.unvault_descriptor.valid = false;
+                self.warning = Some(
+                    "The unvault descriptor does not match the stakeholders of the deposit descriptor"
+                        .to_string(),
+                );
+                return false;
+            }
+        };
+
+        // The cpfp descriptor has the keys of all the managers and only them.
+        let cpfp_keys = cpfp.xpubs();
+        if cpfp_keys.len() != parameters.managers_keys.len()
+            || parameters
+                .managers_keys
+                .iter()
+                .any(|key| !cpfp_keys.contains(key))
+        {
+            self.cpfp_descriptor.valid = false;
+            self.warning = Some(
+                "The cpfp descriptor does not have the managers keys of the unvault descriptor"
+                    .to_string(),
+            );
+            return false;
+        }
+
+        ctx.descriptors = Some(config::ScriptsConfig {
+            deposit_descriptor: deposit.to_string(),
+            unvault_descriptor: unvault.to_string(),
+            cpfp_descriptor: cpfp.to_string(),
+        });
+        ctx.number_managers = parameters.managers_keys.len();
+        ctx.managers_threshold = parameters.managers_threshold;
+        ctx.cosigners_enabled = !parameters.cosigners_keys.is_empty();
+        ctx.number_cosigners = parameters.cosigners_keys.len();
+        true
+    }
+
+    fn errors(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.enabled {
+            check_value(&mut errors, &self.deposit_descriptor, "deposit descriptor");
+            check_value(&mut errors, &self.unvault_descriptor, "unvault descriptor");
+            check_value(&mut errors, &self.cpfp_descriptor, "cpfp descriptor");
+        }
+        errors.extend(self.warning.clone());
+        errors
+    }
+
+    fn view(&mut self) -> Element<Message> {
+        self.view.render(
+            self.enabled,
+            &self.deposit_descriptor,
+            &self.unvault_descriptor,
+            &self.cpfp_descriptor,
+            self.warning.as_ref(),
+        )
+    }
+}
+
+impl Default for ImportDescriptors {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<ImportDescriptors> for Box<dyn Step> {
+    fn from(s: ImportDescriptors) -> Box<dyn Step> {
+        Box::new(s)
+    }
+}
+
+/// DefineOurXpubs asks for the participant keys when the shared part of the installation
+/// was imported from a federation bundle or when the descriptors were imported,
+/// it replaces the steps defining the descriptors.
+pub struct DefineOurXpubs {
+    stakeholder_xpub: Option<RequiredXpub>,
+    manager_xpub: Option<RequiredXpub>,
+    /// Some while an xpub is requested to the hardware wallet.
+    importing: Option<Role>,
+    warning: Option<String>,
+    view: view::DefineOurXpubs,
+}
+
+impl DefineOurXpubs {
+    pub fn new(role: &[Role]) -> Self {
+        Self {
+            stakeholder_xpub: if role.contains(&Role::Stakeholder) {
+                Some(RequiredXpub::new())
+            } else {
+                None
+            },
+            manager_xpub: if role.contains(&Role::Manager) {
+                Some(RequiredXpub::new())
+            } else {
+                None
+            },
+            importing: None,
+            warning: None,
+            view: view::DefineOurXpubs::new(),
+        }
+    }
+}
+
+impl Step for DefineOurXpubs {
+    fn answers(&self, answers: &Answers) -> Result<Vec<Message>, Error> {
+        Ok(answers.our_xpubs_messages())
+    }
+
+    fn skip(&self, ctx: &Context) -> bool {
+        ctx.federation.is_none() && ctx.descriptors.is_none()
+    }
+
+    fn load_context(&mut self, ctx: &Context) {
+        if let Some(previous) = &ctx.previous {
+            if let (Some(xpub), Some(previous_xpub)) =
+                (&mut self.stakeholder_xpub, previous.stakeholder_xpub())
+            {
+                if xpub.xpub.value.is_empty() {
+                    xpub.update(previous_xpub);
+                }
+            }
+            if let (Some(xpub), Some(previous_xpub)) =
+                (&mut self.manager_xpub, previous.manager_xpub())
+            {
+                if xpub.xpub.value.is_empty() {
+                    xpub.update(previous_xpub);
+                }
+            }
+        }
+    }
+
+    fn update(&mut self, message: Message) {
+        match message {
+            Message::ImportXpub(role) => {
+                self.importing = Some(role);
+                self.warning = None;
+            }
+            Message::XpubImported(role, res) => {
+                self.importing = None;
+                let xpub = match role {
+                    Role::Stakeholder => self.stakeholder_xpub.as_mut(),
+                    Role::Manager => self.manager_xpub.as_mut(),
+                };
+                match (xpub, res) {
+                    (Some(xpub), Ok(imported)) => xpub.import(&imported),
+                    (_, Err(e)) => self.warning = Some(e.to_string()),
+                    _ => {}
+                }
+            }
+            Message::DefineOurXpubs(message::DefineOurXpubs::StakeholderXpubEdited(xpub)) => {
+                if let Some(stakeholder_xpub) = &mut self.stakeholder_xpub {
+                    stakeholder_xpub.update(xpub);
+                }
+            }
+            Message::DefineOurXpubs(message::DefineOurXpubs::ManagerXpubEdited(xpub)) => {
+                if let Some(manager_xpub) = &mut self.manager_xpub {
+                    manager_xpub.update(xpub);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn apply(&mut self, ctx: &mut Context, config: &mut config::Config) -> bool {
+        let scripts_config = match (&ctx.federation, &ctx.descriptors) {
+            (Some(federation), _) => config::ScriptsConfig {
+                deposit_descriptor: federation.deposit_descriptor.clone(),
+                unvault_descriptor: federation.unvault_descriptor.clone(),
+                cpfp_descriptor: federation.cpfp_descriptor.clone(),
+            },
+            (None, Some(descriptors)) => descriptors.clone(),
+            (None, None) => return false,
+        };
+
+        self.warning = None;
+        let parameters = match (
+            DepositDescriptor::from_str(&scripts_config.deposit_descriptor),
+            UnvaultDescriptor::from_str(&scripts_config.unvault_descriptor),
+        ) {
+            (Ok(deposit), Ok(unvault)) => UnvaultParameters::new(&deposit, &unvault),
+            _ => None,
+        };
+        let parameters = match parameters {
+            Some(parameters) => parameters,
+            None => {
+                self.warning = Some("The descriptors are not valid".to_string());
+                return false;
+            }
+        };
+
+        // The keys of the descriptors are our xpub followed by the wildcard derivation step.
+        let is_key_of = |xpub: &RequiredXpub, keys: &[DescriptorPublicKey]| {
+            DescriptorPublicKey::from_str(&format!("{}/*", xpub.xpub.value))
+                .map(|key| keys.contains(&key))
+                .unwrap_or(false)
+        };
+
+        if let Some(xpub) = &mut self.stakeholder_xpub {
+            xpub.check_validity(&ctx.network);
+            if xpub.xpub.valid && !is_key_of(xpub, &parameters.stakeholders_keys) {
+                xpub.xpub.valid = false;
+                self.warning =
+                    Some("Your stakeholder xpub is not part of the deposit descriptor".to_string());
+            }
+        }
+
+        if let Some(xpub) = &mut self.manager_xpub {
+            xpub.check_validity(&ctx.network);
+            if xpub.xpub.valid && !is_key_of(xpub, &parameters.managers_keys) {
+                xpub.xpub.valid = false;
+                self.warning =
+                    Some("Your manager xpub is not part of the unvault descriptor".to_string());
+            }
+        }
+
+        if self.stakeholder_xpub.iter().any(|xpub| !xpub.xpub.valid)
+            || self.manager_xpub.iter().any(|xpub| !xpub.xpub.valid)
+        {
+            return false;
+        }
+
+        config.scripts_config = scripts_config;
+        if let Some(federation) = &ctx.federation {
+            config.coordinator_host = federation.coordinator_host.clone();
+            config.coordinator_noise_key = federation.coordinator_noise_key.clone();
+        }
+
+        config.stakeholder_config =
+            self.stakeholder_xpub
+                .as_ref()
+                .map(|xpub| config::StakeholderConfig {
+                    xpub: xpub.xkey().expect("already checked"),
+                    watchtowers: Vec::new(),
+                    emergency_address: "".to_string(),
+                });
+
+        // Without a bundle, the cosigners are defined by the next steps.
+        config.manager_config = self.manager_xpub.as_ref().map(|xpub| ManagerConfig {
+            xpub: xpub.xkey().expect("already checked"),
+            cosigners: ctx
+                .federation
+                .iter()
+                .flat_map(|federation| federation.cosigners.iter())
+                .map(|cosigner| CosignerConfig {
+                    host: SocketAddr::from_str(&cosigner.host).expect("checked at import"),
+                    noise_key: PublicKey::from_slice(
+                        &Vec::from_hex(&cosigner.noise_key).expect("checked at import"),
+                    )
+                    .expect("checked at import"),
+                })
+                .collect(),
+        });
+
+        if let Some(federation) = &ctx.federation {
+            ctx.managers_threshold = federation.managers_threshold;
+            ctx.cosigners_enabled = !federation.cosigners.is_empty();
+            ctx.number_cosigners = federation.cosigners.len();
+        }
+        true
+    }
+
+    fn errors(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if let Some(xpub) = &self.stakeholder_xpub {
+            check_value(&mut errors, &xpub.xpub, "our stakeholder xpub");
+        }
+        if let Some(xpub) = &self.manager_xpub {
+            check_value(&mut errors, &xpub.xpub, "our manager xpub");
+        }
+        errors.extend(self.warning.clone());
+        errors
+    }
+
+    fn view(&mut self) -> Element<Message> {
+        self.view.render(
+            self.stakeholder_xpub.as_ref().map(|xpub| &xpub.xpub),
+            self.manager_xpub.as_ref().map(|xpub| &xpub.xpub),
+            self.importing,
+            self.warning.as_ref(),
+        )
+    }
+}
+
+impl From<DefineOurXpubs> for Box<dyn Step> {
+    fn from(s: DefineOurXpubs) -> Box<dyn Step> {
+        Box::new(s)
+    }
+}
+
 pub struct DefineCpfpDescriptor {
     manager_xpubs: Vec<RequiredXpub>,
     warning: Option<String>,
@@ -207,6 +794,15 @@ impl DefineCpfpDescriptor {
 }
 
 impl Step for DefineCpfpDescriptor {
+    fn answers(&self, answers: &Answers) -> Result<Vec<Message>, Error> {
+        Ok(answers.cpfp_descriptor_messages())
+    }
+
+    /// skip this step if the descriptors were imported
+    fn skip(&self, ctx: &Context) -> bool {
+        ctx.federation.is_some() || ctx.descriptors.is_some()
+    }
+
     fn load_context(&mut self, ctx: &Context) {
         while self.manager_xpubs.len() != ctx.number_managers {
             match self.manager_xpubs.len().cmp(&ctx.number_managers) {
@@ -217,6 +813,17 @@ impl Step for DefineCpfpDescriptor {
                 Ordering::Equal => (),
             }
         }
+        if let Some(previous) = &ctx.previous {
+            for (xpub, previous_xpub) in self
+                .manager_xpubs
+                .iter_mut()
+                .zip(previous.managers_xpubs.iter())
+            {
+                if xpub.xpub.value.is_empty() {
+                    xpub.update(previous_xpub.clone());
+                }
+            }
+        }
     }
 
     fn update(&mut self, message: Message) {
@@ -265,6 +872,19 @@ impl Step for DefineCpfpDescriptor {
         self.warning.is_none()
     }
 
+    fn errors(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        for (i, xpub) in self.manager_xpubs.iter().enumerate() {
+            check_value(
+                &mut errors,
+                &xpub.xpub,
+                &format!("cpfp manager xpub #{}", i + 1),
+            );
+        }
+        errors.extend(self.warning.clone());
+        errors
+    }
+
     fn view(&mut self) -> Element<Message> {
         return self.view.render(
             self.manager_xpubs
@@ -298,6 +918,8 @@ impl From<DefineCpfpDescriptor> for Box<dyn Step> {
 pub struct DefineCoordinator {
     host: form::Value<String>,
     noise_key: form::Value<String>,
+    testing: bool,
+    connection: Option<Result<(), server::Error>>,
 
     view: view::DefineCoordinator,
 }
@@ -307,22 +929,52 @@ impl DefineCoordinator {
         Self {
             host: form::Value::default(),
             noise_key: form::Value::default(),
+            testing: false,
+            connection: None,
             view: view::DefineCoordinator::new(),
         }
     }
 }
 
 impl Step for DefineCoordinator {
+    fn answers(&self, answers: &Answers) -> Result<Vec<Message>, Error> {
+        Ok(answers.coordinator_messages())
+    }
+
+    /// skip this step if the coordinator was imported from a bundle
+    fn skip(&self, ctx: &Context) -> bool {
+        ctx.federation.is_some()
+    }
+
+    fn load_context(&mut self, ctx: &Context) {
+        if let Some(previous) = &ctx.previous {
+            if self.host.value.is_empty() && self.noise_key.value.is_empty() {
+                self.host.value = previous.config.coordinator_host.clone();
+                self.noise_key.value = previous.config.coordinator_noise_key.clone();
+            }
+        }
+    }
+
     fn update(&mut self, message: Message) {
         if let Message::DefineCoordinator(msg) = message {
             match msg {
                 message::DefineCoordinator::HostEdited(host) => {
                     self.host.value = host;
                     self.host.valid = true;
+                    self.connection = None;
                 }
                 message::DefineCoordinator::NoiseKeyEdited(key) => {
                     self.noise_key.value = key;
                     self.noise_key.valid = true;
+                    self.connection = None;
+                }
+                message::DefineCoordinator::TestConnection(..) => {
+                    self.testing = true;
+                    self.connection = None;
+                }
+                message::DefineCoordinator::ConnectionTested(res) => {
+                    self.testing = false;
+                    self.connection = Some(res);
                 }
             };
         };
@@ -348,8 +1000,21 @@ impl Step for DefineCoordinator {
         true
     }
 
+    fn errors(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        check_value(&mut errors, &self.host, "coordinator host");
+        check_value(&mut errors, &self.noise_key, "coordinator noise key");
+        errors
+    }
+
     fn view(&mut self) -> Element<Message> {
-        self.view.render(&self.host, &self.noise_key)
+        self.view.render(
+            &self.host,
+            &self.noise_key,
+            parse_server(&self.host.value, &self.noise_key.value),
+            self.testing,
+            self.connection.as_ref(),
+        )
     }
 }
 
@@ -368,11 +1033,13 @@ impl From<DefineCoordinator> for Box<dyn Step> {
 pub struct DefineBitcoind {
     cookie_path: form::Value<String>,
     address: form::Value<String>,
+    testing: bool,
+    connection: Option<Result<BlockchainInfo, bitcoind::Error>>,
 
     view: view::DefineBitcoind,
 }
 
-fn bitcoind_default_cookie_path(network: &bitcoin::Network) -> Option<String> {
+pub fn bitcoind_default_cookie_path(network: &bitcoin::Network) -> Option<String> {
     #[cfg(target_os = "linux")]
     let configs_dir = dirs::home_dir();
 
@@ -406,7 +1073,7 @@ fn bitcoind_default_cookie_path(network: &bitcoin::Network) -> Option<String> {
     None
 }
 
-fn bitcoind_default_address(network: &bitcoin::Network) -> String {
+pub fn bitcoind_default_address(network: &bitcoin::Network) -> String {
     match network {
         bitcoin::Network::Bitcoin => "127.0.0.1:8332".to_string(),
         bitcoin::Network::Testnet => "127.0.0.1:18332".to_string(),
@@ -420,13 +1087,26 @@ impl DefineBitcoind {
         Self {
             cookie_path: form::Value::default(),
             address: form::Value::default(),
+            testing: false,
+            connection: None,
             view: view::DefineBitcoind::new(),
         }
     }
 }
 
 impl Step for DefineBitcoind {
+    fn answers(&self, answers: &Answers) -> Result<Vec<Message>, Error> {
+        Ok(answers.bitcoind_messages())
+    }
+
     fn load_context(&mut self, ctx: &Context) {
+        if let Some(previous) = &ctx.previous {
+            if self.cookie_path.value.is_empty() && self.address.value.is_empty() {
+                let bitcoind_config = &previous.config.bitcoind_config;
+                self.cookie_path.value = bitcoind_config.cookie_path.to_string_lossy().to_string();
+                self.address.value = bitcoind_config.addr.to_string();
+            }
+        }
         if self.cookie_path.value.is_empty() {
             self.cookie_path.value = bitcoind_default_cookie_path(&ctx.network).unwrap_or_default()
         }
@@ -440,10 +1120,20 @@ impl Step for DefineBitcoind {
                 message::DefineBitcoind::AddressEdited(address) => {
                     self.address.value = address;
                     self.address.valid = true;
+                    self.connection = None;
                 }
                 message::DefineBitcoind::CookiePathEdited(path) => {
                     self.cookie_path.value = path;
                     self.address.valid = true;
+                    self.connection = None;
+                }
+                message::DefineBitcoind::TestConnection => {
+                    self.testing = true;
+                    self.connection = None;
+                }
+                message::DefineBitcoind::ConnectionTested(res) => {
+                    self.testing = false;
+                    self.connection = Some(res);
                 }
             };
         };
@@ -475,8 +1165,20 @@ impl Step for DefineBitcoind {
         }
     }
 
+    fn errors(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        check_value(&mut errors, &self.cookie_path, "bitcoind cookie path");
+        check_value(&mut errors, &self.address, "bitcoind address");
+        errors
+    }
+
     fn view(&mut self) -> Element<Message> {
-        self.view.render(&self.address, &self.cookie_path)
+        self.view.render(
+            &self.address,
+            &self.cookie_path,
+            self.testing,
+            self.connection.as_ref(),
+        )
     }
 }
 
@@ -496,6 +1198,11 @@ pub struct Final {
     generating: bool,
     warning: Option<String>,
     config_path: Option<PathBuf>,
+    exporting: bool,
+    bundle_path: Option<PathBuf>,
+    fingerprint: Option<SetupFingerprint>,
+    show_fingerprint_qr_code: bool,
+    changes: Option<Vec<Change>>,
     view: view::Final,
 }
 
@@ -505,6 +1212,11 @@ impl Final {
             generating: false,
             warning: None,
             config_path: None,
+            exporting: false,
+            bundle_path: None,
+            fingerprint: None,
+            show_fingerprint_qr_code: false,
+            changes: None,
             view: view::Final::new(),
         }
     }
@@ -528,14 +1240,40 @@ impl Step for Final {
                 self.config_path = None;
                 self.warning = None;
             }
+            Message::ExportBundle => {
+                self.exporting = true;
+                self.bundle_path = None;
+                self.warning = None;
+            }
+            Message::BundleExported(res) => {
+                self.exporting = false;
+                match res {
+                    Err(e) => self.warning = Some(e.to_string()),
+                    Ok(path) => self.bundle_path = Some(path),
+                }
+            }
+            Message::ToggleFingerprintQRCode => {
+                self.show_fingerprint_qr_code = !self.show_fingerprint_qr_code;
+            }
             _ => {}
         };
     }
 
+    fn load_context(&mut self, ctx: &Context) {
+        self.fingerprint = ctx.setup_fingerprint;
+        self.changes = ctx.changes.clone();
+        self.view.load(self.fingerprint.as_ref());
+    }
+
     fn view(&mut self) -> Element<Message> {
         self.view.render(
             self.generating,
             self.config_path.as_ref(),
+            self.exporting,
+            self.bundle_path.as_ref(),
+            self.fingerprint.as_ref(),
+            self.show_fingerprint_qr_code,
+            self.changes.as_ref(),
             self.warning.as_ref(),
         )
     }
@@ -555,9 +1293,13 @@ impl From<Final> for Box<dyn Step> {
 
 #[cfg(test)]
 mod tests {
-    use super::{DefineCpfpDescriptor as DefineCpfpDescriptorStep, *};
+    use super::{
+        DefineCoordinator as DefineCoordinatorStep,
+        DefineCpfpDescriptor as DefineCpfpDescriptorStep, *,
+    };
     use crate::installer::config::Config;
     use crate::installer::message::{DefineCpfpDescriptor, ParticipantXpub, *};
+    use bitcoin::util::bip32::ExtendedPubKey;
 
     const STAKEHOLDERS_XPUBS: [&str; 4] = [
         "xpub6DEzq5DNPx2rPiZJ7wvFhxRKUKDoV1GwjFmFdaxFfbsw9HsHyxc9usoRUMxqJaMrwoXh4apahsGEnjAS4cVCBDgqsx5Groww22AdHbgxVDg", 
@@ -665,8 +1407,15 @@ mod tests {
             network: bitcoin::Network::Bitcoin,
             cosigners_enabled: true,
             private_noise_key: "".to_string(),
+            save_private_noise_key: false,
             number_managers: 1,
             number_cosigners: 4,
+            managers_threshold: 0,
+            federation: None,
+            descriptors: None,
+            setup_fingerprint: None,
+            previous: None,
+            changes: None,
             stakeholders_xpubs: vec![
                 STAKEHOLDERS_XPUBS[2].to_string(),
                 STAKEHOLDERS_XPUBS[1].to_string(),
@@ -705,8 +1454,15 @@ mod tests {
             network: bitcoin::Network::Bitcoin,
             cosigners_enabled: true,
             private_noise_key: "".to_string(),
+            save_private_noise_key: false,
             number_managers: 1,
             number_cosigners: 4,
+            managers_threshold: 0,
+            federation: None,
+            descriptors: None,
+            setup_fingerprint: None,
+            previous: None,
+            changes: None,
             stakeholders_xpubs: vec![
                 STAKEHOLDERS_XPUBS[3].to_string(),
                 STAKEHOLDERS_XPUBS[2].to_string(),
@@ -752,8 +1508,15 @@ mod tests {
             network: bitcoin::Network::Bitcoin,
             cosigners_enabled: true,
             private_noise_key: "".to_string(),
+            save_private_noise_key: false,
             number_managers: 1,
             number_cosigners: 4,
+            managers_threshold: 0,
+            federation: None,
+            descriptors: None,
+            setup_fingerprint: None,
+            previous: None,
+            changes: None,
             stakeholders_xpubs: vec![
                 STAKEHOLDERS_XPUBS[2].to_string(),
                 STAKEHOLDERS_XPUBS[1].to_string(),
@@ -785,8 +1548,15 @@ mod tests {
             network: bitcoin::Network::Bitcoin,
             cosigners_enabled: true,
             private_noise_key: "".to_string(),
+            save_private_noise_key: false,
             number_managers: 1,
             number_cosigners: 4,
+            managers_threshold: 0,
+            federation: None,
+            descriptors: None,
+            setup_fingerprint: None,
+            previous: None,
+            changes: None,
             stakeholders_xpubs: vec![
                 STAKEHOLDERS_XPUBS[3].to_string(),
                 STAKEHOLDERS_XPUBS[2].to_string(),
@@ -851,4 +1621,381 @@ mod tests {
             cpfp_2_config.scripts_config.cpfp_descriptor,
         );
     }
+
+    #[test]
+    fn import_our_xpub_from_device() {
+        let mut ctx = Context::new(bitcoin::Network::Bitcoin);
+        let mut step = stakeholder::DefineStakeholderXpubs::new();
+        load_stakeholders_xpubs(
+            &mut step,
+            vec![
+                STAKEHOLDERS_XPUBS[3].to_string(),
+                STAKEHOLDERS_XPUBS[0].to_string(),
+                STAKEHOLDERS_XPUBS[1].to_string(),
+            ],
+        );
+
+        step.update(Message::ImportXpub(crate::revault::Role::Stakeholder));
+        step.update(Message::XpubImported(
+            crate::revault::Role::Stakeholder,
+            Err(crate::installer::Error::HardwareWallet(
+                "device disconnected".to_string(),
+            )),
+        ));
+        let mut config = Config::new();
+        assert!(!step.apply(&mut ctx, &mut config));
+
+        // The imported xpub keeps its key origin in the descriptor.
+        let xpub = ExtendedPubKey::from_str(STAKEHOLDERS_XPUBS[2]).unwrap();
+        let imported = DescriptorPublicKey::from_str(&crate::installer::xpub_with_origin(
+            &bitcoin::util::bip32::Fingerprint::from_hex("d34db33f").unwrap(),
+            &crate::installer::revault_derivation_path(
+                bitcoin::Network::Bitcoin,
+                crate::revault::Role::Stakeholder,
+            ),
+            &xpub,
+        ))
+        .unwrap();
+        step.update(Message::ImportXpub(crate::revault::Role::Stakeholder));
+        step.update(Message::XpubImported(
+            crate::revault::Role::Stakeholder,
+            Ok(imported),
+        ));
+        assert!(step.apply(&mut ctx, &mut config));
+        assert_eq!(config.stakeholder_config.unwrap().xpub, xpub);
+        assert!(config.scripts_config.deposit_descriptor.contains(&format!(
+            "[d34db33f/48'/0'/0'/2']{}/*",
+            STAKEHOLDERS_XPUBS[2]
+        )));
+    }
+
+    #[test]
+    fn define_our_xpubs_from_federation() {
+        use revaultd::revault_tx::scripts::{DepositDescriptor, UnvaultDescriptor};
+
+        let keys = |xpubs: &[&str]| -> Vec<DescriptorPublicKey> {
+            xpubs
+                .iter()
+                .map(|xpub| DescriptorPublicKey::from_str(&format!("{}/*", xpub)).unwrap())
+                .collect()
+        };
+        let federation = Federation {
+            network: bitcoin::Network::Bitcoin,
+            deposit_descriptor: DepositDescriptor::new(keys(&STAKEHOLDERS_XPUBS[..2]))
+                .unwrap()
+                .to_string(),
+            unvault_descriptor: UnvaultDescriptor::new(
+                keys(&STAKEHOLDERS_XPUBS[..2]),
+                keys(&MANAGERS_XPUBS),
+                1,
+                Vec::new(),
+                10,
+            )
+            .unwrap()
+            .to_string(),
+            cpfp_descriptor: CpfpDescriptor::new(keys(&MANAGERS_XPUBS))
+                .unwrap()
+                .to_string(),
+            coordinator_host: "127.0.0.1:8383".to_string(),
+            coordinator_noise_key:
+                "d91563973102454a7830137e92d0548bc83b4ea2799f1df04622ca1307381402".to_string(),
+            cosigners: Vec::new(),
+            managers_threshold: 1,
+        };
+        let mut ctx = Context::new(bitcoin::Network::Bitcoin);
+        ctx.federation = Some(federation.clone());
+
+        let mut step = super::DefineOurXpubs::new(&Role::STAKEHOLDER_AND_MANAGER);
+        assert!(!step.skip(&ctx));
+
+        // Our stakeholder xpub is not part of the federation.
+        step.update(Message::DefineOurXpubs(
+            message::DefineOurXpubs::StakeholderXpubEdited(STAKEHOLDERS_XPUBS[3].to_string()),
+        ));
+        step.update(Message::DefineOurXpubs(
+            message::DefineOurXpubs::ManagerXpubEdited(MANAGERS_XPUBS[1].to_string()),
+        ));
+        let mut config = Config::new();
+        assert!(!step.apply(&mut ctx, &mut config));
+
+        step.update(Message::DefineOurXpubs(
+            message::DefineOurXpubs::StakeholderXpubEdited(STAKEHOLDERS_XPUBS[1].to_string()),
+        ));
+        assert!(step.apply(&mut ctx, &mut config));
+        assert_eq!(
+            config.scripts_config.deposit_descriptor,
+            federation.deposit_descriptor
+        );
+        assert_eq!(config.coordinator_host, federation.coordinator_host);
+        assert_eq!(
+            config.manager_config.unwrap().xpub.to_string(),
+            MANAGERS_XPUBS[1]
+        );
+        assert_eq!(ctx.managers_threshold, 1);
+    }
+
+    #[test]
+    fn define_our_xpubs_with_key_origin() {
+        use revaultd::revault_tx::scripts::{DepositDescriptor, UnvaultDescriptor};
+
+        let with_origin = |i: usize, xpub: &str| format!("[d34db33{}/48'/0'/0'/2']{}", i, xpub);
+        let stakeholders_keys: Vec<DescriptorPublicKey> = STAKEHOLDERS_XPUBS[..2]
+            .iter()
+            .enumerate()
+            .map(|(i, xpub)| {
+                DescriptorPublicKey::from_str(&format!("{}/*", with_origin(i, xpub))).unwrap()
+            })
+            .collect();
+        let managers_keys: Vec<DescriptorPublicKey> = MANAGERS_XPUBS
+            .iter()
+            .map(|xpub| DescriptorPublicKey::from_str(&format!("{}/*", xpub)).unwrap())
+            .collect();
+        let federation = Federation {
+            network: bitcoin::Network::Bitcoin,
+            deposit_descriptor: DepositDescriptor::new(stakeholders_keys.clone())
+                .unwrap()
+                .to_string(),
+            unvault_descriptor: UnvaultDescriptor::new(
+                stakeholders_keys,
+                managers_keys.clone(),
+                1,
+                Vec::new(),
+                10,
+            )
+            .unwrap()
+            .to_string(),
+            cpfp_descriptor: CpfpDescriptor::new(managers_keys).unwrap().to_string(),
+            coordinator_host: "127.0.0.1:8383".to_string(),
+            coordinator_noise_key:
+                "d91563973102454a7830137e92d0548bc83b4ea2799f1df04622ca1307381402".to_string(),
+            cosigners: Vec::new(),
+            managers_threshold: 1,
+        };
+        let mut ctx = Context::new(bitcoin::Network::Bitcoin);
+        ctx.federation = Some(federation);
+
+        let mut step = super::DefineOurXpubs::new(&Role::STAKEHOLDER_ONLY);
+        let mut config = Config::new();
+
+        // The xpub is in the descriptor but with another key origin.
+        step.update(Message::DefineOurXpubs(
+            message::DefineOurXpubs::StakeholderXpubEdited(STAKEHOLDERS_XPUBS[1].to_string()),
+        ));
+        assert!(!step.apply(&mut ctx, &mut config));
+        step.update(Message::DefineOurXpubs(
+            message::DefineOurXpubs::StakeholderXpubEdited(with_origin(0, STAKEHOLDERS_XPUBS[1])),
+        ));
+        assert!(!step.apply(&mut ctx, &mut config));
+
+        step.update(Message::DefineOurXpubs(
+            message::DefineOurXpubs::StakeholderXpubEdited(with_origin(1, STAKEHOLDERS_XPUBS[1])),
+        ));
+        assert!(step.apply(&mut ctx, &mut config));
+        assert_eq!(
+            config.stakeholder_config.unwrap().xpub.to_string(),
+            STAKEHOLDERS_XPUBS[1]
+        );
+    }
+
+    #[test]
+    fn define_private_noise_key() {
+        let mut ctx = Context::new(bitcoin::Network::Bitcoin);
+        let mut step = super::DefinePrivateNoiseKey::new();
+        assert!(step.apply(&mut ctx, &mut Config::new()));
+        assert_eq!(ctx.private_noise_key.len(), 64);
+        let generated = ctx.private_noise_key.clone();
+
+        step.update(Message::DefinePrivateNoiseKey(
+            message::DefinePrivateNoiseKey::Generate,
+        ));
+        assert!(step.apply(&mut ctx, &mut Config::new()));
+        assert_ne!(ctx.private_noise_key, generated);
+
+        // RFC 7748 test vector.
+        step.update(Message::DefinePrivateNoiseKey(
+            message::DefinePrivateNoiseKey::ManualEntry(true),
+        ));
+        step.update(Message::DefinePrivateNoiseKey(
+            message::DefinePrivateNoiseKey::KeyEdited(
+                "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a".to_string(),
+            ),
+        ));
+        assert!(step.apply(&mut ctx, &mut Config::new()));
+        assert_eq!(
+            noise_public_key(&ctx.private_noise_key)
+                .unwrap()
+                .as_ref()
+                .to_hex(),
+            "8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a"
+        );
+
+        step.update(Message::DefinePrivateNoiseKey(
+            message::DefinePrivateNoiseKey::KeyEdited("deadbeef".to_string()),
+        ));
+        assert!(!step.apply(&mut ctx, &mut Config::new()));
+    }
+
+    #[test]
+    fn edit_installation() {
+        let keys = |xpubs: &[&str]| -> Vec<DescriptorPublicKey> {
+            let mut xpubs: Vec<String> = xpubs.iter().map(|xpub| format!("{}/*", xpub)).collect();
+            xpubs.sort();
+            xpubs
+                .iter()
+                .map(|xpub| DescriptorPublicKey::from_str(xpub).unwrap())
+                .collect()
+        };
+
+        let mut previous_config = Config::new();
+        previous_config.stakeholder_config = Some(config::StakeholderConfig {
+            xpub: ExtendedPubKey::from_str(STAKEHOLDERS_XPUBS[1]).unwrap(),
+            watchtowers: Vec::new(),
+            emergency_address: "".to_string(),
+        });
+        previous_config.scripts_config.deposit_descriptor =
+            DepositDescriptor::new(keys(&STAKEHOLDERS_XPUBS))
+                .unwrap()
+                .to_string();
+        previous_config.scripts_config.unvault_descriptor = UnvaultDescriptor::new(
+            keys(&STAKEHOLDERS_XPUBS),
+            keys(&MANAGERS_XPUBS),
+            2,
+            Vec::new(),
+            12,
+        )
+        .unwrap()
+        .to_string();
+        previous_config.coordinator_host = "127.0.0.1:8383".to_string();
+        previous_config.coordinator_noise_key =
+            "d91563973102454a7830137e92d0548bc83b4ea2799f1df04622ca1307381402".to_string();
+
+        let mut ctx = Context::edit(PreviousInstallation {
+            config: previous_config.clone(),
+            private_noise_key: "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a"
+                .to_string(),
+            stakeholders_xpubs: STAKEHOLDERS_XPUBS.iter().map(|x| x.to_string()).collect(),
+            managers_xpubs: MANAGERS_XPUBS.iter().map(|x| x.to_string()).collect(),
+            cosigners_keys: Vec::new(),
+            managers_threshold: 2,
+            spending_delay: 12,
+            installed: true,
+        });
+        let mut config = Config::new();
+
+        let mut steps: Vec<Box<dyn Step>> = vec![
+            super::DefinePrivateNoiseKey::new().into(),
+            stakeholder::DefineStakeholderXpubs::new().into(),
+            stakeholder::DefineManagerXpubs::new().into(),
+            DefineCoordinatorStep::new().into(),
+        ];
+        for step in &mut steps {
+            step.load_context(&ctx);
+            assert!(step.apply(&mut ctx, &mut config));
+        }
+
+        assert_eq!(
+            ctx.private_noise_key,
+            "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a"
+        );
+        assert_eq!(ctx.managers_threshold, 2);
+        assert_eq!(
+            config.scripts_config.deposit_descriptor,
+            previous_config.scripts_config.deposit_descriptor
+        );
+        assert_eq!(
+            config.scripts_config.unvault_descriptor,
+            previous_config.scripts_config.unvault_descriptor
+        );
+        assert_eq!(config.coordinator_host, previous_config.coordinator_host);
+        assert_eq!(
+            config.coordinator_noise_key,
+            previous_config.coordinator_noise_key
+        );
+    }
+
+    #[test]
+    fn import_descriptors() {
+        let keys = |xpubs: &[&str]| -> Vec<DescriptorPublicKey> {
+            xpubs
+                .iter()
+                .map(|xpub| DescriptorPublicKey::from_str(&format!("{}/*", xpub)).unwrap())
+                .collect()
+        };
+        let deposit = DepositDescriptor::new(keys(&STAKEHOLDERS_XPUBS)).unwrap();
+        let unvault = UnvaultDescriptor::new(
+            keys(&STAKEHOLDERS_XPUBS),
+            keys(&MANAGERS_XPUBS),
+            2,
+            COSIGNERS_KEYS
+                .iter()
+                .map(|key| DescriptorPublicKey::from_str(key).unwrap())
+                .collect(),
+            12,
+        )
+        .unwrap();
+        let cpfp = CpfpDescriptor::new(keys(&MANAGERS_XPUBS)).unwrap();
+
+        let mut ctx = Context::new(bitcoin::Network::Bitcoin);
+        let mut config = Config::new();
+        let mut step = super::ImportDescriptors::new();
+        assert!(step.apply(&mut ctx, &mut config));
+        assert!(ctx.descriptors.is_none());
+
+        step.update(Message::ImportDescriptors(
+            message::ImportDescriptors::Enabled(true),
+        ));
+        for msg in [
+            message::ImportDescriptors::DepositDescriptorEdited(deposit.to_string()),
+            message::ImportDescriptors::UnvaultDescriptorEdited(unvault.to_string()),
+            message::ImportDescriptors::CpfpDescriptorEdited(deposit.to_string()),
+        ] {
+            step.update(Message::ImportDescriptors(msg));
+        }
+        // The deposit descriptor is not the cpfp descriptor of the managers.
+        assert!(!step.apply(&mut ctx, &mut config));
+
+        step.update(Message::ImportDescriptors(
+            message::ImportDescriptors::CpfpDescriptorEdited(cpfp.to_string()),
+        ));
+        assert!(step.apply(&mut ctx, &mut config));
+        assert_eq!(ctx.managers_threshold, 2);
+        assert_eq!(ctx.number_managers, 2);
+        assert_eq!(ctx.number_cosigners, 4);
+        assert!(ctx.cosigners_enabled);
+
+        let mut step = super::DefineOurXpubs::new(&Role::STAKEHOLDER_AND_MANAGER);
+        assert!(!step.skip(&ctx));
+        step.update(Message::DefineOurXpubs(
+            message::DefineOurXpubs::StakeholderXpubEdited(STAKEHOLDERS_XPUBS[2].to_string()),
+        ));
+        // Our manager xpub must be one of the cpfp descriptor.
+        step.update(Message::DefineOurXpubs(
+            message::DefineOurXpubs::ManagerXpubEdited(STAKEHOLDERS_XPUBS[3].to_string()),
+        ));
+        assert!(!step.apply(&mut ctx, &mut config));
+
+        step.update(Message::DefineOurXpubs(
+            message::DefineOurXpubs::ManagerXpubEdited(MANAGERS_XPUBS[0].to_string()),
+        ));
+        assert!(step.apply(&mut ctx, &mut config));
+        assert_eq!(
+            config.scripts_config.deposit_descriptor,
+            deposit.to_string()
+        );
+        assert_eq!(
+            config.scripts_config.unvault_descriptor,
+            unvault.to_string()
+        );
+        assert_eq!(config.scripts_config.cpfp_descriptor, cpfp.to_string());
+        assert_eq!(
+            config.stakeholder_config.unwrap().xpub.to_string(),
+            STAKEHOLDERS_XPUBS[2]
+        );
+        let manager_config = config.manager_config.unwrap();
+        assert_eq!(manager_config.xpub.to_string(), MANAGERS_XPUBS[0]);
+        // The cosigners are defined by the next steps.
+        assert!(manager_config.cosigners.is_empty());
+        assert!(stakeholder::DefineStakeholderXpubs::new().skip(&ctx));
+        assert!(DefineCpfpDescriptorStep::new().skip(&ctx));
+    }
 }
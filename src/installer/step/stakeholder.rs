@@ -1,7 +1,6 @@
 use std::cmp::Ordering;
 use std::str::FromStr;
 
-use bitcoin::util::bip32::ExtendedPubKey;
 use iced::Element;
 use revaultd::revault_tx::{
     miniscript::DescriptorPublicKey,
@@ -11,18 +10,21 @@ use revaultd::revault_tx::{
 use revault_ui::component::form;
 
 use crate::installer::{
+    answers::Answers,
     config,
     message::{self, Message},
     step::{
-        common::{CosignerKey, ParticipantXpub, RequiredXpub},
+        common::{check_value, participants, CosignerKey, ParticipantXpub, RequiredXpub},
         Context, Step,
     },
-    view,
+    view, Error,
 };
 
 pub struct DefineStakeholderXpubs {
     other_xpubs: Vec<ParticipantXpub>,
     our_xpub: RequiredXpub,
+    /// true while our xpub is requested to the hardware wallet.
+    importing: bool,
     warning: Option<String>,
 
     view: view::DefineStakeholderXpubsAsStakeholder,
@@ -33,6 +35,7 @@ impl DefineStakeholderXpubs {
         Self {
             warning: None,
             our_xpub: RequiredXpub::new(),
+            importing: false,
             other_xpubs: Vec::new(),
             view: view::DefineStakeholderXpubsAsStakeholder::new(),
         }
@@ -40,9 +43,40 @@ impl DefineStakeholderXpubs {
 }
 
 impl Step for DefineStakeholderXpubs {
+    fn answers(&self, answers: &Answers) -> Result<Vec<Message>, Error> {
+        Ok(answers.stakeholder_xpubs_messages())
+    }
+
+    /// skip this step if the descriptors were imported
+    fn skip(&self, ctx: &Context) -> bool {
+        ctx.federation.is_some() || ctx.descriptors.is_some()
+    }
+
+    fn load_context(&mut self, ctx: &Context) {
+        if let Some(previous) = &ctx.previous {
+            if self.our_xpub.xpub.value.is_empty() && self.other_xpubs.is_empty() {
+                if let Some(xpub) = previous.stakeholder_xpub() {
+                    self.our_xpub.update(xpub);
+                }
+                self.other_xpubs = participants(&previous.other_stakeholders_xpubs());
+            }
+        }
+    }
+
     fn update(&mut self, message: Message) {
-        if let Message::DefineStakeholderXpubs(msg) = message {
-            match msg {
+        match message {
+            Message::ImportXpub(_) => {
+                self.importing = true;
+                self.warning = None;
+            }
+            Message::XpubImported(_, res) => {
+                self.importing = false;
+                match res {
+                    Ok(xpub) => self.our_xpub.import(&xpub),
+                    Err(e) => self.warning = Some(e.to_string()),
+                }
+            }
+            Message::DefineStakeholderXpubs(msg) => match msg {
                 message::DefineStakeholderXpubs::OurXpubEdited(xpub) => {
                     self.our_xpub.update(xpub);
                 }
@@ -60,7 +94,8 @@ impl Step for DefineStakeholderXpubs {
                 message::DefineStakeholderXpubs::AddXpub => {
                     self.other_xpubs.push(ParticipantXpub::new());
                 }
-            };
+            },
+            _ => {}
         };
     }
 
@@ -81,7 +116,7 @@ impl Step for DefineStakeholderXpubs {
         }
 
         config.stakeholder_config = Some(config::StakeholderConfig {
-            xpub: ExtendedPubKey::from_str(&self.our_xpub.xpub.value).expect("already checked"),
+            xpub: self.our_xpub.xkey().expect("already checked"),
             watchtowers: Vec::new(),
             emergency_address: "".to_string(),
         });
@@ -117,9 +152,24 @@ impl Step for DefineStakeholderXpubs {
         self.warning.is_none()
     }
 
+    fn errors(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        check_value(&mut errors, &self.our_xpub.xpub, "our stakeholder xpub");
+        for (i, xpub) in self.other_xpubs.iter().enumerate() {
+            check_value(
+                &mut errors,
+                &xpub.xpub,
+                &format!("stakeholder xpub #{}", i + 1),
+            );
+        }
+        errors.extend(self.warning.clone());
+        errors
+    }
+
     fn view(&mut self) -> Element<Message> {
         return self.view.render(
             &self.our_xpub.xpub,
+            self.importing,
             self.other_xpubs
                 .iter_mut()
                 .enumerate()
@@ -182,6 +232,15 @@ impl DefineManagerXpubs {
     }
 }
 impl Step for DefineManagerXpubs {
+    fn answers(&self, answers: &Answers) -> Result<Vec<Message>, Error> {
+        Ok(answers.manager_xpubs_messages())
+    }
+
+    /// skip this step if the descriptors were imported
+    fn skip(&self, ctx: &Context) -> bool {
+        ctx.federation.is_some() || ctx.descriptors.is_some()
+    }
+
     fn load_context(&mut self, ctx: &Context) {
         self.stakeholder_xpubs = ctx.stakeholders_xpubs.clone();
         self.cosigners_enabled = ctx.cosigners_enabled;
@@ -194,6 +253,22 @@ impl Step for DefineManagerXpubs {
                 Ordering::Equal => (),
             }
         }
+        if let Some(previous) = &ctx.previous {
+            if self.manager_xpubs.is_empty() {
+                self.manager_xpubs = participants(&previous.managers_xpubs);
+                self.managers_threshold.value = previous.managers_threshold;
+                self.spending_delay.value = previous.spending_delay;
+            }
+            for (key, previous_key) in self
+                .cosigners
+                .iter_mut()
+                .zip(previous.cosigners_keys.iter())
+            {
+                if key.key.value.is_empty() {
+                    key.update(previous_key.clone());
+                }
+            }
+        }
     }
 
     fn update(&mut self, message: Message) {
@@ -313,7 +388,9 @@ impl Step for DefineManagerXpubs {
         };
 
         ctx.number_cosigners = self.cosigners.len();
+        ctx.cosigners_enabled = self.cosigners_enabled;
         ctx.number_managers = managers_keys.len();
+        ctx.managers_threshold = self.managers_threshold.value;
 
         match UnvaultDescriptor::new(
             stakeholders_keys,
@@ -332,6 +409,26 @@ impl Step for DefineManagerXpubs {
         self.warning.is_none()
     }
 
+    fn errors(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        for (i, xpub) in self.manager_xpubs.iter().enumerate() {
+            check_value(&mut errors, &xpub.xpub, &format!("manager xpub #{}", i + 1));
+        }
+        if self.cosigners_enabled {
+            for (i, cosigner) in self.cosigners.iter().enumerate() {
+                check_value(
+                    &mut errors,
+                    &cosigner.key,
+                    &format!("cosigner key #{}", i + 1),
+                );
+            }
+        }
+        check_value(&mut errors, &self.managers_threshold, "managers threshold");
+        check_value(&mut errors, &self.spending_delay, "spending delay");
+        errors.extend(self.warning.clone());
+        errors
+    }
+
     fn view(&mut self) -> Element<Message> {
         let cosigners = if self.cosigners_enabled {
             self.cosigners
@@ -399,6 +496,22 @@ impl DefineEmergencyAddress {
 }
 
 impl Step for DefineEmergencyAddress {
+    fn answers(&self, answers: &Answers) -> Result<Vec<Message>, Error> {
+        Ok(answers.emergency_address_messages())
+    }
+
+    fn load_context(&mut self, ctx: &Context) {
+        if let Some(stakeholder_config) = ctx
+            .previous
+            .as_ref()
+            .and_then(|previous| previous.config.stakeholder_config.as_ref())
+        {
+            if self.address.value.is_empty() {
+                self.address.value = stakeholder_config.emergency_address.clone();
+            }
+        }
+    }
+
     fn update(&mut self, message: Message) {
         if let Message::DefineEmergencyAddress(address) = message {
             self.address.value = address;
@@ -439,6 +552,13 @@ impl Step for DefineEmergencyAddress {
         }
     }
 
+    fn errors(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        check_value(&mut errors, &self.address, "emergency address");
+        errors.extend(self.warning.clone());
+        errors
+    }
+
     fn view(&mut self) -> Element<Message> {
         self.view.render(&self.address, self.warning.as_ref())
     }
@@ -1,9 +1,15 @@
 use iced::{
-    button::State as Button, pick_list, scrollable, text_input, Alignment, Checkbox, Column,
-    Container, Element, Length, Row,
+    button::State as Button, pick_list, qr_code, scrollable, text_input, Alignment, Checkbox,
+    Column, Container, Element, Length, QRCode, Row,
 };
 
+use std::net::SocketAddr;
+
+use bitcoin::hashes::hex::ToHex;
+use revaultd::revault_net::noise::PublicKey as NoisePubkey;
+
 use revault_ui::{
+    color,
     component::{
         button, card, form, image::revault_colored_logo, scroll, text::Text,
         ContainerBackgroundStyle,
@@ -12,8 +18,14 @@ use revault_ui::{
 };
 
 use crate::{
-    installer::message::{self, Message},
-    revault::Role,
+    bitcoind::{self, BlockchainInfo},
+    installer::{
+        bundle::Federation,
+        edit::Change,
+        message::{self, Message},
+    },
+    revault::{Role, SetupFingerprint},
+    server,
 };
 
 const NETWORKS: [bitcoin::Network; 4] = [
@@ -25,47 +37,149 @@ const NETWORKS: [bitcoin::Network; 4] = [
 
 pub struct Welcome {
     network_input: pick_list::State<bitcoin::Network>,
+    bundle_path_input: text_input::State,
+    import_button: Button,
     install_button: Button,
+    resume_button: Button,
+    discard_button: Button,
 }
 
 impl Welcome {
     pub fn new() -> Self {
         Self {
             network_input: pick_list::State::default(),
+            bundle_path_input: text_input::State::new(),
+            import_button: Button::default(),
             install_button: Button::default(),
+            resume_button: Button::default(),
+            discard_button: Button::default(),
         }
     }
 
-    pub fn render(&mut self, network: &bitcoin::Network) -> Element<Message> {
-        Container::new(Container::new(
-            Column::new()
-                .push(Container::new(
-                    revault_colored_logo()
-                        .width(Length::Units(400))
-                        .height(Length::Fill),
-                ))
-                .push(Container::new(
-                    pick_list::PickList::new(
-                        &mut self.network_input,
-                        &NETWORKS[..],
-                        Some(*network),
-                        message::Message::Network,
+    pub fn render(
+        &mut self,
+        network: &bitcoin::Network,
+        bundle_path: &form::Value<String>,
+        importing: bool,
+        federation: Option<&Federation>,
+        draft: Option<&bitcoin::Network>,
+        warning: Option<&String>,
+    ) -> Element<Message> {
+        let mut import_button = button::white_card_button(
+            &mut self.import_button,
+            button::button_content(
+                None,
+                if importing {
+                    "Importing..."
+                } else {
+                    "Import bundle"
+                },
+            ),
+        )
+        .width(Length::Units(200));
+        if !importing {
+            import_button = import_button.on_press(Message::ImportBundle(
+                std::path::PathBuf::from(&bundle_path.value),
+            ));
+        }
+
+        let mut bundle = Column::new()
+            .push(Text::new("Federation bundle (optional):").bold())
+            .push(
+                Row::new()
+                    .push(
+                        form::Form::new(
+                            &mut self.bundle_path_input,
+                            "Path to the federation bundle",
+                            bundle_path,
+                            Message::BundlePathEdited,
+                        )
+                        .warning("Please enter the path of a valid federation bundle")
+                        .size(20)
+                        .padding(10)
+                        .render(),
                     )
-                    .padding(10),
-                ))
-                .push(
-                    button::primary(
-                        &mut self.install_button,
-                        button::button_content(None, "Install"),
+                    .push(import_button)
+                    .spacing(10)
+                    .align_items(Alignment::Center),
+            )
+            .spacing(10)
+            .max_width(1000);
+
+        if let Some(federation) = federation {
+            bundle = bundle.push(card::border_success(Container::new(Text::new(&format!(
+                "Federation imported for {}, checksum: {}",
+                federation.network,
+                federation.checksum()
+            )))));
+        }
+
+        if let Some(error) = warning {
+            bundle = bundle.push(card::alert_warning(Container::new(Text::new(error))));
+        }
+
+        let mut col = Column::new().push(Container::new(
+            revault_colored_logo()
+                .width(Length::Units(400))
+                .height(Length::Fill),
+        ));
+
+        if let Some(draft_network) = draft {
+            col = col.push(card::white(Container::new(
+                Column::new()
+                    .push(Text::new(&format!(
+                        "An unfinished installation for {} was found.",
+                        draft_network
+                    )))
+                    .push(
+                        Row::new()
+                            .push(
+                                button::primary(
+                                    &mut self.resume_button,
+                                    button::button_content(None, "Resume"),
+                                )
+                                .on_press(Message::ResumeDraft)
+                                .width(Length::Units(200)),
+                            )
+                            .push(
+                                button::white_card_button(
+                                    &mut self.discard_button,
+                                    button::button_content(None, "Discard"),
+                                )
+                                .on_press(Message::DiscardDraft)
+                                .width(Length::Units(200)),
+                            )
+                            .spacing(10),
                     )
-                    .on_press(Message::Next)
-                    .width(Length::Units(200)),
+                    .spacing(20)
+                    .align_items(Alignment::Center),
+            )));
+        }
+
+        Container::new(Container::new(
+            col.push(Container::new(
+                pick_list::PickList::new(
+                    &mut self.network_input,
+                    &NETWORKS[..],
+                    Some(*network),
+                    message::Message::Network,
                 )
-                .width(Length::Fill)
-                .height(Length::Fill)
-                .padding(100)
-                .spacing(50)
-                .align_items(Alignment::Center),
+                .padding(10),
+            ))
+            .push(bundle)
+            .push(
+                button::primary(
+                    &mut self.install_button,
+                    button::button_content(None, "Install"),
+                )
+                .on_press(Message::Next)
+                .width(Length::Units(200)),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .padding(100)
+            .spacing(50)
+            .align_items(Alignment::Center),
         ))
         .center_y()
         .center_x()
@@ -184,6 +298,46 @@ pub fn required_xpub<'a>(
     .into()
 }
 
+/// Form of our xpub with a button importing it from the connected hardware wallet.
+fn our_xpub_form<'a>(
+    xpub_input: &'a mut text_input::State,
+    import_button: &'a mut Button,
+    placeholder: &str,
+    xpub: &form::Value<String>,
+    role: Role,
+    importing: bool,
+    on_change: fn(String) -> Message,
+) -> Element<'a, Message> {
+    let mut import = button::white_card_button(
+        import_button,
+        button::button_content(
+            Some(icon::connect_device_icon()),
+            if importing {
+                "Importing..."
+            } else {
+                "Import from device"
+            },
+        ),
+    )
+    .width(Length::Units(250));
+    if !importing {
+        import = import.on_press(Message::ImportXpub(role));
+    }
+
+    Row::new()
+        .push(
+            form::Form::new(xpub_input, placeholder, xpub, on_change)
+                .warning("Please enter a valid xpub")
+                .size(20)
+                .padding(10)
+                .render(),
+        )
+        .push(import)
+        .spacing(10)
+        .align_items(Alignment::Center)
+        .into()
+}
+
 pub fn cosigner_key<'a>(
     key: &form::Value<String>,
     key_input: &'a mut text_input::State,
@@ -209,6 +363,8 @@ pub fn cosigner_key<'a>(
 
 pub struct DefinePrivateNoiseKey {
     key_input: text_input::State,
+    generate_button: Button,
+    export_button: Button,
     next_button: Button,
     previous_button: Button,
     scroll: scrollable::State,
@@ -218,34 +374,332 @@ impl DefinePrivateNoiseKey {
     pub fn new() -> Self {
         Self {
             key_input: text_input::State::new(),
+            generate_button: Button::new(),
+            export_button: Button::new(),
             next_button: Button::new(),
             previous_button: Button::new(),
             scroll: scrollable::State::new(),
         }
     }
 
-    pub fn render<'a>(&'a mut self, key: &form::Value<String>) -> Element<Message> {
-        layout(
-            &mut self.scroll,
-            &mut self.previous_button,
-            Column::new()
-                .push(Text::new("Fill your private noise key:").bold().size(50))
+    pub fn render<'a>(
+        &'a mut self,
+        key: &form::Value<String>,
+        public_key: Option<&NoisePubkey>,
+        manual_entry: bool,
+        save_in_draft: bool,
+        exporting: bool,
+        public_key_path: Option<&std::path::PathBuf>,
+        warning: Option<&String>,
+    ) -> Element<Message> {
+        let mut col = Column::new()
+            .push(Text::new("Your noise key:").bold().size(50))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .padding(100)
+            .spacing(50)
+            .align_items(Alignment::Center);
+
+        if let Some(error) = warning {
+            col = col.push(card::alert_warning(Container::new(Text::new(error))));
+        }
+
+        if let Some(public_key) = public_key {
+            let mut export_button = button::white_card_button(
+                &mut self.export_button,
+                button::button_content(
+                    None,
+                    if exporting {
+                        "Exporting..."
+                    } else {
+                        "Export public key"
+                    },
+                ),
+            )
+            .width(Length::Units(200));
+            if !exporting {
+                export_button = export_button.on_press(Message::ExportNoisePublicKey(*public_key));
+            }
+
+            let mut public_key_col = Column::new()
+                .push(Text::new(
+                    "Your noise public key, the coordinator operator needs it:",
+                ))
+                .push(Text::new(&public_key.as_ref().to_hex()).bold())
+                .push(export_button)
+                .spacing(10)
+                .align_items(Alignment::Center);
+            if let Some(path) = public_key_path {
+                public_key_col = public_key_col.push(Text::new(&format!(
+                    "Noise public key exported to {}",
+                    path.to_string_lossy()
+                )));
+            }
+            col = col.push(public_key_col);
+        }
+
+        let mut advanced = Column::new()
+            .push(Checkbox::new(
+                manual_entry,
+                "Enter the private key manually (advanced)",
+                |msg| {
+                    Message::DefinePrivateNoiseKey(message::DefinePrivateNoiseKey::ManualEntry(msg))
+                },
+            ))
+            .spacing(10);
+        if manual_entry {
+            advanced = advanced.push(
+                form::Form::new(&mut self.key_input, "", key, |msg| {
+                    Message::DefinePrivateNoiseKey(message::DefinePrivateNoiseKey::KeyEdited(msg))
+                })
+                .warning("Please enter a 32 bytes noise private key that is hex encoded")
+                .size(20)
+                .padding(10)
+                .render(),
+            );
+        } else {
+            advanced = advanced.push(
+                button::white_card_button(
+                    &mut self.generate_button,
+                    button::button_content(None, "Generate a new key"),
+                )
+                .on_press(Message::DefinePrivateNoiseKey(
+                    message::DefinePrivateNoiseKey::Generate,
+                ))
+                .width(Length::Units(200)),
+            );
+        }
+
+        advanced = advanced.push(Checkbox::new(
+            save_in_draft,
+            "Keep the private key in the draft of the installation, so that it is not \
+             generated again if the installer is closed before the end",
+            |msg| Message::DefinePrivateNoiseKey(message::DefinePrivateNoiseKey::SaveInDraft(msg)),
+        ));
+
+        col = col.push(advanced).push(
+            button::primary(&mut self.next_button, button::button_content(None, "Next"))
+                .on_press(Message::Next)
+                .width(Length::Units(200)),
+        );
+
+        layout(&mut self.scroll, &mut self.previous_button, col.into())
+    }
+}
+
+pub struct ImportDescriptors {
+    deposit_descriptor_input: text_input::State,
+    unvault_descriptor_input: text_input::State,
+    cpfp_descriptor_input: text_input::State,
+    previous_button: Button,
+    next_button: Button,
+    scroll: scrollable::State,
+}
+
+impl ImportDescriptors {
+    pub fn new() -> Self {
+        Self {
+            deposit_descriptor_input: text_input::State::new(),
+            unvault_descriptor_input: text_input::State::new(),
+            cpfp_descriptor_input: text_input::State::new(),
+            previous_button: Button::new(),
+            next_button: Button::new(),
+            scroll: scrollable::State::new(),
+        }
+    }
+
+    pub fn render<'a>(
+        &'a mut self,
+        enabled: bool,
+        deposit_descriptor: &form::Value<String>,
+        unvault_descriptor: &form::Value<String>,
+        cpfp_descriptor: &form::Value<String>,
+        warning: Option<&String>,
+    ) -> Element<'a, Message> {
+        let mut col = Column::new()
+            .push(Text::new("Descriptors").bold().size(50))
+            .push(Checkbox::new(
+                enabled,
+                "I already have the descriptors of the federation",
+                |enabled| Message::ImportDescriptors(message::ImportDescriptors::Enabled(enabled)),
+            ))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .padding(100)
+            .spacing(50)
+            .align_items(Alignment::Center);
+
+        if enabled {
+            col = col
                 .push(
-                    Column::new().spacing(10).push(
-                        form::Form::new(&mut self.key_input, "", key, Message::PrivateNoiseKey)
-                            .warning(
-                                "Please enter a 32 bytes noise private key that is hex encoded",
+                    Column::new()
+                        .push(Text::new("Deposit descriptor:").bold())
+                        .push(
+                            form::Form::new(
+                                &mut self.deposit_descriptor_input,
+                                "wsh(multi(...))",
+                                deposit_descriptor,
+                                |msg| {
+                                    Message::ImportDescriptors(
+                                        message::ImportDescriptors::DepositDescriptorEdited(msg),
+                                    )
+                                },
                             )
-                            .size(20)
+                            .warning("Please enter a valid deposit descriptor")
+                            .size(15)
                             .padding(10)
                             .render(),
-                    ),
+                        )
+                        .spacing(10),
                 )
                 .push(
-                    button::primary(&mut self.next_button, button::button_content(None, "Next"))
-                        .on_press(Message::Next)
-                        .width(Length::Units(200)),
+                    Column::new()
+                        .push(Text::new("Unvault descriptor:").bold())
+                        .push(
+                            form::Form::new(
+                                &mut self.unvault_descriptor_input,
+                                "wsh(andor(...))",
+                                unvault_descriptor,
+                                |msg| {
+                                    Message::ImportDescriptors(
+                                        message::ImportDescriptors::UnvaultDescriptorEdited(msg),
+                                    )
+                                },
+                            )
+                            .warning("Please enter a valid unvault descriptor")
+                            .size(15)
+                            .padding(10)
+                            .render(),
+                        )
+                        .spacing(10),
                 )
+                .push(
+                    Column::new()
+                        .push(Text::new("Cpfp descriptor:").bold())
+                        .push(
+                            form::Form::new(
+                                &mut self.cpfp_descriptor_input,
+                                "wsh(multi(...))",
+                                cpfp_descriptor,
+                                |msg| {
+                                    Message::ImportDescriptors(
+                                        message::ImportDescriptors::CpfpDescriptorEdited(msg),
+                                    )
+                                },
+                            )
+                            .warning("Please enter a valid cpfp descriptor")
+                            .size(15)
+                            .padding(10)
+                            .render(),
+                        )
+                        .spacing(10),
+                );
+        } else {
+            col = col.push(Text::new(
+                "The descriptors will be built from the xpubs of the participants.",
+            ));
+        }
+
+        if let Some(error) = warning {
+            col = col.push(card::alert_warning(Container::new(Text::new(error))));
+        }
+
+        col = col.push(
+            button::primary(&mut self.next_button, button::button_content(None, "Next"))
+                .on_press(Message::Next)
+                .width(Length::Units(200)),
+        );
+
+        layout(&mut self.scroll, &mut self.previous_button, col.into())
+    }
+}
+
+pub struct DefineOurXpubs {
+    stakeholder_xpub_input: text_input::State,
+    stakeholder_import_button: Button,
+    manager_xpub_input: text_input::State,
+    manager_import_button: Button,
+    previous_button: Button,
+    save_button: Button,
+    scroll: scrollable::State,
+}
+
+impl DefineOurXpubs {
+    pub fn new() -> Self {
+        Self {
+            stakeholder_xpub_input: text_input::State::new(),
+            stakeholder_import_button: Button::new(),
+            manager_xpub_input: text_input::State::new(),
+            manager_import_button: Button::new(),
+            previous_button: Button::new(),
+            save_button: Button::new(),
+            scroll: scrollable::State::new(),
+        }
+    }
+
+    pub fn render<'a>(
+        &'a mut self,
+        stakeholder_xpub: Option<&form::Value<String>>,
+        manager_xpub: Option<&form::Value<String>>,
+        importing: Option<Role>,
+        warning: Option<&String>,
+    ) -> Element<'a, Message> {
+        let mut content = Column::new().push(Text::new("Your keys").bold().size(50));
+
+        if let Some(xpub) = stakeholder_xpub {
+            content = content.push(
+                Column::new()
+                    .push(Text::new("Your stakeholder xpub:").bold())
+                    .push(our_xpub_form(
+                        &mut self.stakeholder_xpub_input,
+                        &mut self.stakeholder_import_button,
+                        "Your stakeholder xpub",
+                        xpub,
+                        Role::Stakeholder,
+                        importing.is_some(),
+                        |msg| {
+                            Message::DefineOurXpubs(message::DefineOurXpubs::StakeholderXpubEdited(
+                                msg,
+                            ))
+                        },
+                    ))
+                    .spacing(10),
+            );
+        }
+
+        if let Some(xpub) = manager_xpub {
+            content = content.push(
+                Column::new()
+                    .push(Text::new("Your manager xpub:").bold())
+                    .push(our_xpub_form(
+                        &mut self.manager_xpub_input,
+                        &mut self.manager_import_button,
+                        "Your manager xpub",
+                        xpub,
+                        Role::Manager,
+                        importing.is_some(),
+                        |msg| {
+                            Message::DefineOurXpubs(message::DefineOurXpubs::ManagerXpubEdited(msg))
+                        },
+                    ))
+                    .spacing(10),
+            );
+        }
+
+        content = content.push(
+            button::primary(&mut self.save_button, button::button_content(None, "Next"))
+                .on_press(Message::Next)
+                .width(Length::Units(200)),
+        );
+
+        if let Some(error) = warning {
+            content = content.push(card::alert_warning(Container::new(Text::new(error))));
+        }
+
+        layout(
+            &mut self.scroll,
+            &mut self.previous_button,
+            content
                 .width(Length::Fill)
                 .height(Length::Fill)
                 .padding(100)
@@ -258,6 +712,7 @@ impl DefinePrivateNoiseKey {
 
 pub struct DefineStakeholderXpubsAsStakeholder {
     our_xpub_input: text_input::State,
+    import_button: Button,
     previous_button: Button,
     save_button: Button,
     add_xpub_button: Button,
@@ -268,6 +723,7 @@ impl DefineStakeholderXpubsAsStakeholder {
     pub fn new() -> Self {
         Self {
             our_xpub_input: text_input::State::new(),
+            import_button: Button::new(),
             add_xpub_button: Button::new(),
             scroll: scrollable::State::new(),
             previous_button: Button::new(),
@@ -277,6 +733,7 @@ impl DefineStakeholderXpubsAsStakeholder {
     pub fn render<'a>(
         &'a mut self,
         our_xpub: &form::Value<String>,
+        importing: bool,
         other_xpubs: Vec<Element<'a, Message>>,
         warning: Option<&String>,
     ) -> Element<'a, Message> {
@@ -285,22 +742,19 @@ impl DefineStakeholderXpubsAsStakeholder {
             .push(
                 Column::new()
                     .push(Text::new("Your stakeholder xpub:").bold())
-                    .push(
-                        form::Form::new(
-                            &mut self.our_xpub_input,
-                            "Your stakeholder xpub",
-                            our_xpub,
-                            |msg| {
-                                Message::DefineStakeholderXpubs(
-                                    message::DefineStakeholderXpubs::OurXpubEdited(msg),
-                                )
-                            },
-                        )
-                        .warning("Please enter a valid xpub")
-                        .size(20)
-                        .padding(10)
-                        .render(),
-                    )
+                    .push(our_xpub_form(
+                        &mut self.our_xpub_input,
+                        &mut self.import_button,
+                        "Your stakeholder xpub",
+                        our_xpub,
+                        Role::Stakeholder,
+                        importing,
+                        |msg| {
+                            Message::DefineStakeholderXpubs(
+                                message::DefineStakeholderXpubs::OurXpubEdited(msg),
+                            )
+                        },
+                    ))
                     .spacing(10),
             )
             .push(
@@ -544,6 +998,7 @@ pub struct DefineManagerXpubsAsManager {
     spending_delay: SpendingDelay,
     add_xpub_button: Button,
     our_xpub_input: text_input::State,
+    import_button: Button,
     scroll: scrollable::State,
     previous_button: Button,
     save_button: Button,
@@ -553,6 +1008,7 @@ impl DefineManagerXpubsAsManager {
     pub fn new() -> Self {
         Self {
             our_xpub_input: text_input::State::new(),
+            import_button: Button::new(),
             add_xpub_button: Button::new(),
             scroll: scrollable::State::new(),
             previous_button: Button::new(),
@@ -568,6 +1024,7 @@ impl DefineManagerXpubsAsManager {
         managers_threshold: &form::Value<usize>,
         spending_delay: &form::Value<u32>,
         our_xpub: &form::Value<String>,
+        importing: bool,
         other_xpubs: Vec<Element<'a, Message>>,
         cosigners: Vec<Element<'a, Message>>,
         cosigners_enabled: bool,
@@ -575,20 +1032,15 @@ impl DefineManagerXpubsAsManager {
     ) -> Element<'a, Message> {
         let manager_xpub_col = Column::new()
             .push(Text::new("Your manager xpub:").bold())
-            .push(
-                form::Form::new(
-                    &mut self.our_xpub_input,
-                    "Your manager xpub",
-                    our_xpub,
-                    |msg| {
-                        Message::DefineManagerXpubs(message::DefineManagerXpubs::OurXpubEdited(msg))
-                    },
-                )
-                .warning("Please enter a valid xpub")
-                .size(20)
-                .padding(10)
-                .render(),
-            )
+            .push(our_xpub_form(
+                &mut self.our_xpub_input,
+                &mut self.import_button,
+                "Your manager xpub",
+                our_xpub,
+                Role::Manager,
+                importing,
+                |msg| Message::DefineManagerXpubs(message::DefineManagerXpubs::OurXpubEdited(msg)),
+            ))
             .spacing(10);
 
         let mut content = Column::new()
@@ -865,6 +1317,7 @@ pub struct DefineCoordinator {
     noise_key_input: text_input::State,
     scroll: scrollable::State,
     previous_button: Button,
+    test_button: Button,
     save_button: Button,
 }
 
@@ -875,6 +1328,7 @@ impl DefineCoordinator {
             noise_key_input: text_input::State::new(),
             scroll: scrollable::State::new(),
             previous_button: Button::new(),
+            test_button: Button::new(),
             save_button: Button::new(),
         }
     }
@@ -882,7 +1336,26 @@ impl DefineCoordinator {
         &'a mut self,
         host: &form::Value<String>,
         noise_key: &form::Value<String>,
+        target: Option<(SocketAddr, NoisePubkey)>,
+        testing: bool,
+        connection: Option<&Result<(), server::Error>>,
     ) -> Element<'a, Message> {
+        let mut col_test = Column::new()
+            .push(test_connection_button(
+                &mut self.test_button,
+                testing,
+                target.map(|(host, key)| {
+                    Message::DefineCoordinator(message::DefineCoordinator::TestConnection(
+                        host, key,
+                    ))
+                }),
+            ))
+            .spacing(10)
+            .align_items(Alignment::Center);
+        if let Some(status) = connection_status(connection) {
+            col_test = col_test.push(status);
+        }
+
         layout(
             &mut self.scroll,
             &mut self.previous_button,
@@ -925,6 +1398,7 @@ impl DefineCoordinator {
                         )
                         .spacing(10),
                 )
+                .push(col_test)
                 .push(
                     button::primary(&mut self.save_button, button::button_content(None, "Next"))
                         .on_press(Message::Next)
@@ -940,6 +1414,46 @@ impl DefineCoordinator {
     }
 }
 
+/// The button is disabled while the connection is tested or if the server
+/// settings are not correct.
+fn test_connection_button<'a, T: 'a + Clone>(
+    state: &'a mut Button,
+    testing: bool,
+    on_press: Option<T>,
+) -> iced::Button<'a, T> {
+    let mut button = button::white_card_button(
+        state,
+        button::button_content(
+            None,
+            if testing {
+                "Testing..."
+            } else {
+                "Test connection"
+            },
+        ),
+    )
+    .width(Length::Units(200));
+    if let Some(msg) = on_press {
+        if !testing {
+            button = button.on_press(msg);
+        }
+    }
+    button
+}
+
+fn connection_status<'a, T: 'a>(
+    connection: Option<&Result<(), server::Error>>,
+) -> Option<Container<'a, T>> {
+    match connection? {
+        Ok(()) => Some(card::border_success(Container::new(Text::new(
+            "Noise handshake succeeded",
+        )))),
+        Err(e) => Some(card::alert_warning(Container::new(Text::new(
+            &e.to_string(),
+        )))),
+    }
+}
+
 pub struct DefineEmergencyAddress {
     address_input: text_input::State,
     scroll: scrollable::State,
@@ -1014,6 +1528,7 @@ impl DefineEmergencyAddress {
 pub struct Cosigner {
     noise_key_input: text_input::State,
     host_input: text_input::State,
+    test_button: Button,
 }
 
 impl Cosigner {
@@ -1021,43 +1536,55 @@ impl Cosigner {
         Self {
             noise_key_input: text_input::State::new(),
             host_input: text_input::State::new(),
+            test_button: Button::new(),
         }
     }
     pub fn render(
         &mut self,
         host: &form::Value<String>,
         noise_key: &form::Value<String>,
+        target: Option<(SocketAddr, NoisePubkey)>,
+        testing: bool,
+        connection: Option<&Result<(), server::Error>>,
     ) -> Element<message::DefineCosigner> {
-        Container::new(
-            Row::new()
-                .push(
-                    form::Form::new(
-                        &mut self.host_input,
-                        "Host",
-                        host,
-                        message::DefineCosigner::HostEdited,
-                    )
-                    .warning("Please enter a valid host")
-                    .size(20)
-                    .padding(10)
-                    .render(),
+        let mut col = Column::new().spacing(10);
+        let row = Row::new()
+            .push(
+                form::Form::new(
+                    &mut self.host_input,
+                    "Host",
+                    host,
+                    message::DefineCosigner::HostEdited,
                 )
-                .push(
-                    form::Form::new(
-                        &mut self.noise_key_input,
-                        "Noise key",
-                        noise_key,
-                        message::DefineCosigner::NoiseKeyEdited,
-                    )
-                    .warning("Key must be a 64 characters hex encoded string")
-                    .size(20)
-                    .padding(10)
-                    .render(),
+                .warning("Please enter a valid host")
+                .size(20)
+                .padding(10)
+                .render(),
+            )
+            .push(
+                form::Form::new(
+                    &mut self.noise_key_input,
+                    "Noise key",
+                    noise_key,
+                    message::DefineCosigner::NoiseKeyEdited,
                 )
-                .spacing(5)
-                .align_items(Alignment::Center),
-        )
-        .into()
+                .warning("Key must be a 64 characters hex encoded string")
+                .size(20)
+                .padding(10)
+                .render(),
+            )
+            .push(test_connection_button(
+                &mut self.test_button,
+                testing,
+                target.map(|(host, key)| message::DefineCosigner::TestConnection(host, key)),
+            ))
+            .spacing(5)
+            .align_items(Alignment::Center);
+        col = col.push(row);
+        if let Some(status) = connection_status(connection) {
+            col = col.push(status);
+        }
+        Container::new(col).into()
     }
 }
 
@@ -1114,6 +1641,7 @@ pub struct DefineBitcoind {
     cookie_path_input: text_input::State,
     scroll: scrollable::State,
     previous_button: Button,
+    test_button: Button,
     save_button: Button,
 }
 
@@ -1124,6 +1652,7 @@ impl DefineBitcoind {
             cookie_path_input: text_input::State::new(),
             scroll: scrollable::State::new(),
             previous_button: Button::new(),
+            test_button: Button::new(),
             save_button: Button::new(),
         }
     }
@@ -1131,6 +1660,8 @@ impl DefineBitcoind {
         &'a mut self,
         address: &form::Value<String>,
         cookie_path: &form::Value<String>,
+        testing: bool,
+        connection: Option<&Result<BlockchainInfo, bitcoind::Error>>,
     ) -> Element<'a, Message> {
         let col_address = Column::new()
             .push(Text::new("Address:").bold())
@@ -1161,6 +1692,47 @@ impl DefineBitcoind {
             )
             .spacing(10);
 
+        let mut test_button = button::white_card_button(
+            &mut self.test_button,
+            button::button_content(
+                None,
+                if testing {
+                    "Testing..."
+                } else {
+                    "Test connection"
+                },
+            ),
+        )
+        .width(Length::Units(200));
+        if !testing {
+            test_button = test_button.on_press(Message::DefineBitcoind(
+                message::DefineBitcoind::TestConnection,
+            ));
+        }
+        let mut col_test = Column::new()
+            .push(test_button)
+            .spacing(10)
+            .align_items(Alignment::Center);
+        match connection {
+            Some(Ok(info)) => {
+                col_test = col_test.push(card::border_success(
+                    Container::new(Text::new(&format!(
+                        "Connected to bitcoind on chain '{}', {} blocks of {} headers, sync progress: {:.2}%",
+                        info.chain,
+                        info.blocks,
+                        info.headers,
+                        info.sync_progress()
+                    ))),
+                ))
+            }
+            Some(Err(e)) => {
+                col_test = col_test.push(card::alert_warning(Container::new(Text::new(
+                    &e.to_string(),
+                ))))
+            }
+            None => {}
+        }
+
         layout(
             &mut self.scroll,
             &mut self.previous_button,
@@ -1172,6 +1744,7 @@ impl DefineBitcoind {
                 )
                 .push(col_address)
                 .push(col_cookie)
+                .push(col_test)
                 .push(
                     button::primary(&mut self.save_button, button::button_content(None, "Next"))
                         .on_press(Message::Next)
@@ -1191,6 +1764,9 @@ pub struct Final {
     scroll: scrollable::State,
     previous_button: Button,
     action_button: Button,
+    export_button: Button,
+    qr_code_button: Button,
+    qr_code: Option<qr_code::State>,
 }
 
 impl Final {
@@ -1199,13 +1775,26 @@ impl Final {
             scroll: scrollable::State::new(),
             previous_button: Button::new(),
             action_button: Button::new(),
+            export_button: Button::new(),
+            qr_code_button: Button::new(),
+            qr_code: None,
         }
     }
 
+    // Fingerprint is loaded directly in the view in order to cache the created qrcode.
+    pub fn load(&mut self, fingerprint: Option<&SetupFingerprint>) {
+        self.qr_code = fingerprint.and_then(|f| qr_code::State::new(f.to_hex()).ok());
+    }
+
     pub fn render(
         &mut self,
         generating: bool,
         config_path: Option<&std::path::PathBuf>,
+        exporting: bool,
+        bundle_path: Option<&std::path::PathBuf>,
+        fingerprint: Option<&SetupFingerprint>,
+        show_fingerprint_qr_code: bool,
+        changes: Option<&Vec<Change>>,
         warning: Option<&String>,
     ) -> Element<Message> {
         let mut col = Column::new()
@@ -1219,6 +1808,63 @@ impl Final {
             col = col.push(card::alert_warning(Container::new(Text::new(error))));
         }
 
+        if let Some(fingerprint) = fingerprint {
+            let mut fingerprint_col = Column::new()
+                .push(Text::new("Setup fingerprint:").bold())
+                .push(Text::new(&fingerprint.words().join(" ")).bold().size(30))
+                .push(Text::new(&fingerprint.to_hex()).small())
+                .push(Text::new(
+                    "Compare it with the other participants, it must be the same for everyone.",
+                ))
+                .spacing(10)
+                .align_items(Alignment::Center);
+            if show_fingerprint_qr_code {
+                if let Some(qr_code) = self.qr_code.as_mut() {
+                    fingerprint_col =
+                        fingerprint_col.push(Container::new(QRCode::new(qr_code).cell_size(5)));
+                }
+            }
+            fingerprint_col = fingerprint_col.push(
+                button::white_card_button(
+                    &mut self.qr_code_button,
+                    button::button_content(
+                        None,
+                        if show_fingerprint_qr_code {
+                            "Hide QR code"
+                        } else {
+                            "Show QR code"
+                        },
+                    ),
+                )
+                .on_press(Message::ToggleFingerprintQRCode)
+                .width(Length::Units(200)),
+            );
+            col = col.push(fingerprint_col);
+        }
+
+        if let Some(changes) = changes {
+            let mut changes_col = Column::new()
+                .push(Text::new("Changes of the installation:").bold())
+                .spacing(5);
+            if changes.is_empty() {
+                changes_col = changes_col.push(Text::new("No change"));
+            }
+            for change in changes {
+                changes_col = changes_col.push(match change {
+                    Change::Removed(line) => Text::new(&format!("- {}", line))
+                        .small()
+                        .color(color::ALERT),
+                    Change::Added(line) => Text::new(&format!("+ {}", line))
+                        .small()
+                        .color(color::SUCCESS),
+                });
+            }
+            col = col.push(card::white(Container::new(changes_col)).width(Length::Fill));
+            col = col.push(Text::new(
+                "The previous configuration files are backed up next to the new ones.",
+            ));
+        }
+
         if generating {
             col = col.push(
                 button::primary(
@@ -1258,6 +1904,36 @@ impl Final {
             );
         }
 
+        let mut export_button = button::white_card_button(
+            &mut self.export_button,
+            button::button_content(
+                None,
+                if exporting {
+                    "Exporting..."
+                } else {
+                    "Export federation bundle"
+                },
+            ),
+        )
+        .width(Length::Units(300));
+        if !exporting {
+            export_button = export_button.on_press(Message::ExportBundle);
+        }
+        let mut export = Column::new()
+            .push(Text::new(
+                "Share the federation bundle with the other participants, they will only have to fill in their own keys.",
+            ))
+            .push(export_button)
+            .spacing(10)
+            .align_items(Alignment::Center);
+        if let Some(path) = bundle_path {
+            export = export.push(Text::new(&format!(
+                "Federation bundle exported to {}",
+                path.to_string_lossy()
+            )));
+        }
+        col = col.push(export);
+
         layout(&mut self.scroll, &mut self.previous_button, col.into())
     }
 }
@@ -1,6 +1,8 @@
 pub mod app;
+pub mod bitcoind;
 pub mod conversion;
 pub mod daemon;
 pub mod installer;
 pub mod loader;
 pub mod revault;
+pub mod server;
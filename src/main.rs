@@ -32,6 +32,29 @@ enum Arg {
     ConfigPath(PathBuf),
     DatadirPath(PathBuf),
     Network(bitcoin::Network),
+    /// Re-run the installer against the existing installation.
+    Edit,
+    /// Install without the GUI, from the answers file.
+    Install,
+    AnswersPath(PathBuf),
+    /// Generate the keys and the configurations of a regtest federation.
+    GenerateFederation,
+    Stakeholders(usize),
+    Managers(usize),
+    ManagersThreshold(usize),
+    SpendingDelay(u32),
+}
+
+/// Parses the value following the option.
+fn option_value<T>(args: &[String], i: usize) -> Result<T, Box<dyn Error>>
+where
+    T: FromStr,
+    <T as FromStr>::Err: std::fmt::Display,
+{
+    let value = args
+        .get(i + 1)
+        .ok_or_else(|| format!("missing arg to {}", args[i]))?;
+    T::from_str(value).map_err(|e| format!("invalid arg to {}: {}", args[i], e).into())
 }
 
 fn parse_args(args: Vec<String>) -> Result<Vec<Arg>, Box<dyn Error>> {
@@ -49,6 +72,26 @@ fn parse_args(args: Vec<String>) -> Result<Vec<Arg>, Box<dyn Error>> {
             } else {
                 return Err("missing arg to --datadir".into());
             }
+        } else if arg == "--answers" {
+            if let Some(a) = args.get(i + 1) {
+                res.push(Arg::AnswersPath(PathBuf::from(a)));
+            } else {
+                return Err("missing arg to --answers".into());
+            }
+        } else if arg == "--edit" {
+            res.push(Arg::Edit);
+        } else if arg == "--install" {
+            res.push(Arg::Install);
+        } else if arg == "--generate-federation" {
+            res.push(Arg::GenerateFederation);
+        } else if arg == "--stakeholders" {
+            res.push(Arg::Stakeholders(option_value(&args, i)?));
+        } else if arg == "--managers" {
+            res.push(Arg::Managers(option_value(&args, i)?));
+        } else if arg == "--threshold" {
+            res.push(Arg::ManagersThreshold(option_value(&args, i)?));
+        } else if arg == "--csv" {
+            res.push(Arg::SpendingDelay(option_value(&args, i)?));
         } else if arg.contains("--") {
             let network = bitcoin::Network::from_str(args[i].trim_start_matches("--"))?;
             res.push(Arg::Network(network));
@@ -115,7 +158,11 @@ impl Application for GUI {
     fn new(config: Config) -> (GUI, Command<Self::Message>) {
         match config {
             Config::Install(config_path, network) => {
-                let (install, command) = Installer::new(config_path, network);
+                let (install, command) = Installer::new(
+                    config_path,
+                    network,
+                    Box::new(|| Box::pin(connect_hardware_wallet())),
+                );
                 (
                     Self {
                         state: State::Installer(install),
@@ -126,6 +173,15 @@ impl Application for GUI {
                     ]),
                 )
             }
+            Config::Edit(install, command) => (
+                Self {
+                    state: State::Installer(install),
+                },
+                Command::batch(vec![
+                    command.map(Message::Install),
+                    Command::perform(ctrl_c(), |_| Message::CtrlC),
+                ]),
+            ),
             Config::Run(cfg) => {
                 let daemon_cfg =
                     DaemonConfig::from_file(Some(cfg.revaultd_config_path.clone())).unwrap();
@@ -186,7 +242,10 @@ impl Application for GUI {
                 );
 
                 context.blockheight = info.blockheight;
-                context.managers_threshold = info.managers_threshold;
+                // The daemon threshold is only used if the descriptors did not give one.
+                if context.managers_threshold == 0 {
+                    context.managers_threshold = info.managers_threshold;
+                }
 
                 let (app, command) = App::new(context);
                 self.state = State::App(app);
@@ -235,6 +294,8 @@ impl Application for GUI {
 pub enum Config {
     Run(app::Config),
     Install(PathBuf, bitcoin::Network),
+    /// The installer edits the installation loaded from the given configuration.
+    Edit(Installer, Command<installer::Message>),
 }
 
 impl Config {
@@ -247,10 +308,126 @@ impl Config {
             Err(e) => Err(format!("Failed to read configuration file: {}", e).into()),
         }
     }
+
+    /// Loads the installation to edit, the installer is not started if its
+    /// configuration cannot be read.
+    pub fn edit(self) -> Result<Self, Box<dyn Error>> {
+        match self {
+            Config::Run(cfg) => {
+                let daemon_cfg = DaemonConfig::from_file(Some(cfg.revaultd_config_path.clone()))
+                    .map_err(|e| format!("Failed to read the daemon configuration: {}", e))?;
+                let datadir_path = cfg
+                    .revaultd_config_path
+                    .parent()
+                    .map(|path| path.to_path_buf())
+                    .unwrap_or_default();
+                let (install, command) = Installer::edit(
+                    datadir_path,
+                    daemon_cfg,
+                    Box::new(|| Box::pin(connect_hardware_wallet())),
+                )
+                .map_err(|e| format!("Failed to load the installation to edit: {}", e))?;
+                Ok(Config::Edit(install, command))
+            }
+            Config::Edit(..) => Ok(self),
+            Config::Install(..) => Err("No installation to edit".into()),
+        }
+    }
+}
+
+/// Installs the configuration files from the answers file without starting the GUI.
+fn install_from_answers(args: Vec<Arg>) -> Result<PathBuf, Box<dyn Error>> {
+    let mut answers_path = None;
+    let mut datadir_path = None;
+    for arg in args {
+        match arg {
+            Arg::Install => {}
+            Arg::AnswersPath(path) => answers_path = Some(path),
+            Arg::DatadirPath(path) => datadir_path = Some(path),
+            _ => return Err("Unknown args combination".into()),
+        }
+    }
+    let answers_path = answers_path.ok_or("missing --answers <file> with --install")?;
+    let datadir_path = match datadir_path {
+        Some(path) => path,
+        None => default_datadir().map_err(|_| "Could not locate the default datadir")?,
+    };
+
+    let answers = installer::Answers::from_file(&answers_path).map_err(|e| e.to_string())?;
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    let path = runtime
+        .block_on(installer::install_from_answers(datadir_path, answers))
+        .map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+/// Writes the files of a regtest federation in the datadir, for development.
+fn generate_federation(args: Vec<Arg>) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut datadir_path = None;
+    let mut stakeholders = None;
+    let mut managers = None;
+    let mut managers_threshold = None;
+    let mut spending_delay = None;
+    for arg in args {
+        match arg {
+            Arg::GenerateFederation => {}
+            Arg::DatadirPath(path) => datadir_path = Some(path),
+            Arg::Stakeholders(n) => stakeholders = Some(n),
+            Arg::Managers(n) => managers = Some(n),
+            Arg::ManagersThreshold(n) => managers_threshold = Some(n),
+            Arg::SpendingDelay(n) => spending_delay = Some(n),
+            _ => return Err("Unknown args combination".into()),
+        }
+    }
+    let params = installer::FederationParameters {
+        stakeholders: stakeholders.ok_or("missing --stakeholders <n>")?,
+        managers: managers.ok_or("missing --managers <n>")?,
+        managers_threshold: managers_threshold.ok_or("missing --threshold <n>")?,
+        spending_delay: spending_delay.ok_or("missing --csv <blocks>")?,
+    };
+    let datadir_path = datadir_path.ok_or("missing --datadir <destination directory>")?;
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    let paths = runtime
+        .block_on(installer::generate_federation(params, datadir_path))
+        .map_err(|e| e.to_string())?;
+    Ok(paths)
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let args = parse_args(std::env::args().collect())?;
+    let mut args = parse_args(std::env::args().collect())?;
+    if args.contains(&Arg::GenerateFederation) {
+        match generate_federation(args) {
+            Ok(paths) => {
+                for path in paths {
+                    println!("{}", path.display());
+                }
+                return Ok(());
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+    if args.contains(&Arg::Install) {
+        match install_from_answers(args) {
+            Ok(path) => {
+                println!("Installed, GUI configuration written to {}", path.display());
+                return Ok(());
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+    let edit = args.contains(&Arg::Edit);
+    args.retain(|arg| *arg != Arg::Edit);
     let config = match args.as_slice() {
         [] => {
             let datadir_path = default_datadir().unwrap();
@@ -272,6 +449,17 @@ fn main() -> Result<(), Box<dyn Error>> {
             return Err("Unknown args combination".into());
         }
     }?;
+    let config = if edit {
+        match config.edit() {
+            Ok(config) => config,
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        config
+    };
 
     let level = if let Config::Run(cfg) = &config {
         log_level_from_config(&cfg)?
@@ -362,6 +550,51 @@ mod tests {
             Some(vec![Arg::Network(bitcoin::Network::Regtest)]),
             parse_args(vec!["--regtest".into()]).ok()
         );
+        assert_eq!(true, parse_args(vec!["--answers".into()]).is_err());
+        assert_eq!(
+            Some(vec![
+                Arg::Install,
+                Arg::AnswersPath(PathBuf::from("setup.toml"))
+            ]),
+            parse_args(
+                "--install --answers setup.toml"
+                    .split(" ")
+                    .map(|a| a.to_string())
+                    .collect()
+            )
+            .ok()
+        );
+        assert_eq!(true, parse_args(vec!["--stakeholders".into()]).is_err());
+        assert_eq!(
+            true,
+            parse_args(vec!["--csv".into(), "ten".into()]).is_err()
+        );
+        assert_eq!(
+            Some(vec![
+                Arg::GenerateFederation,
+                Arg::Stakeholders(3),
+                Arg::Managers(2),
+                Arg::ManagersThreshold(1),
+                Arg::SpendingDelay(12),
+            ]),
+            parse_args(
+                "--generate-federation --stakeholders 3 --managers 2 --threshold 1 --csv 12"
+                    .split(" ")
+                    .map(|a| a.to_string())
+                    .collect()
+            )
+            .ok()
+        );
+        assert_eq!(
+            Some(vec![Arg::Edit, Arg::Network(bitcoin::Network::Regtest)]),
+            parse_args(
+                "--edit --regtest"
+                    .split(" ")
+                    .map(|a| a.to_string())
+                    .collect()
+            )
+            .ok()
+        );
         assert_eq!(
             Some(vec![
                 Arg::DatadirPath(PathBuf::from("hello")),
@@ -1,4 +1,16 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use std::str::FromStr;
+
+use bitcoin::hashes::{hex::ToHex, sha256, Hash};
+use revaultd::{
+    revault_net::noise::PublicKey as NoisePubkey,
+    revault_tx::{
+        miniscript::DescriptorPublicKey,
+        scripts::{CpfpDescriptor, DepositDescriptor, UnvaultDescriptor},
+    },
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Role {
     Manager,
     Stakeholder,
@@ -23,3 +35,217 @@ impl Role {
     pub const STAKEHOLDER_ONLY: [Role; 1] = [Role::Stakeholder];
     pub const STAKEHOLDER_AND_MANAGER: [Role; 2] = [Role::Stakeholder, Role::Manager];
 }
+
+/// SetupFingerprint is a short digest of the descriptors and of the coordinator
+/// noise key. The participants compare it out of band to make sure they all
+/// installed the same federation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SetupFingerprint(sha256::Hash);
+
+impl SetupFingerprint {
+    /// Number of bytes of the digest displayed in hex.
+    const HEX_LEN: usize = 8;
+    /// Number of words displayed, each word encodes 11 bits of the digest.
+    const WORDS_LEN: usize = 6;
+
+    pub fn new(
+        deposit_descriptor: &DepositDescriptor,
+        unvault_descriptor: &UnvaultDescriptor,
+        cpfp_descriptor: &CpfpDescriptor,
+        coordinator_noise_key: &NoisePubkey,
+    ) -> Self {
+        let data = format!(
+            "{}\n{}\n{}\n{}",
+            deposit_descriptor,
+            unvault_descriptor,
+            cpfp_descriptor,
+            coordinator_noise_key.as_ref().to_hex()
+        );
+        Self(sha256::Hash::hash(data.as_bytes()))
+    }
+
+    pub fn to_hex(&self) -> String {
+        self.0.into_inner()[..Self::HEX_LEN].to_hex()
+    }
+
+    /// Words of the BIP39 english word list.
+    pub fn words(&self) -> Vec<&'static str> {
+        let bytes = self.0.into_inner();
+        let word_list = bip39::Language::English.word_list();
+        (0..Self::WORDS_LEN)
+            .map(|i| {
+                let mut index = 0;
+                for bit in i * 11..(i + 1) * 11 {
+                    index <<= 1;
+                    if bytes[bit / 8] & (0x80 >> (bit % 8)) != 0 {
+                        index |= 1;
+                    }
+                }
+                word_list[index]
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Display for SetupFingerprint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.words().join(" "), self.to_hex())
+    }
+}
+
+/// Keys and parameters of the unvault descriptor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnvaultParameters {
+    pub stakeholders_keys: Vec<DescriptorPublicKey>,
+    pub managers_keys: Vec<DescriptorPublicKey>,
+    pub cosigners_keys: Vec<DescriptorPublicKey>,
+    pub managers_threshold: usize,
+    pub spending_delay: u32,
+}
+
+impl UnvaultParameters {
+    /// The threshold is not exposed by the unvault descriptor, it is built again with
+    /// each possible value until it matches. Returns None if the stakeholders keys of the
+    /// deposit descriptor do not produce the unvault descriptor.
+    pub fn new(deposit: &DepositDescriptor, unvault: &UnvaultDescriptor) -> Option<Self> {
+        let stakeholders_keys = deposit.xpubs();
+
+        // The managers' xpubs are all the xpubs from the Unvault descriptor except the
+        // Stakeholders' ones and the Cosigning Servers' ones.
+        let mut managers_keys = Vec::new();
+        let mut cosigners_keys = Vec::new();
+        for key in unvault.xpubs() {
+            match key {
+                DescriptorPublicKey::SinglePub(_) => cosigners_keys.push(key),
+                DescriptorPublicKey::XPub(_) => {
+                    if !stakeholders_keys.contains(&key) {
+                        managers_keys.push(key)
+                    }
+                }
+            }
+        }
+
+        let unvault = unvault.to_string();
+        let spending_delay = spending_delay(&unvault)?;
+        let managers_threshold = (1..=managers_keys.len()).find(|threshold| {
+            UnvaultDescriptor::new(
+                stakeholders_keys.clone(),
+                managers_keys.clone(),
+                *threshold,
+                cosigners_keys.clone(),
+                spending_delay,
+            )
+            .map(|descriptor| descriptor.to_string() == unvault)
+            .unwrap_or(false)
+        })?;
+
+        Some(Self {
+            stakeholders_keys,
+            managers_keys,
+            cosigners_keys,
+            managers_threshold,
+            spending_delay,
+        })
+    }
+}
+
+/// Reads the relative timelock of the unvault descriptor: `older(<delay>)`.
+fn spending_delay(unvault_descriptor: &str) -> Option<u32> {
+    let (_, rest) = unvault_descriptor.split_once("older(")?;
+    let (delay, _) = rest.split_once(')')?;
+    u32::from_str(delay).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STAKEHOLDERS_XPUBS: [&str; 2] = [
+        "xpub6DEzq5DNPx2rPiZJ7wvFhxRKUKDoV1GwjFmFdaxFfbsw9HsHyxc9usoRUMxqJaMrwoXh4apahsGEnjAS4cVCBDgqsx5Groww22AdHbgxVDg",
+        "xpub6F7Ltmsut73cbUNAzh44DkxncMeQfPtRzx7aoXjFbUdd7yofR2intU4b6QcsXot1jgmVjHB3iMybCLhtqvhAx3L4VPbGUz5fwuyNeTkypUP",
+    ];
+
+    const MANAGERS_XPUBS: [&str; 2] = [
+        "xpub6CZFHPW1GiB8YgV7zGpeQDB6mMHZYPQyUaHrM1nMvKMgLxwok4xCtnzjuxQ3p1LHJUkz5i1Y7bRy5fmGrdg8UBVb39XdXNtWWd2wTsNd7T9",
+        "xpub6Doj75MBvKp7bgHxF1KeDGxm36rd4wonZWv8sfzTeNoNVX2QZaQdrEcs7NDXvs4Cbsy9TPMx5VDcMK6JjSKepBbYDPiJ9bLBR4bqfdHmxZx",
+    ];
+
+    fn keys(xpubs: &[&str]) -> Vec<DescriptorPublicKey> {
+        xpubs
+            .iter()
+            .map(|xpub| DescriptorPublicKey::from_str(&format!("{}/*", xpub)).unwrap())
+            .collect()
+    }
+
+    fn new_fingerprint(coordinator_noise_key: [u8; 32]) -> SetupFingerprint {
+        SetupFingerprint::new(
+            &DepositDescriptor::new(keys(&STAKEHOLDERS_XPUBS)).unwrap(),
+            &UnvaultDescriptor::new(
+                keys(&STAKEHOLDERS_XPUBS),
+                keys(&MANAGERS_XPUBS),
+                1,
+                Vec::new(),
+                10,
+            )
+            .unwrap(),
+            &CpfpDescriptor::new(keys(&MANAGERS_XPUBS)).unwrap(),
+            &NoisePubkey(coordinator_noise_key),
+        )
+    }
+
+    #[test]
+    fn setup_fingerprint() {
+        let fingerprint = new_fingerprint([1; 32]);
+        assert_eq!(fingerprint, new_fingerprint([1; 32]));
+        assert_eq!(fingerprint.to_hex().len(), 16);
+        assert_eq!(fingerprint.words().len(), 6);
+        assert_eq!(fingerprint.words(), new_fingerprint([1; 32]).words());
+
+        let other = new_fingerprint([2; 32]);
+        assert_ne!(fingerprint.to_hex(), other.to_hex());
+    }
+
+    #[test]
+    fn setup_fingerprint_bip39_vector() {
+        // Test vector of the BIP39 specification for the entropy 7f7f..7f.
+        const MNEMONIC: &str =
+            "legal winner thank year wave sausage worth useful legal winner thank yellow";
+        let mnemonic = bip39::Mnemonic::from_entropy(&[0x7f; 16]).unwrap();
+        assert_eq!(mnemonic.to_string(), MNEMONIC);
+
+        // The words of the fingerprint are read from the digest bits the same
+        // way the mnemonic is read from the entropy bits.
+        let fingerprint = SetupFingerprint(sha256::Hash::from_inner([0x7f; 32]));
+        assert_eq!(
+            fingerprint.words(),
+            MNEMONIC.split(' ').take(6).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_spending_delay() {
+        assert_eq!(
+            spending_delay("wsh(andor(multi(1,A,B),older(10),thresh(2,pkh(C),a:pkh(D))))"),
+            Some(10)
+        );
+        assert_eq!(spending_delay("wsh(multi(1,A,B))"), None);
+    }
+
+    #[test]
+    fn unvault_parameters() {
+        let deposit = DepositDescriptor::new(keys(&STAKEHOLDERS_XPUBS)).unwrap();
+        let unvault = UnvaultDescriptor::new(
+            keys(&STAKEHOLDERS_XPUBS),
+            keys(&MANAGERS_XPUBS),
+            2,
+            Vec::new(),
+            12,
+        )
+        .unwrap();
+        let parameters = UnvaultParameters::new(&deposit, &unvault).unwrap();
+        assert_eq!(parameters.managers_threshold, 2);
+        assert_eq!(parameters.spending_delay, 12);
+        assert_eq!(parameters.managers_keys, keys(&MANAGERS_XPUBS));
+        assert!(parameters.cosigners_keys.is_empty());
+    }
+}
@@ -0,0 +1,144 @@
+use std::io::{ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::path::Path;
+use std::time::Duration;
+
+use revaultd::revault_net::noise::{
+    KKHandshakeActOne, KKHandshakeActTwo, KKMessageActTwo, PublicKey as NoisePubkey,
+    SecretKey as NoisePrivkey, KK_MSG_2_SIZE,
+};
+
+/// Time given to the server to accept the connection and to answer the handshake.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Opens a noise connection with the server of the coordinator or of a cosigner,
+/// with our noise private key and the server noise public key.
+pub async fn check_connection(
+    host: SocketAddr,
+    our_key: NoisePrivkey,
+    their_key: NoisePubkey,
+) -> Result<(), Error> {
+    tokio::task::spawn_blocking(move || handshake(host, &our_key, &their_key))
+        .await
+        .map_err(|e| Error::Unexpected(e.to_string()))?
+}
+
+/// Runs the initiator side of the noise KK handshake on a single tcp connection.
+/// The connection errors are reported apart from the handshake errors in order to
+/// tell an unreachable host apart from a refused handshake.
+fn handshake(
+    host: SocketAddr,
+    our_key: &NoisePrivkey,
+    their_key: &NoisePubkey,
+) -> Result<(), Error> {
+    let mut stream = TcpStream::connect_timeout(&host, TIMEOUT)
+        .map_err(|e| Error::Unreachable(e.to_string()))?;
+    stream
+        .set_read_timeout(Some(TIMEOUT))
+        .and_then(|_| stream.set_write_timeout(Some(TIMEOUT)))
+        .map_err(|e| Error::Unexpected(e.to_string()))?;
+
+    let (act_one, msg_one) = KKHandshakeActOne::initiator(our_key, their_key)
+        .map_err(|e| Error::Handshake(e.to_string()))?;
+    stream.write_all(&msg_one.0).map_err(handshake_io_error)?;
+    let mut msg_two = [0u8; KK_MSG_2_SIZE];
+    stream
+        .read_exact(&mut msg_two)
+        .map_err(handshake_io_error)?;
+    KKHandshakeActTwo::initiator(act_one, &KKMessageActTwo(msg_two))
+        .map(|_| ())
+        .map_err(|e| Error::Handshake(e.to_string()))
+}
+
+fn handshake_io_error(e: std::io::Error) -> Error {
+    match e.kind() {
+        ErrorKind::WouldBlock | ErrorKind::TimedOut => Error::Handshake("timed out".to_string()),
+        _ => Error::Handshake(e.to_string()),
+    }
+}
+
+/// Reads the noise private key written by the installer in the network datadir.
+pub fn read_noise_secret(network_datadir: &Path) -> Result<NoisePrivkey, Error> {
+    let bytes = std::fs::read(network_datadir.join("noise_secret"))
+        .map_err(|e| Error::NoiseSecret(e.to_string()))?;
+    NoisePrivkey::from_slice(&bytes)
+        .ok_or_else(|| Error::NoiseSecret("key must be 32 bytes".to_string()))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    NoiseSecret(String),
+    Unreachable(String),
+    Handshake(String),
+    Unexpected(String),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::NoiseSecret(e) => write!(f, "Failed to read our noise private key: {}", e),
+            Self::Unreachable(e) => write!(f, "Server is unreachable: {}", e),
+            Self::Handshake(e) => write!(
+                f,
+                "Noise handshake failed, the server or our noise key may be wrong: {}",
+                e
+            ),
+            Self::Unexpected(e) => write!(f, "Unexpected error: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revaultd::revault_net::{sodiumoxide::crypto::box_, transport::KKTransport};
+    use std::net::TcpListener;
+
+    /// Accepts a single noise connection of the given client.
+    fn noise_responder(server_key: NoisePrivkey, client_key: NoisePubkey) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let _ = KKTransport::accept(&listener, &server_key, &[client_key]);
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn check_connection_ok() {
+        let (client_pubkey, client_privkey) = box_::gen_keypair();
+        let (server_pubkey, server_privkey) = box_::gen_keypair();
+        let addr = noise_responder(server_privkey, client_pubkey);
+        assert_eq!(
+            check_connection(addr, client_privkey, server_pubkey).await,
+            Ok(())
+        );
+    }
+
+    #[tokio::test]
+    async fn check_connection_wrong_key() {
+        let (client_pubkey, client_privkey) = box_::gen_keypair();
+        let (_, server_privkey) = box_::gen_keypair();
+        let (wrong_pubkey, _) = box_::gen_keypair();
+        let addr = noise_responder(server_privkey, client_pubkey);
+        assert!(matches!(
+            check_connection(addr, client_privkey, wrong_pubkey).await,
+            Err(Error::Handshake(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn check_connection_unreachable() {
+        let (_, client_privkey) = box_::gen_keypair();
+        let (server_pubkey, _) = box_::gen_keypair();
+        // The port is free again once the listener is dropped.
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        assert!(matches!(
+            check_connection(addr, client_privkey, server_pubkey).await,
+            Err(Error::Unreachable(_))
+        ));
+    }
+}